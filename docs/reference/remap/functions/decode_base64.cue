@@ -3,7 +3,8 @@ package metadata
 remap: functions: decode_base64: {
 	category:    "Codec"
 	description: """
-		Decodes the `value` (a [Base64](\(urls.base64)) string) into its original string.
+		Decodes the `value` (a [Base64](\(urls.base64)) string) into its original string. Line breaks in
+		the `value`, such as those added when wrapping Base64 in MIME messages, are ignored.
 		"""
 
 	arguments: [
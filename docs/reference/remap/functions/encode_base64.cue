@@ -31,8 +31,16 @@ remap: functions: encode_base64: {
 				url_safe: "Modified Base64 for [URL variants](\(urls.base64_url_safe))."
 			}
 		},
+		{
+			name:        "line_width"
+			description: "Wraps the Base64 output into lines of at most this many characters, separated by `\\r\\n`, as required by MIME."
+			required:    false
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`line_width` isn't a positive integer.",
 	]
-	internal_failure_reasons: []
 	return: types: ["string"]
 
 	examples: [
@@ -57,5 +65,12 @@ remap: functions: encode_base64: {
 				"""
 			return: "cGxlYXNlIGVuY29kZSBtZSwgYnV0IHNhZmUgZm9yIFVSTHM="
 		},
+		{
+			title: "Encode to Base64 (wrapped into lines)"
+			source: """
+				encode_base64!("please encode me", line_width: 8)
+				"""
+			return: "cGxlYXNl\r\nIGVuY29k\r\nZSBtZQ=="
+		},
 	]
 }
@@ -0,0 +1,45 @@
+package metadata
+
+remap: functions: mask_ip: {
+	category: "IP"
+	description: """
+		Anonymizes the `ip` by zeroing its host portion, keeping the first 24 bits of an IPv4 address and the
+		first 48 bits of an IPv6 address.
+		"""
+	notices: [
+		"""
+			This is equivalent to `ip_subnet` with a subnet of `/24` for IPv4 addresses and `/48` for IPv6
+			addresses.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "ip"
+			description: "The ip address - either a v4 or a v6 address."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`ip` is not a valid IP address",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Mask IPv4 address"
+			source: #"""
+				mask_ip("192.168.10.32")
+				"""#
+			return: "192.168.10.0"
+		},
+		{
+			title: "Mask IPv6 address"
+			source: #"""
+				mask_ip("2404:6800:4003:c02::64")
+				"""#
+			return: "2404:6800:4003::"
+		},
+	]
+}
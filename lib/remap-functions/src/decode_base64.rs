@@ -60,6 +60,13 @@ impl Expression for DecodeBase64Fn {
             Base64Charset::UrlSafe => base64::URL_SAFE,
         };
 
+        // Encoded values are often wrapped into lines, e.g. in MIME messages.
+        let value = value
+            .iter()
+            .filter(|&&b| b != b'\r' && b != b'\n')
+            .copied()
+            .collect::<Vec<_>>();
+
         match base64::decode_config(value, config) {
             Ok(s) => Ok(Value::from(s)),
             Err(_) => Err("unable to decode value to base64".into()),
@@ -142,5 +149,10 @@ mod test {
             args: func_args![value: value!(""), charset: value!("url_safe")],
             want: Ok(value!("")),
         }
+
+        with_line_breaks {
+            args: func_args![value: value!("c29tZSs9\r\nc3RyaW5n\r\nL3ZhbHVl")],
+            want: Ok(value!("some+=string/value")),
+        }
     ];
 }
@@ -27,6 +27,11 @@ impl Function for EncodeBase64 {
                 accepts: |v| matches!(v, Value::Bytes(_)),
                 required: false,
             },
+            Parameter {
+                keyword: "line_width",
+                accepts: |v| matches!(v, Value::Integer(_)),
+                required: false,
+            },
         ]
     }
 
@@ -34,11 +39,13 @@ impl Function for EncodeBase64 {
         let value = arguments.required("value")?.boxed();
         let padding = arguments.optional("padding").map(Expr::boxed);
         let charset = arguments.optional("charset").map(Expr::boxed);
+        let line_width = arguments.optional("line_width").map(Expr::boxed);
 
         Ok(Box::new(EncodeBase64Fn {
             value,
             padding,
             charset,
+            line_width,
         }))
     }
 }
@@ -48,6 +55,7 @@ struct EncodeBase64Fn {
     value: Box<dyn Expression>,
     padding: Option<Box<dyn Expression>>,
     charset: Option<Box<dyn Expression>>,
+    line_width: Option<Box<dyn Expression>>,
 }
 
 impl Expression for EncodeBase64Fn {
@@ -76,9 +84,29 @@ impl Expression for EncodeBase64Fn {
             .transpose()?
             .unwrap_or_default();
 
+        let line_width = self
+            .line_width
+            .as_ref()
+            .map(|w| {
+                w.execute(state, object)
+                    .and_then(|v| Value::try_integer(v).map_err(Into::into))
+            })
+            .transpose()?;
+
         let config = base64::Config::new(charset.into(), padding);
+        let encoded = base64::encode_config(value, config);
 
-        Ok(base64::encode_config(value, config).into())
+        match line_width {
+            None => Ok(encoded.into()),
+            Some(width) if width <= 0 => Err("line width must be positive".into()),
+            Some(width) => Ok(encoded
+                .as_bytes()
+                .chunks(width as usize)
+                .map(String::from_utf8_lossy)
+                .collect::<Vec<_>>()
+                .join("\r\n")
+                .into()),
+        }
     }
 
     fn type_def(&self, state: &state::Compiler) -> TypeDef {
@@ -94,11 +122,17 @@ impl Expression for EncodeBase64Fn {
             .as_ref()
             .map(|charset| charset.type_def(state).into_fallible(true));
 
+        let line_width_def = self
+            .line_width
+            .as_ref()
+            .map(|line_width| line_width.type_def(state).into_fallible(true));
+
         self.value
             .type_def(state)
             .fallible_unless(Kind::Bytes)
             .merge_optional(padding_def)
             .merge_optional(charset_def)
+            .merge_optional(line_width_def)
             .with_constraint(Kind::Bytes)
     }
 }
@@ -114,6 +148,7 @@ mod test {
                 value: lit!("foo").boxed(),
                 padding: None,
                 charset: None,
+                line_width: None,
             },
             def: TypeDef { kind: Kind::Bytes, ..Default::default() },
         }
@@ -123,6 +158,7 @@ mod test {
                 value: lit!("foo").boxed(),
                 padding: Some(lit!(false).boxed()),
                 charset: Some(lit!("standard").boxed()),
+                line_width: None,
             },
             def: TypeDef { fallible: true, kind: Kind::Bytes, ..Default::default() },
         }
@@ -132,6 +168,7 @@ mod test {
                 value: lit!("foo").boxed(),
                 padding: Some(lit!("foo").boxed()),
                 charset: None,
+                line_width: None,
             },
             def: TypeDef { fallible: true, kind: Kind::Bytes, ..Default::default() },
         }
@@ -141,6 +178,17 @@ mod test {
                 value: Literal::from(127).boxed(),
                 padding: None,
                 charset: None,
+                line_width: None,
+            },
+            def: TypeDef { fallible: true, kind: Kind::Bytes, ..Default::default() },
+        }
+
+        line_width_fallible {
+            expr: |_| EncodeBase64Fn {
+                value: lit!("foo").boxed(),
+                padding: None,
+                charset: None,
+                line_width: Some(lit!(76).boxed()),
             },
             def: TypeDef { fallible: true, kind: Kind::Bytes, ..Default::default() },
         }
@@ -150,6 +198,7 @@ mod test {
                 value: Literal::from(127).boxed(),
                 padding: Some(lit!("foo").boxed()),
                 charset: Some(lit!(127).boxed()),
+                line_width: None,
             },
             def: TypeDef { fallible: true, kind: Kind::Bytes, ..Default::default() },
         }
@@ -193,6 +242,21 @@ mod test {
             want: Ok(value!("")),
         }
 
+        with_line_width {
+            args: func_args![value: value!("some+=string/value"), line_width: value!(8)],
+            want: Ok(value!("c29tZSs9\r\nc3RyaW5n\r\nL3ZhbHVl")),
+        }
+
+        line_width_longer_than_value {
+            args: func_args![value: value!("some+=string/value"), line_width: value!(76)],
+            want: Ok(value!("c29tZSs9c3RyaW5nL3ZhbHVl")),
+        }
+
+        invalid_line_width_error {
+            args: func_args![value: value!("some string value"), line_width: value!(0)],
+            want: Err("function call error: line width must be positive"),
+        }
+
         invalid_charset_error {
             args: func_args![value: value!("some string value"), padding: value!(false), charset: value!("foo")],
             want: Err("function call error: unknown charset"),
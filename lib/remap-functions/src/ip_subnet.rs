@@ -112,7 +112,7 @@ fn parse_subnet(subnet: &str) -> Result<u32> {
 }
 
 /// Masks the address by performing a bitwise AND between the two addresses.
-fn mask_ips(ip: IpAddr, mask: IpAddr) -> Result<IpAddr> {
+pub(crate) fn mask_ips(ip: IpAddr, mask: IpAddr) -> Result<IpAddr> {
     match (ip, mask) {
         (IpAddr::V4(addr), IpAddr::V4(mask)) => {
             let addr: u32 = addr.into();
@@ -141,13 +141,13 @@ fn mask_ips(ip: IpAddr, mask: IpAddr) -> Result<IpAddr> {
 }
 
 /// Returns an ipv4 address that masks out the given number of bits.
-fn ipv4_mask(subnet_bits: u32) -> IpAddr {
+pub(crate) fn ipv4_mask(subnet_bits: u32) -> IpAddr {
     let bits = !0u32 << (32 - subnet_bits);
     Ipv4Addr::from(bits).into()
 }
 
 /// Returns an ipv6 address that masks out the given number of bits.
-fn ipv6_mask(subnet_bits: u32) -> IpAddr {
+pub(crate) fn ipv6_mask(subnet_bits: u32) -> IpAddr {
     let bits = !0u128 << (128 - subnet_bits);
     Ipv6Addr::from(bits).into()
 }
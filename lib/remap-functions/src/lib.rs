@@ -54,6 +54,8 @@ mod join;
 mod length;
 #[cfg(feature = "log")]
 mod log;
+#[cfg(feature = "mask_ip")]
+mod mask_ip;
 #[cfg(feature = "match")]
 mod r#match;
 #[cfg(feature = "md5")]
@@ -201,6 +203,8 @@ pub use join::Join;
 pub use length::Length;
 #[cfg(feature = "log")]
 pub use log::Log;
+#[cfg(feature = "mask_ip")]
+pub use mask_ip::MaskIp;
 #[cfg(feature = "merge")]
 pub use merge::Merge;
 #[cfg(feature = "now")]
@@ -344,6 +348,8 @@ pub fn all() -> Vec<Box<dyn remap::Function>> {
         Box::new(Length),
         #[cfg(feature = "log")]
         Box::new(Log),
+        #[cfg(feature = "mask_ip")]
+        Box::new(MaskIp),
         #[cfg(feature = "md5")]
         Box::new(Md5),
         #[cfg(feature = "merge")]
@@ -0,0 +1,122 @@
+use crate::ip_subnet::{ipv4_mask, ipv6_mask, mask_ips};
+use remap::prelude::*;
+use std::net::IpAddr;
+
+/// The prefix length kept for IPv4 addresses, zeroing the last octet.
+const IPV4_PREFIX: u32 = 24;
+
+/// The prefix length kept for IPv6 addresses, zeroing everything after the
+/// routing prefix most sites are assigned.
+const IPV6_PREFIX: u32 = 48;
+
+#[derive(Clone, Copy, Debug)]
+pub struct MaskIp;
+
+impl Function for MaskIp {
+    fn identifier(&self) -> &'static str {
+        "mask_ip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Bytes(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+
+        Ok(Box::new(MaskIpFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MaskIpFn {
+    value: Box<dyn Expression>,
+}
+
+impl MaskIpFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>) -> Self {
+        Self { value }
+    }
+}
+
+impl Expression for MaskIpFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let ip: IpAddr = self
+            .value
+            .execute(state, object)?
+            .try_bytes_utf8_lossy()?
+            .parse()
+            .map_err(|err| format!("unable to parse IP address: {}", err))?;
+
+        let mask = match ip {
+            IpAddr::V4(_) => ipv4_mask(IPV4_PREFIX),
+            IpAddr::V6(_) => ipv6_mask(IPV6_PREFIX),
+        };
+
+        Ok(mask_ips(ip, mask)?.to_string().into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value
+            .type_def(state)
+            .into_fallible(true)
+            .with_constraint(value::Kind::Bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    remap::test_type_def![value_string {
+        expr: |_| MaskIpFn {
+            value: Literal::from("192.168.0.1").boxed()
+        },
+        def: TypeDef {
+            kind: value::Kind::Bytes,
+            fallible: true,
+            ..Default::default()
+        },
+    }];
+
+    #[test]
+    fn mask_ip() {
+        let cases = vec![
+            (
+                btreemap! { "foo" => "192.168.10.23" },
+                Ok(Value::from("192.168.10.0")),
+                MaskIpFn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                btreemap! { "foo" => "2404:6800:4003:c02::64" },
+                Ok(Value::from("2404:6800:4003::")),
+                MaskIpFn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                btreemap! { "foo" => "192.168.10" },
+                Err(
+                    "function call error: unable to parse IP address: invalid IP address syntax"
+                        .to_string(),
+                ),
+                MaskIpFn::new(Box::new(Path::from("foo"))),
+            ),
+        ];
+
+        let mut state = state::Program::default();
+
+        for (object, exp, func) in cases {
+            let mut object = Value::Map(object);
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use chrono::{DateTime, Utc};
+use rand::{rngs::SmallRng, RngCore, SeedableRng};
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+
+/// Provides the values of functions that don't depend on the event, such as
+/// `now()`, so that a mapping can be made to produce the same output every
+/// time it is executed, e.g. in tests.
+///
+/// By default, the wall clock and a randomly seeded generator are used.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecutionContext {
+    now: Option<DateTime<Utc>>,
+    seed: Option<u64>,
+}
+
+impl ExecutionContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fixes the time returned by `now()`.
+    pub fn with_now(mut self, now: DateTime<Utc>) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// Seeds the generator used by functions such as `random_int()`. The
+    /// generator is seeded once, when the context is given to a mapping, and
+    /// carries on from one execution to the next, so a mapping run over the
+    /// same events in the same order draws the same numbers.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+/// The state of a context, shared by all the executions of a mapping.
+#[derive(Debug)]
+pub(in crate::mapping) struct State {
+    now: Option<DateTime<Utc>>,
+    rng: Option<Mutex<SmallRng>>,
+}
+
+impl State {
+    pub(in crate::mapping) fn new(context: &ExecutionContext) -> Self {
+        Self {
+            now: context.now,
+            rng: context
+                .seed
+                .map(|seed| Mutex::new(SmallRng::seed_from_u64(seed))),
+        }
+    }
+}
+
+/// The context of the execution in progress.
+#[derive(Default)]
+struct Current {
+    state: Option<Arc<State>>,
+    deadline: Option<Instant>,
+}
+
+thread_local! {
+    // Queries only have access to the event they are executed against, so
+    // the context of the execution in progress on this thread is kept here.
+    static CURRENT: RefCell<Current> = RefCell::new(Current::default());
+}
+
+/// Restores the previous context once an execution has finished, even if it
+/// panicked.
+struct Guard {
+    previous: Current,
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let previous = std::mem::take(&mut self.previous);
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Runs `f` with the context in place for the functions that read from it,
+/// and the deadline by which the execution must have finished, if any.
+pub(in crate::mapping) fn scoped<T>(
+    state: Option<&Arc<State>>,
+    deadline: Option<Instant>,
+    f: impl FnOnce() -> T,
+) -> T {
+    let next = Current {
+        state: state.cloned(),
+        deadline,
+    };
+    let _guard = Guard {
+        previous: CURRENT.with(|current| current.replace(next)),
+    };
+
+    f()
+}
+
+/// Runs `f` with a context of its own, rather than the one of a mapping.
+#[cfg(test)]
+pub(in crate::mapping) fn scoped_new<T>(context: &ExecutionContext, f: impl FnOnce() -> T) -> T {
+    scoped(Some(&Arc::new(State::new(context))), None, f)
+}
+
+/// The current time, according to the context of the execution in progress.
+pub(in crate::mapping) fn now() -> DateTime<Utc> {
+    CURRENT
+        .with(|current| current.borrow().state.as_ref().and_then(|state| state.now))
+        .unwrap_or_else(Utc::now)
+}
+
+/// Runs `f` with the random number generator of the execution in progress,
+/// or the thread's generator if it isn't seeded.
+///
+/// A seeded generator is shared by concurrent executions of the same mapping,
+/// so those only draw the same numbers if they run in the same order.
+pub(in crate::mapping) fn with_rng<T>(f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+    CURRENT.with(|current| {
+        let current = current.borrow();
+        match current.state.as_ref().and_then(|state| state.rng.as_ref()) {
+            Some(rng) => f(&mut *rng.lock().unwrap_or_else(PoisonError::into_inner)),
+            None => f(&mut rand::thread_rng()),
+        }
+    })
+}
+
+/// Whether the execution in progress has run past its deadline, for
+/// functions that can take long enough to check it as they go, such as
+/// `merge`.
+pub(in crate::mapping) fn timed_out() -> bool {
+    CURRENT.with(|current| match current.borrow().deadline {
+        Some(deadline) => Instant::now() >= deadline,
+        None => false,
+    })
+}
@@ -1,16 +1,97 @@
-use crate::event::{Event, Value};
-use std::collections::BTreeMap;
+use crate::{
+    event::{Event, Value},
+    types::Conversion,
+};
+use chrono::{TimeZone, Utc};
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::TryFrom;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
 
+mod context;
 pub mod parser;
 pub mod query;
 
-use query::query_value::QueryValue;
+pub use context::ExecutionContext;
+use query::{bound::Bound, query_value::QueryValue};
 
 pub type Result<T> = std::result::Result<T, String>;
 
-pub(self) trait Function: Send + core::fmt::Debug {
+pub(self) trait Function: Send + Sync + core::fmt::Debug {
     fn apply(&self, target: &mut Event) -> Result<()>;
+
+    /// Describe the effect of the statement in prose, for use in
+    /// [`Mapping::explain`].
+    fn describe(&self) -> String;
+
+    /// Applies the statement while recording which conditional branches are
+    /// taken. The `location` identifies the statement within the mapping.
+    ///
+    /// Only statements that contain branches need to override this.
+    fn apply_recording(
+        &self,
+        target: &mut Event,
+        _location: &str,
+        _coverage: &mut Coverage,
+    ) -> Result<()> {
+        self.apply(target)
+    }
+
+    /// Checks the statement for errors that can be detected before it is
+    /// applied to any event, see [`Mapping::validate`].
+    fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The number of times each branch of a conditional was taken.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BranchCoverage {
+    pub then_taken: usize,
+    pub else_taken: usize,
+}
+
+/// Records which branches of the conditionals of a mapping were taken.
+///
+/// Conditionals are identified by their location, which is the index of the
+/// top-level statement, followed by `.then` or `.else` for each nested
+/// conditional they are part of, e.g. `2.else`. Conditionals are only
+/// recorded once they have been evaluated at least once.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Coverage {
+    branches: BTreeMap<String, BranchCoverage>,
+}
+
+impl Coverage {
+    pub fn branches(&self) -> &BTreeMap<String, BranchCoverage> {
+        &self.branches
+    }
+
+    fn merge(&mut self, other: Coverage) {
+        for (location, other) in other.branches {
+            let branch = self.branches.entry(location).or_default();
+            branch.then_taken += other.then_taken;
+            branch.else_taken += other.else_taken;
+        }
+    }
+
+    fn record(&mut self, location: &str, taken: bool) {
+        let branch = self.branches.entry(location.to_owned()).or_default();
+        if taken {
+            branch.then_taken += 1;
+        } else {
+            branch.else_taken += 1;
+        }
+    }
+}
+
+/// Formats a list of target paths for use in a statement description.
+fn describe_paths(paths: &[String]) -> String {
+    paths
+        .iter()
+        .map(|path| format!(".{}", path))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 //------------------------------------------------------------------------------
@@ -31,12 +112,23 @@ impl Function for Assignment {
     fn apply(&self, target: &mut Event) -> Result<()> {
         match self.function.execute(&target)? {
             QueryValue::Value(v) => {
+                if let Some(path) = self.function.removed_path() {
+                    target.as_mut_log().remove(path);
+                }
                 target.as_mut_log().insert(&self.path, v);
                 Ok(())
             }
             _ => Err("assignment must be from a value".to_string()),
         }
     }
+
+    fn describe(&self) -> String {
+        format!("assign .{} = {}", self.path, self.function.describe())
+    }
+
+    fn validate(&self) -> Result<()> {
+        self.function.validate()
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -61,18 +153,60 @@ impl Function for Deletion {
         }
         Ok(())
     }
+
+    fn describe(&self) -> String {
+        format!("delete {}", describe_paths(&self.paths))
+    }
 }
 
 //------------------------------------------------------------------------------
 
+/// Removes all fields except the given paths and the fields nested under
+/// them. A path ending in `.*` keeps only the fields nested under it.
+///
+/// Maps that are left empty once their fields are removed are removed as
+/// well.
 #[derive(Debug)]
 pub(self) struct OnlyFields {
     paths: Vec<String>,
+    /// Paths that are kept along with all of their descendants.
+    kept: BTreeSet<String>,
+    /// Paths given with a `.*` suffix, only the descendants of which are kept.
+    parents: BTreeSet<String>,
 }
 
 impl OnlyFields {
     pub(self) fn new(paths: Vec<String>) -> Self {
-        Self { paths }
+        let mut kept = BTreeSet::new();
+        let mut parents = BTreeSet::new();
+
+        for path in &paths {
+            match path.strip_suffix(".*") {
+                Some(parent) => parents.insert(parent.to_owned()),
+                None => kept.insert(path.clone()),
+            };
+        }
+
+        Self {
+            paths,
+            kept,
+            parents,
+        }
+    }
+
+    /// Whether a field is kept, which is when it is one of the kept paths, or
+    /// nested under one of the paths, e.g. `foo.bar` and `foo[0]` are nested
+    /// under `foo`, but `foobar` isn't.
+    ///
+    /// Only the prefixes of the key that end where a segment does can be
+    /// paths it is nested under, so each of those is looked up in turn, rather
+    /// than comparing the key against every path.
+    fn keeps(&self, key: &str) -> bool {
+        self.kept.contains(key)
+            || key
+                .match_indices(|c| c == '.' || c == '[')
+                .map(|(i, _)| &key[..i])
+                .any(|prefix| self.kept.contains(prefix) || self.parents.contains(prefix))
     }
 }
 
@@ -80,15 +214,7 @@ impl Function for OnlyFields {
     fn apply(&self, target: &mut Event) -> Result<()> {
         let target_log = target.as_mut_log();
 
-        let keys: Vec<String> = target_log
-            .keys()
-            .filter(|k| {
-                self.paths
-                    .iter()
-                    .find(|p| k.starts_with(p.as_str()))
-                    .is_none()
-            })
-            .collect();
+        let keys: Vec<String> = target_log.keys().filter(|k| !self.keeps(k)).collect();
 
         for key in keys {
             target_log.remove_prune(key, true);
@@ -96,15 +222,23 @@ impl Function for OnlyFields {
 
         Ok(())
     }
+
+    fn describe(&self) -> String {
+        format!("only keep {}", describe_paths(&self.paths))
+    }
 }
 
 //------------------------------------------------------------------------------
 
+/// A chain of conditional statements, e.g. `if A {} else if B {} else {}`.
+///
+/// Conditions are evaluated in order, and the statement of the first one
+/// that returns `true` is applied. If none do, the `else` statement is
+/// applied, if there is one.
 #[derive(Debug)]
 pub(self) struct IfStatement {
-    query: Box<dyn query::Function>,
-    true_statement: Box<dyn Function>,
-    false_statement: Box<dyn Function>,
+    branches: Vec<(Box<dyn query::Function>, Box<dyn Function>)>,
+    else_statement: Option<Box<dyn Function>>,
 }
 
 impl IfStatement {
@@ -112,21 +246,102 @@ impl IfStatement {
         query: Box<dyn query::Function>,
         true_statement: Box<dyn Function>,
         false_statement: Box<dyn Function>,
+    ) -> Self {
+        Self::chain(vec![(query, true_statement)], Some(false_statement))
+    }
+
+    pub(self) fn chain(
+        branches: Vec<(Box<dyn query::Function>, Box<dyn Function>)>,
+        else_statement: Option<Box<dyn Function>>,
     ) -> Self {
         Self {
-            query,
-            true_statement,
-            false_statement,
+            branches,
+            else_statement,
         }
     }
 }
 
+fn condition(query: &dyn query::Function, target: &Event) -> Result<bool> {
+    match query.execute(target)? {
+        QueryValue::Value(Value::Boolean(b)) => Ok(b),
+        _ => Err("query returned non-boolean value".to_string()),
+    }
+}
+
 impl Function for IfStatement {
     fn apply(&self, target: &mut Event) -> Result<()> {
-        match self.query.execute(target)? {
-            QueryValue::Value(Value::Boolean(true)) => self.true_statement.apply(target),
-            QueryValue::Value(Value::Boolean(false)) => self.false_statement.apply(target),
-            _ => Err("query returned non-boolean value".to_string()),
+        for (query, statement) in &self.branches {
+            if condition(query.as_ref(), target)? {
+                return statement.apply(target);
+            }
+        }
+
+        match &self.else_statement {
+            Some(statement) => statement.apply(target),
+            None => Ok(()),
+        }
+    }
+
+    /// Each condition in the chain is recorded as if it were nested in the
+    /// `else` branch of the previous one, so `if A {} else if B {}` has the
+    /// same coverage locations as `if A {} else { if B {} }`.
+    fn apply_recording(
+        &self,
+        target: &mut Event,
+        location: &str,
+        coverage: &mut Coverage,
+    ) -> Result<()> {
+        let mut location = location.to_owned();
+
+        for (query, statement) in &self.branches {
+            let taken = condition(query.as_ref(), target)?;
+            coverage.record(&location, taken);
+
+            if taken {
+                let location = format!("{}.then", location);
+                return statement.apply_recording(target, &location, coverage);
+            }
+
+            location = format!("{}.else", location);
+        }
+
+        match &self.else_statement {
+            Some(statement) => statement.apply_recording(target, &location, coverage),
+            None => Ok(()),
+        }
+    }
+
+    fn describe(&self) -> String {
+        let mut description = self
+            .branches
+            .iter()
+            .map(|(query, statement)| {
+                format!("if {} then {}", query.describe(), statement.describe())
+            })
+            .collect::<Vec<_>>()
+            .join(" else ");
+
+        if let Some(statement) = &self.else_statement {
+            description.push_str(&format!(" else {}", statement.describe()));
+        }
+
+        description
+    }
+
+    fn validate(&self) -> Result<()> {
+        for (query, statement) in &self.branches {
+            query.validate()?;
+
+            if query.is_constant() && condition(query.as_ref(), &Event::from("")).is_err() {
+                return Err(format!("condition {} is not a boolean", query.describe()));
+            }
+
+            statement.validate()?;
+        }
+
+        match &self.else_statement {
+            Some(statement) => statement.validate(),
+            None => Ok(()),
         }
     }
 }
@@ -140,28 +355,553 @@ impl Function for Noop {
     fn apply(&self, _: &mut Event) -> Result<()> {
         Ok(())
     }
+
+    fn describe(&self) -> String {
+        "do nothing".to_owned()
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// A sequence of statements applied in order, used for branches of an
+/// [`IfStatement`] built with [`MappingBuilder`].
+///
+/// Conditionals within the block are recorded at the location of the block,
+/// followed by their index within it, e.g. `2.then.1`.
+#[derive(Debug)]
+pub(self) struct Block {
+    statements: Vec<Box<dyn Function>>,
+}
+
+impl Block {
+    pub(self) fn new(statements: Vec<Box<dyn Function>>) -> Self {
+        Self { statements }
+    }
+}
+
+impl Function for Block {
+    fn apply(&self, target: &mut Event) -> Result<()> {
+        for statement in &self.statements {
+            statement.apply(target)?;
+        }
+        Ok(())
+    }
+
+    fn apply_recording(
+        &self,
+        target: &mut Event,
+        location: &str,
+        coverage: &mut Coverage,
+    ) -> Result<()> {
+        for (i, statement) in self.statements.iter().enumerate() {
+            let location = format!("{}.{}", location, i);
+            statement.apply_recording(target, &location, coverage)?;
+        }
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        self.statements
+            .iter()
+            .map(|statement| statement.describe())
+            .collect::<Vec<_>>()
+            .join(", then ")
+    }
+
+    fn validate(&self) -> Result<()> {
+        self.statements
+            .iter()
+            .try_for_each(|statement| statement.validate())
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Statements can only fail with an error message, so aborting is signalled by
+/// this reserved message, which `Mapping::execute` turns into
+/// [`Outcome::Aborted`] rather than an error.
+const ABORTED: &str = "\u{0}mapping aborted";
+
+/// Reserved message returned by functions that stop partway through because
+/// the execution ran past its deadline, see [`Mapping::with_timeout`].
+const TIMED_OUT: &str = "\u{0}mapping timed out";
+
+/// Stops the mapping and marks the event to be discarded.
+#[derive(Debug)]
+pub(in crate::mapping) struct Abort {}
+
+impl Function for Abort {
+    fn apply(&self, _: &mut Event) -> Result<()> {
+        Err(ABORTED.to_owned())
+    }
+
+    fn describe(&self) -> String {
+        "abort and discard the event".to_owned()
+    }
 }
 
 //------------------------------------------------------------------------------
 
+/// The outcome of successfully executing a mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// All statements were applied.
+    Completed,
+
+    /// A statement aborted the mapping, and the event should be discarded.
+    /// Any statements before it have still been applied to the event.
+    Aborted,
+}
+
+/// Determines what happens when a statement of a mapping fails to apply.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OnError {
+    /// Abort the mapping and return the error. This is the default.
+    Fail,
+
+    /// Write the error message to the given path and carry on with the
+    /// remaining statements.
+    RecordAndContinue(String),
+
+    /// Write the error message to the given path and skip the remaining
+    /// statements.
+    RecordAndStop(String),
+}
+
+impl Default for OnError {
+    fn default() -> Self {
+        Self::Fail
+    }
+}
+
+/// The original source of a statement, along with the comments on the lines
+/// preceding it.
+#[derive(Debug, Clone, PartialEq)]
+pub(self) struct StatementSource {
+    comments: Vec<String>,
+    text: String,
+}
+
+impl StatementSource {
+    pub(self) fn new(comments: Vec<String>, text: String) -> Self {
+        Self { comments, text }
+    }
+}
+
+/// The source a mapping was parsed from, retained for tooling that needs to
+/// write the mapping back out.
+#[derive(Debug, Clone, PartialEq)]
+pub(self) struct MappingSource {
+    statements: Vec<StatementSource>,
+    trailing_comments: Vec<String>,
+}
+
+impl MappingSource {
+    pub(self) fn new(statements: Vec<StatementSource>, trailing_comments: Vec<String>) -> Self {
+        Self {
+            statements,
+            trailing_comments,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Mapping {
     assignments: Vec<Box<dyn Function>>,
+    timeout: Option<Duration>,
+    on_error: OnError,
+    source: Option<MappingSource>,
+    coverage: Option<Mutex<Coverage>>,
+    context: Option<Arc<context::State>>,
 }
 
 impl Mapping {
     pub(self) fn new(assignments: Vec<Box<dyn Function>>) -> Self {
-        Mapping { assignments }
+        Mapping {
+            assignments,
+            timeout: None,
+            on_error: OnError::default(),
+            source: None,
+            coverage: None,
+            context: None,
+        }
+    }
+
+    pub(self) fn with_source(mut self, source: MappingSource) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Writes the mapping back out as source, one statement per line, with
+    /// each statement preceded by its comments.
+    ///
+    /// This is only available for mappings parsed with
+    /// [`parser::ParseOptions::preserve_comments`] set. Statements are written
+    /// as they were originally formatted, though a comment that shares a line
+    /// with a statement may be moved to its own line ahead of the next one.
+    pub fn to_source(&self) -> Option<String> {
+        let source = self.source.as_ref()?;
+
+        let lines = source
+            .statements
+            .iter()
+            .flat_map(|statement| {
+                statement
+                    .comments
+                    .iter()
+                    .chain(std::iter::once(&statement.text))
+            })
+            .chain(source.trailing_comments.iter())
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+
+        Some(lines.join("\n"))
+    }
+
+    /// Limits the wall-clock time a single call to `execute` may take.
+    ///
+    /// The limit is checked after each statement, and no further statements
+    /// are applied once it is exceeded. Merging maps, which can take long
+    /// enough on its own, also checks the limit as it goes and stops partway
+    /// through, leaving the target map partially merged.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets how statement failures are handled, see [`OnError`].
+    ///
+    /// When a failure is recorded onto the event, `execute` returns `Ok`
+    /// so that the event can still be routed, e.g. to a dead-letter sink.
+    pub fn with_on_error(mut self, on_error: OnError) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    /// Records which branches of the conditionals of the mapping are taken
+    /// across all calls to `execute`, see [`Mapping::coverage`].
+    pub fn with_coverage(mut self) -> Self {
+        self.coverage = Some(Mutex::new(Coverage::default()));
+        self
+    }
+
+    /// Sets the clock and random number generator seen by functions such as
+    /// `now()` during each call to `execute`, see [`ExecutionContext`].
+    pub fn with_context(mut self, context: ExecutionContext) -> Self {
+        self.context = Some(Arc::new(context::State::new(&context)));
+        self
+    }
+
+    /// Returns a snapshot of the branches taken so far, if coverage is being
+    /// recorded.
+    pub fn coverage(&self) -> Option<Coverage> {
+        self.coverage.as_ref().map(|coverage| {
+            coverage
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone()
+        })
+    }
+
+    pub fn execute(&self, event: &mut Event) -> Result<Outcome> {
+        self.execute_tracking(event, None)
+    }
+
+    /// Executes the mapping, and also reports for each statement whether it
+    /// changed the event, e.g. a deletion of a field that isn't present
+    /// doesn't.
+    ///
+    /// Changes are detected by comparing the event before and after each
+    /// statement, so this is considerably slower than `execute`. Statements
+    /// that weren't applied, because the mapping aborted or stopped early,
+    /// are reported as unchanged.
+    pub fn execute_with_changes(&self, event: &mut Event) -> Result<(Outcome, Vec<bool>)> {
+        let mut changes = Vec::with_capacity(self.assignments.len());
+        let outcome = self.execute_tracking(event, Some(&mut changes))?;
+        changes.resize(self.assignments.len(), false);
+
+        Ok((outcome, changes))
+    }
+
+    /// Executes the mapping against a copy of the event, which only replaces
+    /// the event once the mapping succeeds. If it fails, the event is left
+    /// exactly as it was rather than partially mapped.
+    ///
+    /// This costs a clone of the event for every call.
+    pub fn execute_transactional(&self, event: &mut Event) -> Result<Outcome> {
+        let mut staged = event.clone();
+        let outcome = self.execute(&mut staged)?;
+        *event = staged;
+
+        Ok(outcome)
     }
 
-    pub fn execute(&self, event: &mut Event) -> Result<()> {
+    /// Executes every statement of the mapping, even after one of them fails,
+    /// and returns the errors of all the statements that failed.
+    ///
+    /// This is meant for authoring a mapping, where seeing every error at once
+    /// saves fixing them one run at a time. Statements that succeed are still
+    /// applied to the event. Errors are always collected, regardless of
+    /// `with_on_error`, and an aborting statement stops the mapping.
+    pub fn execute_all(&self, event: &mut Event) -> std::result::Result<(), Vec<String>> {
+        self.in_context(None, || self.apply_all(event))
+    }
+
+    fn apply_all(&self, event: &mut Event) -> std::result::Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        for (i, assignment) in self.assignments.iter().enumerate() {
+            match assignment.apply(event) {
+                Ok(()) => (),
+                Err(err) if err == ABORTED => break,
+                Err(err) => errors.push(format!("failed to apply mapping {}: {}", i, err)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn in_context<T>(&self, deadline: Option<Instant>, f: impl FnOnce() -> T) -> T {
+        match (&self.context, deadline) {
+            (None, None) => f(),
+            (state, deadline) => context::scoped(state.as_ref(), deadline, f),
+        }
+    }
+
+    fn execute_tracking(
+        &self,
+        event: &mut Event,
+        changes: Option<&mut Vec<bool>>,
+    ) -> Result<Outcome> {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        self.in_context(deadline, || self.execute_covered(event, changes))
+    }
+
+    fn execute_covered(
+        &self,
+        event: &mut Event,
+        changes: Option<&mut Vec<bool>>,
+    ) -> Result<Outcome> {
+        match &self.coverage {
+            None => self.execute_statements(event, None, changes),
+            Some(shared) => {
+                // Coverage is recorded locally first, so that concurrent
+                // executions only contend for the lock once each.
+                let mut coverage = Coverage::default();
+                let result = self.execute_statements(event, Some(&mut coverage), changes);
+                shared
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .merge(coverage);
+                result
+            }
+        }
+    }
+
+    fn execute_statements(
+        &self,
+        event: &mut Event,
+        mut coverage: Option<&mut Coverage>,
+        mut changes: Option<&mut Vec<bool>>,
+    ) -> Result<Outcome> {
+        let timed_out = |i| {
+            format!(
+                "mapping timed out after {:?} at mapping {}",
+                self.timeout.unwrap_or_default(),
+                i
+            )
+        };
+
         for (i, assignment) in self.assignments.iter().enumerate() {
-            if let Err(err) = assignment.apply(event) {
-                return Err(format!("failed to apply mapping {}: {}", i, err));
+            let before = changes.as_ref().map(|_| event.as_log().clone());
+
+            let result = match coverage.as_mut() {
+                Some(coverage) => assignment.apply_recording(event, &i.to_string(), coverage),
+                None => assignment.apply(event),
+            };
+
+            if let (Some(changes), Some(before)) = (changes.as_mut(), before) {
+                changes.push(before != *event.as_log());
+            }
+
+            if let Err(err) = result {
+                if err == ABORTED {
+                    return Ok(Outcome::Aborted);
+                }
+                if err == TIMED_OUT {
+                    return Err(timed_out(i));
+                }
+
+                let err = format!("failed to apply mapping {}: {}", i, err);
+
+                match &self.on_error {
+                    OnError::Fail => return Err(err),
+                    OnError::RecordAndContinue(path) => {
+                        event.as_mut_log().insert(path, Value::from(err));
+                    }
+                    OnError::RecordAndStop(path) => {
+                        event.as_mut_log().insert(path, Value::from(err));
+                        return Ok(Outcome::Completed);
+                    }
+                }
+            }
+
+            if context::timed_out() {
+                return Err(timed_out(i));
             }
         }
+        Ok(Outcome::Completed)
+    }
+
+    /// Lazily applies the mapping to each event yielded by `iter`.
+    ///
+    /// Events are mapped one at a time as the returned iterator is consumed,
+    /// so a batch never needs to be buffered in full. A failure to map one
+    /// event is yielded in its place and does not affect the events after it.
+    /// Events for which the mapping aborted are left out.
+    pub fn execute_iter<'a, I>(&'a self, iter: I) -> impl Iterator<Item = Result<Event>> + 'a
+    where
+        I: Iterator<Item = Event> + 'a,
+    {
+        iter.filter_map(move |mut event| match self.execute(&mut event) {
+            Ok(Outcome::Completed) => Some(Ok(event)),
+            Ok(Outcome::Aborted) => None,
+            Err(err) => Some(Err(err)),
+        })
+    }
+
+    /// Checks the mapping for type errors that are certain to occur whenever
+    /// it is executed, such as `1 + "x"` or a condition that is a literal
+    /// string.
+    ///
+    /// Only queries made up entirely of literals are checked, as the types of
+    /// event fields aren't known until the mapping is executed. The parser
+    /// runs this when [`parser::ParseOptions::validate`] is set, so that
+    /// these errors are reported at parse time.
+    pub fn validate(&self) -> Result<()> {
+        for (i, assignment) in self.assignments.iter().enumerate() {
+            assignment
+                .validate()
+                .map_err(|err| format!("invalid mapping {}: {}", i, err))?;
+        }
+
         Ok(())
     }
+
+    /// Returns a human-readable description of each statement of the
+    /// mapping, in the order they are executed.
+    pub fn explain(&self) -> Vec<String> {
+        self.assignments
+            .iter()
+            .map(|assignment| assignment.describe())
+            .collect()
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// A query used by the statements of a [`MappingBuilder`].
+#[derive(Debug)]
+pub struct Query(Box<dyn query::Function>);
+
+impl Query {
+    /// Resolves to the given value.
+    pub fn literal(value: impl Into<Value>) -> Self {
+        Self(Box::new(query::Literal::from(value.into())))
+    }
+
+    /// Resolves to the value at a path of the event, e.g. `foo.bar[0]`. The
+    /// query fails if the path doesn't exist.
+    pub fn path(path: &str) -> Self {
+        let segments: Vec<Vec<&str>> = path.split('.').map(|segment| vec![segment]).collect();
+        Self(Box::new(query::path::Path::from(segments)))
+    }
+
+    /// Resolves to whether this query and `other` resolve to equal values.
+    pub fn equals(self, other: Query) -> Self {
+        Self(Box::new(query::arithmetic::Arithmetic::new(
+            self.0,
+            other.0,
+            query::arithmetic::Operator::Equal,
+        )))
+    }
+}
+
+/// Builds a [`Mapping`] statement by statement, as an alternative to parsing
+/// one from source.
+#[derive(Debug, Default)]
+pub struct MappingBuilder {
+    statements: Vec<Box<dyn Function>>,
+}
+
+impl MappingBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assigns the result of a query to a path, like `.path = query`.
+    pub fn assign(mut self, path: impl Into<String>, query: Query) -> Self {
+        self.statements
+            .push(Box::new(Assignment::new(path.into(), query.0)));
+        self
+    }
+
+    /// Deletes the given paths, like `del(.a, .b)`.
+    pub fn delete(mut self, paths: Vec<String>) -> Self {
+        self.statements.push(Box::new(Deletion::new(paths)));
+        self
+    }
+
+    /// Deletes all fields except the given paths, like `only_fields(.a, .b)`.
+    pub fn only_fields(mut self, paths: Vec<String>) -> Self {
+        self.statements.push(Box::new(OnlyFields::new(paths)));
+        self
+    }
+
+    /// Merges the map a query resolves to into the map at a path, like
+    /// `merge(.path, query, deep)`.
+    pub fn merge(mut self, path: impl Into<String>, query: Query, deep: bool) -> Self {
+        let deep = Box::new(query::Literal::from(Value::Boolean(deep)));
+        self.statements.push(Box::new(MergeFn::new(
+            path.into(),
+            query.0,
+            Some(deep),
+            None,
+        )));
+        self
+    }
+
+    /// Applies the statements of `then` if the condition resolves to `true`,
+    /// and those of `otherwise` if it resolves to `false`.
+    pub fn if_stmt(
+        mut self,
+        condition: Query,
+        then: MappingBuilder,
+        otherwise: MappingBuilder,
+    ) -> Self {
+        self.statements.push(Box::new(IfStatement::new(
+            condition.0,
+            then.into_statement(),
+            otherwise.into_statement(),
+        )));
+        self
+    }
+
+    pub fn build(self) -> Mapping {
+        Mapping::new(self.statements)
+    }
+
+    fn into_statement(mut self) -> Box<dyn Function> {
+        match self.statements.len() {
+            0 => Box::new(Noop {}),
+            1 => self.statements.remove(0),
+            _ => Box::new(Block::new(self.statements)),
+        }
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -169,33 +909,93 @@ impl Mapping {
 /// Merges two BTreeMaps of `Value`s.
 /// The second map is merged into the first one.
 ///
-/// If `deep` is true, only the top level values are merged in. If both maps contain a field
+/// If `deep` is false, only the top level values are merged in. If both maps contain a field
 /// with the same name, the field from the first is overwritten with the field from the second.
 ///
-/// If `deep` is false, should both maps contain a field with the same name, and both those
-/// fields are also maps, the function will recurse and will merge the child fields from the second
-/// into the child fields from the first.
+/// If `deep` is true, should both maps contain a field with the same name, and both those
+/// fields are also maps, the child fields from the second are merged into the child fields from
+/// the first.
+///
+/// Child maps are merged using an explicit stack rather than by recursing, so that arbitrarily
+/// deeply nested maps can't overflow the stack.
+///
+/// If `union_arrays` is true, should both maps contain a field with the same name, and both those
+/// fields are arrays, the arrays are concatenated with any duplicate elements removed, instead of
+/// the array from the second map overwriting the first.
 ///
-/// Note, this does recurse, so there is the theoretical possibility that it could blow up the
-/// stack. From quick tests on a sample project I was able to merge maps with a depth of 3,500
-/// before encountering issues. So I think that is likely to be within acceptable limits.
-/// If it becomes a problem, we can unroll this function, but that will come at a cost of extra
-/// code complexity.
-fn merge_maps<K>(map1: &mut BTreeMap<K, Value>, map2: &BTreeMap<K, Value>, deep: bool)
+/// The deadline of the execution in progress is checked before each child map is merged, and the
+/// merge stops partway through once it has passed.
+fn merge_maps<K>(
+    map1: &mut BTreeMap<K, Value>,
+    map2: &BTreeMap<K, Value>,
+    deep: bool,
+    union_arrays: bool,
+) -> Result<()>
+where
+    K: std::cmp::Ord + Clone,
+{
+    let mut stack = merge_fields(map1, map2, deep, union_arrays);
+
+    while let Some((child1, child2)) = stack.pop() {
+        if context::timed_out() {
+            return Err(TIMED_OUT.to_owned());
+        }
+        stack.extend(merge_fields(child1, child2, deep, union_arrays));
+    }
+
+    Ok(())
+}
+
+type ChildMaps<'a> = Vec<(&'a mut BTreeMap<String, Value>, &'a BTreeMap<String, Value>)>;
+
+/// Merges the fields of the second map into the first one, without descending
+/// into child maps. Instead, the pairs of child maps that still need to be
+/// merged are returned.
+fn merge_fields<'a, K>(
+    map1: &'a mut BTreeMap<K, Value>,
+    map2: &'a BTreeMap<K, Value>,
+    deep: bool,
+    union_arrays: bool,
+) -> ChildMaps<'a>
 where
     K: std::cmp::Ord + Clone,
 {
+    // Child maps can't be borrowed while other fields of `map1` are being
+    // modified, so only their keys are collected in this first pass.
+    let mut children = BTreeSet::new();
+
     for (key2, value2) in map2.iter() {
         match (deep, map1.get_mut(key2), value2) {
-            (true, Some(Value::Map(ref mut child1)), Value::Map(ref child2)) => {
+            (true, Some(Value::Map(_)), Value::Map(_)) => {
                 // We are doing a deep merge and both fields are maps.
-                merge_maps(child1, child2, deep);
+                children.insert(key2);
+            }
+            (_, Some(Value::Array(ref mut array1)), Value::Array(ref array2)) if union_arrays => {
+                let mut union = Vec::with_capacity(array1.len() + array2.len());
+                for value in array1.drain(..).chain(array2.iter().cloned()) {
+                    if !union.contains(&value) {
+                        union.push(value);
+                    }
+                }
+                *array1 = union;
             }
             _ => {
                 map1.insert(key2.clone(), value2.clone());
             }
         }
     }
+
+    if children.is_empty() {
+        return Vec::new();
+    }
+
+    map1.iter_mut()
+        .filter(|(key1, _)| children.contains(key1))
+        .filter_map(|(key1, value1)| match (value1, map2.get(key1)) {
+            (Value::Map(child1), Some(Value::Map(child2))) => Some((child1, child2)),
+            _ => None,
+        })
+        .collect()
 }
 
 #[derive(Debug)]
@@ -203,6 +1003,7 @@ pub(in crate::mapping) struct MergeFn {
     to_path: String,
     from: Box<dyn query::Function>,
     deep: Option<Box<dyn query::Function>>,
+    union_arrays: Option<Box<dyn query::Function>>,
 }
 
 impl MergeFn {
@@ -210,11 +1011,13 @@ impl MergeFn {
         to_path: String,
         from: Box<dyn query::Function>,
         deep: Option<Box<dyn query::Function>>,
+        union_arrays: Option<Box<dyn query::Function>>,
     ) -> Self {
         MergeFn {
             to_path,
             from,
             deep,
+            union_arrays,
         }
     }
 }
@@ -229,6 +1032,17 @@ impl Function for MergeFn {
                 _ => return Err("deep parameter passed to merge is a non-boolean value".into()),
             },
         };
+        let union_arrays = match &self.union_arrays {
+            None => false,
+            Some(union_arrays) => match union_arrays.execute(target)? {
+                QueryValue::Value(Value::Boolean(value)) => value,
+                _ => {
+                    return Err(
+                        "union_arrays parameter passed to merge is a non-boolean value".into(),
+                    )
+                }
+            },
+        };
 
         let to_value = target.as_mut_log().get_mut(&self.to_path).ok_or(format!(
             "parameter {} passed to merge is not found",
@@ -237,13 +1051,445 @@ impl Function for MergeFn {
 
         match (to_value, from_value) {
             (Value::Map(ref mut map1), QueryValue::Value(Value::Map(ref map2))) => {
-                merge_maps(map1, &map2, deep);
-                Ok(())
+                merge_maps(map1, &map2, deep, union_arrays)
             }
 
             _ => Err("parameters passed to merge are non-map values".into()),
         }
     }
+
+    fn describe(&self) -> String {
+        let mut description = format!("merge {} into .{}", self.from.describe(), self.to_path);
+        if let Some(deep) = &self.deep {
+            description.push_str(&format!(" (deep: {})", deep.describe()));
+        }
+        if let Some(union_arrays) = &self.union_arrays {
+            description.push_str(&format!(" (union arrays: {})", union_arrays.describe()));
+        }
+        description
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Adds a delta to the integer at a path, in place.
+///
+/// A missing field is treated as zero, so counters don't need to be
+/// initialized before they are first incremented.
+#[derive(Debug)]
+pub(in crate::mapping) struct IncrementFn {
+    path: String,
+    delta: Box<dyn query::Function>,
+}
+
+impl IncrementFn {
+    pub(in crate::mapping) fn new(path: String, delta: Box<dyn query::Function>) -> Self {
+        Self { path, delta }
+    }
+}
+
+impl Function for IncrementFn {
+    fn apply(&self, target: &mut Event) -> Result<()> {
+        let delta = match self.delta.execute(target)? {
+            QueryValue::Value(Value::Integer(delta)) => delta,
+            _ => return Err("delta passed to increment is a non-integer value".into()),
+        };
+
+        match target.as_mut_log().get_mut(&self.path) {
+            Some(Value::Integer(value)) => {
+                *value = value.checked_add(delta).ok_or(format!(
+                    "parameter {} passed to increment overflowed",
+                    self.path
+                ))?;
+            }
+            Some(_) => {
+                return Err(format!(
+                    "parameter {} passed to increment is a non-integer value",
+                    self.path
+                ))
+            }
+            None => {
+                target
+                    .as_mut_log()
+                    .insert(&self.path, Value::Integer(delta));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!("increment .{} by {}", self.path, self.delta.describe())
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Replaces the value at each of the given paths with a mask, keeping the
+/// field itself present. Paths that don't exist in the event are ignored.
+#[derive(Debug)]
+pub(in crate::mapping) struct RedactPathsFn {
+    paths: Vec<String>,
+    mask: Box<dyn query::Function>,
+}
+
+impl RedactPathsFn {
+    pub(in crate::mapping) fn new(paths: Vec<String>, mask: Box<dyn query::Function>) -> Self {
+        Self { paths, mask }
+    }
+}
+
+impl Function for RedactPathsFn {
+    fn apply(&self, target: &mut Event) -> Result<()> {
+        let mask = match self.mask.execute(target)? {
+            QueryValue::Value(mask) => mask,
+            _ => return Err("mask passed to redact_paths is a non-value".into()),
+        };
+
+        for path in &self.paths {
+            if let Some(value) = target.as_mut_log().get_mut(path) {
+                *value = mask.clone();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "redact {} with {}",
+            describe_paths(&self.paths),
+            self.mask.describe()
+        )
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Converts a camelCase or PascalCase key to snake_case. Runs of capitals are
+/// treated as a single word, so `HTTPServer` becomes `http_server`.
+fn to_snake_case(key: &str) -> String {
+    let chars = key.chars().collect::<Vec<_>>();
+    let mut snake = String::with_capacity(key.len());
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let prev = if i > 0 { Some(chars[i - 1]) } else { None };
+            let next = chars.get(i + 1);
+
+            let starts_word = match prev {
+                None | Some('_') => false,
+                Some(prev) if prev.is_uppercase() => next.map_or(false, |c| c.is_lowercase()),
+                Some(_) => true,
+            };
+
+            if starts_word {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+
+    snake
+}
+
+/// Recursively converts the keys of all maps within the value to snake_case.
+fn snake_case_keys(value: Value) -> Result<Value> {
+    match value {
+        Value::Map(map) => {
+            let mut converted = BTreeMap::new();
+            for (key, value) in map {
+                let key = to_snake_case(&key);
+                if converted.contains_key(&key) {
+                    return Err(format!("converted key {} already exists", key));
+                }
+                converted.insert(key, snake_case_keys(value)?);
+            }
+            Ok(Value::Map(converted))
+        }
+        Value::Array(array) => array
+            .into_iter()
+            .map(snake_case_keys)
+            .collect::<Result<Vec<_>>>()
+            .map(Value::Array),
+        value => Ok(value),
+    }
+}
+
+/// Rewrites every key of the map at a path, including those of nested maps,
+/// from camelCase or PascalCase to snake_case.
+///
+/// If two keys of the same map convert to the same key, the statement fails
+/// and the map is left untouched.
+#[derive(Debug)]
+pub(in crate::mapping) struct SnakeCaseKeysFn {
+    path: String,
+}
+
+impl SnakeCaseKeysFn {
+    pub(in crate::mapping) fn new(path: String) -> Self {
+        Self { path }
+    }
+}
+
+impl Function for SnakeCaseKeysFn {
+    fn apply(&self, target: &mut Event) -> Result<()> {
+        let value = target.as_mut_log().get_mut(&self.path).ok_or(format!(
+            "parameter {} passed to snake_case_keys is not found",
+            self.path
+        ))?;
+
+        if !matches!(value, Value::Map(_)) {
+            return Err(format!(
+                "parameter {} passed to snake_case_keys is a non-map value",
+                self.path
+            ));
+        }
+
+        *value = snake_case_keys(value.clone())?;
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!("convert the keys of .{} to snake_case", self.path)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Removes every null value within the event, or within the value at a path,
+/// including nulls nested in maps and elements of arrays.
+///
+/// Unlike `compact`, empty strings, zeros and containers left empty by the
+/// removal are kept.
+#[derive(Debug)]
+pub(in crate::mapping) struct RemoveNullsFn {
+    path: Option<String>,
+}
+
+impl RemoveNullsFn {
+    /// Removes nulls within the value at a path, or within the whole event if
+    /// there is no path.
+    pub(in crate::mapping) fn new(path: Option<String>) -> Self {
+        Self { path }
+    }
+}
+
+/// Escapes the characters of a top-level key that would otherwise be read as
+/// part of a path, so that the key can be used as a path of its own.
+fn escape_key(key: &str) -> String {
+    let mut escaped = String::with_capacity(key.len());
+    for c in key.chars() {
+        if matches!(c, '.' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn remove_nulls(value: Value) -> Value {
+    match value {
+        Value::Map(map) => Value::Map(
+            map.into_iter()
+                .filter(|(_, value)| !matches!(value, Value::Null))
+                .map(|(key, value)| (key, remove_nulls(value)))
+                .collect(),
+        ),
+        Value::Array(array) => Value::Array(
+            array
+                .into_iter()
+                .filter(|value| !matches!(value, Value::Null))
+                .map(remove_nulls)
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+impl Function for RemoveNullsFn {
+    fn apply(&self, target: &mut Event) -> Result<()> {
+        let log = target.as_mut_log();
+
+        let paths = match &self.path {
+            Some(path) => vec![path.clone()],
+            None => log.as_map().keys().map(|key| escape_key(key)).collect(),
+        };
+
+        for path in paths {
+            match log.get_mut(&path) {
+                Some(Value::Null) => {
+                    log.remove(&path);
+                }
+                Some(value) => *value = remove_nulls(std::mem::replace(value, Value::Null)),
+                None => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        match &self.path {
+            Some(path) => format!("remove null values within .{}", path),
+            None => "remove null values within the event".to_owned(),
+        }
+    }
+}
+
+/// Collapses the nested maps and arrays of the map at a path into a single
+/// level, e.g. `{"a": {"b": [1]}}` becomes `{"a.b[0]": 1}`.
+///
+/// Map keys are joined by the separator, and array elements are keyed by
+/// their index in brackets. Empty maps and arrays are kept as they are, so
+/// that their fields aren't lost.
+#[derive(Debug)]
+pub(in crate::mapping) struct Flatten {
+    path: String,
+    separator: String,
+}
+
+impl Flatten {
+    pub(in crate::mapping) fn new(path: String, separator: String) -> Self {
+        Self { path, separator }
+    }
+}
+
+fn flatten_into(
+    key: String,
+    value: Value,
+    separator: &str,
+    flattened: &mut BTreeMap<String, Value>,
+) {
+    match value {
+        Value::Map(map) if !map.is_empty() => {
+            for (k, v) in map {
+                flatten_into(
+                    format!("{}{}{}", key, separator, k),
+                    v,
+                    separator,
+                    flattened,
+                );
+            }
+        }
+        Value::Array(array) if !array.is_empty() => {
+            for (i, v) in array.into_iter().enumerate() {
+                flatten_into(format!("{}[{}]", key, i), v, separator, flattened);
+            }
+        }
+        value => {
+            flattened.insert(key, value);
+        }
+    }
+}
+
+impl Function for Flatten {
+    fn apply(&self, target: &mut Event) -> Result<()> {
+        let value = target.as_mut_log().get_mut(&self.path).ok_or(format!(
+            "parameter {} passed to flatten is not found",
+            self.path
+        ))?;
+
+        let map = match value {
+            Value::Map(map) => std::mem::take(map),
+            value => {
+                return Err(format!(
+                    "parameter {} passed to flatten is a non-map value of type {}",
+                    self.path,
+                    value.kind()
+                ))
+            }
+        };
+
+        let mut flattened = BTreeMap::new();
+        for (key, value) in map {
+            flatten_into(key, value, &self.separator, &mut flattened);
+        }
+
+        *value = Value::Map(flattened);
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "flatten .{} into keys joined by {:?}",
+            self.path, self.separator
+        )
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Applies a query function to the field at a path and writes the result
+/// back, but only if the field exists. The function is expected to read the
+/// field itself, which the parser takes care of.
+#[derive(Debug)]
+pub(in crate::mapping) struct ApplyIfPresentFn {
+    path: String,
+    function: Box<dyn query::Function>,
+}
+
+impl ApplyIfPresentFn {
+    pub(in crate::mapping) fn new(path: String, function: Box<dyn query::Function>) -> Self {
+        Self { path, function }
+    }
+}
+
+impl Function for ApplyIfPresentFn {
+    fn apply(&self, target: &mut Event) -> Result<()> {
+        if target.as_log().get(&self.path).is_none() {
+            return Ok(());
+        }
+
+        match self.function.execute(target)? {
+            QueryValue::Value(v) => {
+                target.as_mut_log().insert(&self.path, v);
+                Ok(())
+            }
+            _ => Err("function passed to apply_if_present must return a value".to_string()),
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "if .{} is present, assign .{} = {}",
+            self.path,
+            self.path,
+            self.function.describe()
+        )
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Moves the value of a field to another path. Renaming a field that doesn't
+/// exist does nothing, and an existing field at the new path is overwritten.
+#[derive(Debug)]
+pub(in crate::mapping) struct Rename {
+    from: String,
+    to: String,
+}
+
+impl Rename {
+    pub(in crate::mapping) fn new(from: String, to: String) -> Self {
+        Self { from, to }
+    }
+}
+
+impl Function for Rename {
+    fn apply(&self, target: &mut Event) -> Result<()> {
+        let log = target.as_mut_log();
+        if let Some(value) = log.remove(&self.from) {
+            log.insert(&self.to, value);
+        }
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!("rename .{} to .{}", self.from, self.to)
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -273,6 +1519,16 @@ impl TryFrom<&str> for LogLevel {
     }
 }
 
+/// Renders a value for logging. Strings are logged as they are, any other
+/// value is rendered as compact JSON.
+fn render_log_message(value: Value) -> String {
+    match value {
+        Value::Bytes(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        value => serde_json::to_string(&value)
+            .unwrap_or_else(|_| String::from_utf8_lossy(&value.into_bytes()).into_owned()),
+    }
+}
+
 #[derive(Debug)]
 pub(in crate::mapping) struct LogFn {
     msg: Box<dyn query::Function>,
@@ -291,8 +1547,7 @@ impl Function for LogFn {
             QueryValue::Value(value) => value,
             _ => return Err("Can only log Value parameters".to_string()),
         };
-        let msg = msg.into_bytes();
-        let string = String::from_utf8_lossy(&msg);
+        let string = render_log_message(msg);
         let level = self.level.unwrap_or(LogLevel::Info);
 
         match level {
@@ -305,4 +1560,1725 @@ impl Function for LogFn {
 
         Ok(())
     }
+
+    fn describe(&self) -> String {
+        let level = match self.level.unwrap_or(LogLevel::Info) {
+            LogLevel::Trace => "trace",
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        };
+        format!("log {} at {} level", self.msg.describe(), level)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Coerces the fields of an event to the types of a schema, removing any field
+/// whose value can't be coerced.
+///
+/// Fields that are absent from the event are left absent. If `dropped_path` is
+/// set, the paths of all removed fields are written to it as an array.
+#[derive(Debug)]
+pub(in crate::mapping) struct CoerceToSchemaFn {
+    schema: Vec<(String, Conversion)>,
+    dropped_path: Option<String>,
+}
+
+impl CoerceToSchemaFn {
+    pub(in crate::mapping) fn new(
+        schema: Vec<(String, Conversion)>,
+        dropped_path: Option<String>,
+    ) -> Self {
+        Self {
+            schema,
+            dropped_path,
+        }
+    }
+}
+
+/// Attempts to coerce a scalar value using the given conversion. Scalars are
+/// converted via their string representation, so the same rules apply as for
+/// the `coercer` transform. Maps, arrays and nulls can't be coerced.
+fn coerce_value(value: &Value, conversion: &Conversion) -> Option<Value> {
+    match (value, conversion) {
+        (Value::Map(_), _) | (Value::Array(_), _) | (Value::Null, _) => None,
+        (Value::Timestamp(_), Conversion::Timestamp)
+        | (Value::Timestamp(_), Conversion::TimestampFmt(_))
+        | (Value::Timestamp(_), Conversion::TimestampTZFmt(_)) => Some(value.clone()),
+        (value, conversion) => conversion.convert(value.as_bytes()).ok(),
+    }
+}
+
+impl Function for CoerceToSchemaFn {
+    fn apply(&self, target: &mut Event) -> Result<()> {
+        let log = target.as_mut_log();
+        let mut dropped = Vec::new();
+
+        for (path, conversion) in &self.schema {
+            let coerced = match log.get(path) {
+                Some(value) => coerce_value(value, conversion),
+                None => continue,
+            };
+
+            match coerced {
+                Some(value) => {
+                    log.insert(path, value);
+                }
+                None => {
+                    log.remove(path);
+                    dropped.push(Value::from(path.clone()));
+                }
+            }
+        }
+
+        if let Some(dropped_path) = &self.dropped_path {
+            log.insert(dropped_path, Value::Array(dropped));
+        }
+
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        let fields = self
+            .schema
+            .iter()
+            .map(|(path, conversion)| format!(".{} to {:?}", path, conversion))
+            .collect::<Vec<_>>();
+
+        let mut description = format!("coerce {}", fields.join(", "));
+        if let Some(dropped_path) = &self.dropped_path {
+            description.push_str(&format!(", recording dropped fields in .{}", dropped_path));
+        }
+        description
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// How a field is parsed by `coerce_timestamps`.
+#[derive(Debug, Clone)]
+pub(in crate::mapping) enum TimestampRule {
+    /// A string in the given format, see `parse_timestamp`.
+    Format(Conversion),
+    /// A number of seconds, or fractions of a second, since the Unix epoch.
+    /// The unit is the number of units per second.
+    Unix(i64),
+}
+
+impl TimestampRule {
+    pub(in crate::mapping) fn parse(rule: &str) -> Result<Self> {
+        Ok(match rule {
+            "unix_seconds" => Self::Unix(1),
+            "unix_milliseconds" => Self::Unix(1_000),
+            "unix_microseconds" => Self::Unix(1_000_000),
+            "unix_nanoseconds" => Self::Unix(1_000_000_000),
+            format => Self::Format(
+                format!("timestamp|{}", format)
+                    .parse::<Conversion>()
+                    .map_err(|err| err.to_string())?,
+            ),
+        })
+    }
+
+    fn apply(&self, value: &Value) -> Result<Value> {
+        match (self, value) {
+            (_, Value::Timestamp(_)) => Ok(value.clone()),
+            (Self::Format(conversion), Value::Bytes(bytes)) => conversion
+                .convert(bytes.clone())
+                .map_err(|err| err.to_string()),
+            (Self::Unix(per_second), Value::Integer(units)) => {
+                let seconds = units.div_euclid(*per_second);
+                let nanos = units.rem_euclid(*per_second) * (1_000_000_000 / per_second);
+
+                Utc.timestamp_opt(seconds, nanos as u32)
+                    .single()
+                    .map(Value::Timestamp)
+                    .ok_or_else(|| format!("timestamp {} is out of range", units))
+            }
+            (Self::Format(_), value) => Err(format!(
+                "unable to parse {} value, expected a string",
+                value.kind()
+            )),
+            (Self::Unix(_), value) => Err(format!(
+                "unable to parse {} value, expected an integer",
+                value.kind()
+            )),
+        }
+    }
+}
+
+/// Parses the fields at each of several paths into timestamps, each with its
+/// own rule, e.g. `coerce_timestamps({".ts": "%Y-%m-%d %H:%M:%S",
+/// ".event_time": "unix_seconds"})`.
+///
+/// Fields that are absent are skipped, and fields that are already timestamps
+/// are left as they are. A field that can't be parsed is left unchanged, but
+/// all other fields are still coerced before the errors of all such fields
+/// are returned together.
+#[derive(Debug)]
+pub(in crate::mapping) struct CoerceTimestampsFn {
+    rules: Vec<(String, TimestampRule)>,
+}
+
+impl CoerceTimestampsFn {
+    pub(in crate::mapping) fn new(rules: Vec<(String, TimestampRule)>) -> Self {
+        Self { rules }
+    }
+}
+
+impl Function for CoerceTimestampsFn {
+    fn apply(&self, target: &mut Event) -> Result<()> {
+        let log = target.as_mut_log();
+        let mut errors = Vec::new();
+
+        for (path, rule) in &self.rules {
+            let coerced = match log.get(path) {
+                Some(value) => rule.apply(value),
+                None => continue,
+            };
+
+            match coerced {
+                Ok(value) => {
+                    log.insert(path, value);
+                }
+                Err(err) => errors.push(format!(".{}: {}", path, err)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "unable to coerce timestamps: {}",
+                errors.join(", ")
+            ))
+        }
+    }
+
+    fn describe(&self) -> String {
+        let fields = self
+            .rules
+            .iter()
+            .map(|(path, rule)| match rule {
+                TimestampRule::Format(conversion) => format!(".{} as {:?}", path, conversion),
+                TimestampRule::Unix(per_second) => {
+                    format!(".{} as 1/{} seconds since the epoch", path, per_second)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        format!("coerce timestamps {}", fields.join(", "))
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Replaces each value of the map at a path with the result of a function
+/// reference, e.g. `map_values(.headers, trim)`.
+///
+/// The function reads each value as its first argument, bound to `value`. If
+/// it fails for any value, the map is left unchanged and the error is
+/// returned.
+#[derive(Debug)]
+pub(in crate::mapping) struct MapValuesFn {
+    path: String,
+    value: Bound,
+    function: Box<dyn query::Function>,
+}
+
+impl MapValuesFn {
+    pub(in crate::mapping) fn new(
+        path: String,
+        value: Bound,
+        function: Box<dyn query::Function>,
+    ) -> Self {
+        Self {
+            path,
+            value,
+            function,
+        }
+    }
+}
+
+/// Returns the map at a path, for a statement that transforms it.
+fn map_at<'a>(
+    target: &'a Event,
+    path: &str,
+    statement: &str,
+) -> Result<&'a BTreeMap<String, Value>> {
+    match target.as_log().get(path) {
+        Some(Value::Map(map)) => Ok(map),
+        Some(value) => Err(format!(
+            "parameter {} passed to {} is a non-map value of type {}",
+            path,
+            statement,
+            value.kind()
+        )),
+        None => Err(format!(
+            "parameter {} passed to {} is not found",
+            path, statement
+        )),
+    }
+}
+
+impl Function for MapValuesFn {
+    fn apply(&self, target: &mut Event) -> Result<()> {
+        let map = map_at(target, &self.path, "map_values")?.clone();
+
+        let map = map
+            .into_iter()
+            .map(
+                |(key, value)| match self.value.bind(value, || self.function.execute(target))? {
+                    QueryValue::Value(value) => Ok((key, value)),
+                    v => Err(format!(
+                        "function returned non-value of type {} for key {}",
+                        v.kind(),
+                        key
+                    )),
+                },
+            )
+            .collect::<Result<BTreeMap<_, _>>>()?;
+
+        target.as_mut_log().insert(&self.path, Value::Map(map));
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "replace each value of .{} with {}",
+            self.path,
+            self.function.describe()
+        )
+    }
+}
+
+/// Replaces each key of the map at a path with the result of a function
+/// reference, which must be a string, e.g. `map_keys(.headers, downcase)`.
+///
+/// The function reads each key as its first argument, bound to `key`. If it
+/// fails for any key, or maps two keys to the same key, the map is left
+/// unchanged and an error is returned.
+#[derive(Debug)]
+pub(in crate::mapping) struct MapKeysFn {
+    path: String,
+    key: Bound,
+    function: Box<dyn query::Function>,
+}
+
+impl MapKeysFn {
+    pub(in crate::mapping) fn new(
+        path: String,
+        key: Bound,
+        function: Box<dyn query::Function>,
+    ) -> Self {
+        Self {
+            path,
+            key,
+            function,
+        }
+    }
+}
+
+impl Function for MapKeysFn {
+    fn apply(&self, target: &mut Event) -> Result<()> {
+        let map = map_at(target, &self.path, "map_keys")?.clone();
+        let mut mapped = BTreeMap::new();
+        let mut sources = BTreeMap::new();
+
+        for (key, value) in map {
+            let new_key = match self
+                .key
+                .bind(Value::from(key.clone()), || self.function.execute(target))?
+            {
+                QueryValue::Value(Value::Bytes(bytes)) => {
+                    String::from_utf8_lossy(&bytes).into_owned()
+                }
+                v => {
+                    return Err(format!(
+                        "function returned non-string value of type {} for key {}",
+                        v.kind(),
+                        key
+                    ))
+                }
+            };
+
+            if let Some(other) = sources.insert(new_key.clone(), key.clone()) {
+                return Err(format!(
+                    "keys {} and {} both map to key {}",
+                    other, key, new_key
+                ));
+            }
+            mapped.insert(new_key, value);
+        }
+
+        target.as_mut_log().insert(&self.path, Value::Map(mapped));
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "replace each key of .{} with {}",
+            self.path,
+            self.function.describe()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execute_iter() {
+        let mapping = parser::parse(".foo = .bar").unwrap();
+
+        let events = vec![
+            {
+                let mut event = Event::from("");
+                event.as_mut_log().insert("bar", Value::from(1));
+                event
+            },
+            Event::from(""),
+            {
+                let mut event = Event::from("");
+                event.as_mut_log().insert("bar", Value::from(3));
+                event
+            },
+        ];
+
+        let results = mapping
+            .execute_iter(events.into_iter())
+            .map(|result| result.map(|event| event.as_log().get("foo").cloned()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            vec![
+                Ok(Some(Value::from(1))),
+                Err("failed to apply mapping 0: path .bar not found in event".to_string()),
+                Ok(Some(Value::from(3))),
+            ]
+        );
+    }
+
+    #[test]
+    fn with_timeout() {
+        let mut event = Event::from("");
+        let map = (0..10_000)
+            .map(|i| (format!("field{}", i), Value::from(i)))
+            .collect::<BTreeMap<_, _>>();
+        event.as_mut_log().insert("foo", Value::Map(map));
+        event
+            .as_mut_log()
+            .insert("bar", Value::Map(BTreeMap::new()));
+
+        let source = "merge(.bar, .foo)\n".repeat(10);
+
+        let mapping = parser::parse(&source).unwrap();
+        assert_eq!(mapping.execute(&mut event.clone()), Ok(Outcome::Completed));
+
+        let mapping = parser::parse(&source)
+            .unwrap()
+            .with_timeout(Duration::from_nanos(1));
+        assert_eq!(
+            mapping.execute(&mut event),
+            Err("mapping timed out after 1ns at mapping 0".to_string())
+        );
+    }
+
+    #[test]
+    fn with_timeout_during_merge() {
+        let mut event = Event::from("");
+        let children = |value: fn(usize) -> Value| {
+            (0..10_000)
+                .map(|i| (format!("field{}", i), value(i)))
+                .collect::<BTreeMap<_, _>>()
+        };
+        event.as_mut_log().insert(
+            "foo",
+            Value::Map(children(|i| {
+                Value::from(serde_json::json!({ "merged": i }))
+            })),
+        );
+        event
+            .as_mut_log()
+            .insert("bar", Value::Map(children(|_| Value::Map(BTreeMap::new()))));
+
+        let mapping = parser::parse("merge(.bar, .foo, true)")
+            .unwrap()
+            .with_timeout(Duration::from_nanos(1));
+        assert_eq!(
+            mapping.execute(&mut event),
+            Err("mapping timed out after 1ns at mapping 0".to_string())
+        );
+
+        // The merge stopped before descending into any of the child maps.
+        let merged = event
+            .as_log()
+            .keys()
+            .filter(|key| key.starts_with("bar.") && key.ends_with(".merged"))
+            .count();
+        assert_eq!(merged, 0);
+    }
+
+    #[test]
+    fn on_error() {
+        let source = r#".foo = .missing
+                        .bar = "bar"
+                        .baz = .also_missing"#;
+
+        let mut event = Event::from("");
+        let mapping = parser::parse(source)
+            .unwrap()
+            .with_on_error(OnError::RecordAndContinue("error".to_owned()));
+        assert_eq!(mapping.execute(&mut event), Ok(Outcome::Completed));
+        assert_eq!(event.as_log().get("bar"), Some(&Value::from("bar")));
+        assert_eq!(
+            event.as_log().get("error"),
+            Some(&Value::from(
+                "failed to apply mapping 2: path .also_missing not found in event"
+            ))
+        );
+
+        let mut event = Event::from("");
+        let mapping = parser::parse(source)
+            .unwrap()
+            .with_on_error(OnError::RecordAndStop("error".to_owned()));
+        assert_eq!(mapping.execute(&mut event), Ok(Outcome::Completed));
+        assert_eq!(event.as_log().get("bar"), None);
+        assert_eq!(
+            event.as_log().get("error"),
+            Some(&Value::from(
+                "failed to apply mapping 0: path .missing not found in event"
+            ))
+        );
+
+        let mut event = Event::from("");
+        let mapping = parser::parse(source).unwrap();
+        assert_eq!(
+            mapping.execute(&mut event),
+            Err("failed to apply mapping 0: path .missing not found in event".to_string())
+        );
+    }
+
+    #[test]
+    fn execute_all() {
+        let mapping = parser::parse(
+            r#".foo = .missing
+               .bar = "bar"
+               .baz = .also_missing"#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("");
+        assert_eq!(
+            mapping.execute_all(&mut event),
+            Err(vec![
+                "failed to apply mapping 0: path .missing not found in event".to_owned(),
+                "failed to apply mapping 2: path .also_missing not found in event".to_owned(),
+            ])
+        );
+        assert_eq!(event.as_log().get("bar"), Some(&Value::from("bar")));
+
+        let mapping = parser::parse(r#".bar = "bar""#).unwrap();
+        assert_eq!(mapping.execute_all(&mut Event::from("")), Ok(()));
+    }
+
+    #[test]
+    fn execute_transactional() {
+        let mapping = parser::parse(
+            r#".foo = "changed"
+               del(.bar)
+               .baz = .missing
+               .qux = "added""#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("original");
+        event.as_mut_log().insert("foo", Value::from("foo"));
+        event
+            .as_mut_log()
+            .insert("bar", Value::from(serde_json::json!({"nested": [1, 2]})));
+        let original = event.clone();
+
+        assert_eq!(
+            mapping.execute_transactional(&mut event),
+            Err("failed to apply mapping 2: path .missing not found in event".to_string())
+        );
+        assert_eq!(event, original);
+        assert_eq!(
+            serde_json::to_vec(event.as_log().as_map()).unwrap(),
+            serde_json::to_vec(original.as_log().as_map()).unwrap()
+        );
+
+        // Without rollback, the statements before the failure are applied.
+        assert!(mapping.execute(&mut event).is_err());
+        assert_eq!(event.as_log().get("foo"), Some(&Value::from("changed")));
+
+        let mut event = original.clone();
+        event.as_mut_log().insert("missing", Value::from(1));
+        assert_eq!(
+            mapping.execute_transactional(&mut event),
+            Ok(Outcome::Completed)
+        );
+        assert_eq!(event.as_log().get("foo"), Some(&Value::from("changed")));
+        assert_eq!(event.as_log().get("bar"), None);
+        assert_eq!(event.as_log().get("qux"), Some(&Value::from("added")));
+    }
+
+    #[test]
+    fn execute_with_context() {
+        use chrono::{TimeZone, Utc};
+
+        let now = Utc.ymd(2021, 1, 4).and_hms_micro(12, 30, 15, 123_456);
+        let mapping = || {
+            parser::parse(
+                r#".ts = now()
+                   .n = random_int(0, 1000000)"#,
+            )
+            .unwrap()
+            .with_context(ExecutionContext::new().with_now(now).with_seed(7))
+        };
+
+        // The generator carries on across events, and starts over for each
+        // mapping seeded the same way.
+        let draw = |mapping: &Mapping| {
+            (0..3)
+                .map(|i| {
+                    let mut event = Event::from("");
+                    if i == 0 {
+                        assert_eq!(mapping.execute(&mut event), Ok(Outcome::Completed));
+                    } else {
+                        assert_eq!(mapping.execute_all(&mut event), Ok(()));
+                    }
+                    assert_eq!(event.as_log().get("ts"), Some(&Value::Timestamp(now)));
+                    event.as_log().get("n").cloned().unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let first = draw(&mapping());
+        assert_eq!(first, draw(&mapping()));
+        assert!(first.iter().any(|n| *n != first[0]));
+    }
+
+    #[test]
+    fn reduce() {
+        let mapping = parser::parse(
+            r#".total = reduce(.nums, add, 0)
+               .path = reduce(.prefixes, strip_prefix, .path)"#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("");
+        event
+            .as_mut_log()
+            .insert("nums", Value::from(serde_json::json!([1, 2, 3, 4])));
+        event
+            .as_mut_log()
+            .insert("prefixes", Value::from(serde_json::json!(["/api", "/v1"])));
+        event
+            .as_mut_log()
+            .insert("path", Value::from("/api/v1/users"));
+
+        assert_eq!(mapping.execute(&mut event), Ok(Outcome::Completed));
+        assert_eq!(event.as_log().get("total"), Some(&Value::from(10)));
+        assert_eq!(event.as_log().get("path"), Some(&Value::from("/users")));
+
+        let mut event = Event::from("");
+        event
+            .as_mut_log()
+            .insert("nums", Value::from(serde_json::json!([1, "2"])));
+        assert_eq!(
+            parser::parse(".total = reduce(.nums, add, 0)")
+                .unwrap()
+                .execute(&mut event),
+            Err("failed to apply mapping 0: unable to reduce element 1: \
+                 unable to add right-hand field type Bytes(b\"2\")"
+                .to_string())
+        );
+    }
+
+    #[test]
+    fn mapping_builder() {
+        let mapping = MappingBuilder::new()
+            .assign("host", Query::path("source.host"))
+            .if_stmt(
+                Query::path("source.env").equals(Query::literal("prod")),
+                MappingBuilder::new()
+                    .assign("important", Query::literal(true))
+                    .delete(vec!["source".to_owned()]),
+                MappingBuilder::new(),
+            )
+            .build();
+
+        let mut event = Event::from("");
+        event
+            .as_mut_log()
+            .insert("source.host", Value::from("web-1"));
+        event.as_mut_log().insert("source.env", Value::from("prod"));
+
+        assert_eq!(mapping.execute(&mut event), Ok(Outcome::Completed));
+        assert_eq!(event.as_log().get("host"), Some(&Value::from("web-1")));
+        assert_eq!(event.as_log().get("important"), Some(&Value::from(true)));
+        assert_eq!(event.as_log().get("source"), None);
+        assert_eq!(
+            mapping.explain(),
+            vec![
+                "assign .host = .source.host".to_owned(),
+                "if .source.env == literal \"prod\" then assign .important = literal true, then delete .source else do nothing".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn abort() {
+        let mapping = parser::parse(
+            r#".foo = "foo"
+               if .level == "debug" { abort() }
+               .bar = .missing"#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("level", Value::from("debug"));
+        assert_eq!(mapping.execute(&mut event), Ok(Outcome::Aborted));
+        assert_eq!(event.as_log().get("foo"), Some(&Value::from("foo")));
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("level", Value::from("info"));
+        assert_eq!(
+            mapping.execute(&mut event),
+            Err("failed to apply mapping 2: path .missing not found in event".to_string())
+        );
+
+        let mapping = mapping.with_on_error(OnError::RecordAndContinue("error".to_owned()));
+        let events = vec![
+            {
+                let mut event = Event::from("");
+                event.as_mut_log().insert("level", Value::from("debug"));
+                event
+            },
+            {
+                let mut event = Event::from("");
+                event.as_mut_log().insert("level", Value::from("info"));
+                event
+            },
+        ];
+
+        let results = mapping
+            .execute_iter(events.into_iter())
+            .map(|result| result.map(|event| event.as_log().get("level").cloned()))
+            .collect::<Vec<_>>();
+        assert_eq!(results, vec![Ok(Some(Value::from("info")))]);
+    }
+
+    #[test]
+    fn execute_with_changes() {
+        let mapping = parser::parse(
+            r#".foo = "foo"
+               del(.missing)
+               .bar = .bar
+               if .foo == "foo" { del(.foo) }
+               abort()
+               .baz = "baz""#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("bar", Value::from("bar"));
+
+        assert_eq!(
+            mapping.execute_with_changes(&mut event),
+            Ok((
+                Outcome::Aborted,
+                vec![true, false, false, true, false, false]
+            ))
+        );
+    }
+
+    #[test]
+    fn coerce_timestamps() {
+        let mapping = parser::parse(
+            r#"coerce_timestamps({
+                 ".ts": "%Y-%m-%d %H:%M:%S %z",
+                 ".event.time": "unix_seconds",
+                 ".event.received": "unix_milliseconds",
+                 ".absent": "unix_seconds",
+               })"#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("");
+        event
+            .as_mut_log()
+            .insert("ts", Value::from("2021-01-04 12:30:00 +0100"));
+        event
+            .as_mut_log()
+            .insert("event.time", Value::from(1_609_763_400));
+        event
+            .as_mut_log()
+            .insert("event.received", Value::from(-1_500));
+
+        assert_eq!(mapping.execute(&mut event), Ok(Outcome::Completed));
+        assert_eq!(
+            event.as_log().get("ts"),
+            Some(&Value::from(Utc.ymd(2021, 1, 4).and_hms(11, 30, 0)))
+        );
+        assert_eq!(
+            event.as_log().get("event.time"),
+            Some(&Value::from(Utc.ymd(2021, 1, 4).and_hms(12, 30, 0)))
+        );
+        assert_eq!(
+            event.as_log().get("event.received"),
+            Some(&Value::from(
+                Utc.ymd(1969, 12, 31).and_hms_milli(23, 59, 58, 500)
+            ))
+        );
+        assert_eq!(event.as_log().get("absent"), None);
+
+        // Coercing again leaves the timestamps as they are.
+        let coerced = event.clone();
+        assert_eq!(mapping.execute(&mut event), Ok(Outcome::Completed));
+        assert_eq!(event, coerced);
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("ts", Value::from("yesterday"));
+        event
+            .as_mut_log()
+            .insert("event.time", Value::from("1609763400"));
+        event
+            .as_mut_log()
+            .insert("event.received", Value::from(1_609_763_400_000i64));
+
+        assert_eq!(
+            mapping.execute(&mut event),
+            Err("failed to apply mapping 0: unable to coerce timestamps: \
+                 .ts: Invalid timestamp \"yesterday\": input contains invalid characters, \
+                 .event.time: unable to parse string value, expected an integer"
+                .to_string())
+        );
+        assert_eq!(event.as_log().get("ts"), Some(&Value::from("yesterday")));
+        assert_eq!(
+            event.as_log().get("event.received"),
+            Some(&Value::from(Utc.ymd(2021, 1, 4).and_hms(12, 30, 0)))
+        );
+    }
+
+    #[test]
+    fn coerce_to_schema() {
+        let mapping = parser::parse(
+            r#"coerce_to_schema({ .foo: "integer", .bar: "integer", .baz: "boolean" }, dropped = .dropped)"#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from("42"));
+        event
+            .as_mut_log()
+            .insert("bar", Value::from("not a number"));
+        mapping.execute(&mut event).unwrap();
+
+        assert_eq!(event.as_log().get("foo"), Some(&Value::from(42)));
+        assert_eq!(event.as_log().get("bar"), None);
+        assert_eq!(event.as_log().get("baz"), None);
+        assert_eq!(
+            event.as_log().get("dropped"),
+            Some(&Value::from(vec![Value::from("bar")]))
+        );
+    }
+
+    #[test]
+    fn increment() {
+        let mapping = parser::parse(
+            r#"increment(.counts.errors, 1)
+               increment(.counts.warnings, 2)"#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("counts.errors", Value::from(41));
+        mapping.execute(&mut event).unwrap();
+        assert_eq!(event.as_log().get("counts.errors"), Some(&Value::from(42)));
+        assert_eq!(event.as_log().get("counts.warnings"), Some(&Value::from(2)));
+
+        let mut event = Event::from("");
+        event
+            .as_mut_log()
+            .insert("counts.errors", Value::from("41"));
+        assert_eq!(
+            mapping.execute(&mut event),
+            Err("failed to apply mapping 0: parameter counts.errors passed to increment is a non-integer value".to_string())
+        );
+    }
+
+    #[test]
+    fn redact_paths() {
+        let mapping =
+            parser::parse(r#"redact_paths([".user.ssn", .card.number, .missing], "***")"#).unwrap();
+
+        let mut event = Event::from("");
+        event
+            .as_mut_log()
+            .insert("user.ssn", Value::from("123-45-6789"));
+        event.as_mut_log().insert("user.name", Value::from("jane"));
+        event
+            .as_mut_log()
+            .insert("card.number", Value::from(4_111_111_111_111_111_i64));
+        mapping.execute(&mut event).unwrap();
+
+        assert_eq!(event.as_log().get("user.ssn"), Some(&Value::from("***")));
+        assert_eq!(event.as_log().get("user.name"), Some(&Value::from("jane")));
+        assert_eq!(event.as_log().get("card.number"), Some(&Value::from("***")));
+        assert_eq!(event.as_log().get("missing"), None);
+    }
+
+    #[test]
+    fn snake_case_keys() {
+        let mapping = parser::parse("snake_case_keys(.payload)").unwrap();
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert(
+            "payload",
+            Value::from(serde_json::json!({
+                "userId": 1,
+                "HTTPServer": {"requestCount": 2, "already_snake": 3},
+                "tagList": [{"tagName": "a"}],
+            })),
+        );
+        mapping.execute(&mut event).unwrap();
+        assert_eq!(
+            event.as_log().get("payload"),
+            Some(&Value::from(serde_json::json!({
+                "user_id": 1,
+                "http_server": {"request_count": 2, "already_snake": 3},
+                "tag_list": [{"tag_name": "a"}],
+            })))
+        );
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert(
+            "payload",
+            Value::from(serde_json::json!({"userId": 1, "user_id": 2})),
+        );
+        assert_eq!(
+            mapping.execute(&mut event),
+            Err("failed to apply mapping 0: converted key user_id already exists".to_string())
+        );
+    }
+
+    #[test]
+    fn only_fields() {
+        let event_from_json = |json: serde_json::Value| match Value::from(json) {
+            Value::Map(map) => Event::from(map),
+            _ => unreachable!(),
+        };
+
+        let event = event_from_json(serde_json::json!({
+            "nested": {"a": {"b": 1, "c": 2}, "d": 3},
+            "nested_sibling": 4,
+            "other": {"e": {"f": 5}, "g": 6},
+        }));
+
+        let cases = vec![
+            (
+                "only_fields(.nested.*)",
+                serde_json::json!({"nested": {"a": {"b": 1, "c": 2}, "d": 3}}),
+            ),
+            // A parent path keeps all of its descendants.
+            (
+                "only_fields(.nested.a)",
+                serde_json::json!({"nested": {"a": {"b": 1, "c": 2}}}),
+            ),
+            // Maps emptied by the removal don't linger.
+            (
+                "only_fields(.nested.d, .other.g)",
+                serde_json::json!({"nested": {"d": 3}, "other": {"g": 6}}),
+            ),
+            (
+                "only_fields(.nested_sibling, .other.e.*)",
+                serde_json::json!({"nested_sibling": 4, "other": {"e": {"f": 5}}}),
+            ),
+        ];
+
+        for (source, exp) in cases {
+            let mapping = parser::parse(source).unwrap();
+            let mut event = event.clone();
+
+            mapping.execute(&mut event).unwrap();
+            assert_eq!(event.as_log(), event_from_json(exp).as_log(), "{}", source);
+        }
+    }
+
+    #[test]
+    fn parse_csv_with_names() {
+        let mapping = parser::parse(
+            r#".fields = parse_csv(.message, ["ts", "host", "status"])
+.tags = [.fields.host, "csv", 1 + 1, []]"#,
+        )
+        .unwrap();
+
+        let mut event = Event::from(r#"2021-01-04T12:00:00Z,"web-1",200"#);
+        mapping.execute(&mut event).unwrap();
+        assert_eq!(
+            event.as_log().get("fields"),
+            Some(&Value::from(serde_json::json!({
+                "ts": "2021-01-04T12:00:00Z",
+                "host": "web-1",
+                "status": "200",
+            })))
+        );
+        assert_eq!(
+            event.as_log().get("tags"),
+            Some(&Value::from(serde_json::json!(["web-1", "csv", 2, []])))
+        );
+
+        let mut event = Event::from("2021-01-04T12:00:00Z,web-1");
+        assert_eq!(
+            mapping.execute(&mut event),
+            Err("failed to apply mapping 0: expected 3 fields, got 2".to_owned())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "mapping-grok")]
+    fn parse_grok_with_definitions() {
+        let mapping = parser::parse(
+            r#".order = parse_grok(.message, "%{ORDER}", {"ORDER": "order %{ORDER_ID:id} %{WORD:status}", "ORDER_ID": "%{INT}-[a-z]+"})"#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("order 1234-ab shipped");
+        mapping.execute(&mut event).unwrap();
+        assert_eq!(
+            event.as_log().get("order.id"),
+            Some(&Value::from("1234-ab"))
+        );
+        assert_eq!(
+            event.as_log().get("order.status"),
+            Some(&Value::from("shipped"))
+        );
+
+        let mut event = Event::from("order 1234 shipped");
+        assert_eq!(
+            mapping.execute(&mut event),
+            Err("failed to apply mapping 0: unable to parse input with grok pattern".to_owned())
+        );
+    }
+
+    #[test]
+    fn map_values_and_keys() {
+        let headers = || {
+            Value::from(serde_json::json!({
+                "accept": "  */*  ",
+                "host": "example.com\n",
+                "x-request-id": " abc ",
+            }))
+        };
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("headers", headers());
+
+        parser::parse("map_values(.headers, trim)")
+            .unwrap()
+            .execute(&mut event)
+            .unwrap();
+        assert_eq!(
+            event.as_log().get("headers"),
+            Some(&Value::from(serde_json::json!({
+                "accept": "*/*",
+                "host": "example.com",
+                "x-request-id": "abc",
+            })))
+        );
+
+        parser::parse("map_keys(.headers, upcase)")
+            .unwrap()
+            .execute(&mut event)
+            .unwrap();
+        assert_eq!(
+            event.as_log().get("headers"),
+            Some(&Value::from(serde_json::json!({
+                "ACCEPT": "*/*",
+                "HOST": "example.com",
+                "X-REQUEST-ID": "abc",
+            })))
+        );
+
+        // Functions can take further arguments after the value or key.
+        let mut event = Event::from("");
+        event.as_mut_log().insert("headers", headers());
+        event.as_mut_log().insert("prefix", Value::from("x-"));
+        parser::parse("map_keys(.headers, strip_prefix, .prefix)")
+            .unwrap()
+            .execute(&mut event)
+            .unwrap();
+        assert_eq!(
+            event.as_log().get("headers.request-id"),
+            Some(&Value::from(" abc "))
+        );
+
+        let cases = vec![
+            (
+                "map_values(.headers, trim)",
+                serde_json::json!({"a": " a ", "b": 1}),
+                "failed to apply mapping 0: invalid argument type 'integer' for parameter 'value'",
+            ),
+            (
+                "map_keys(.headers, downcase)",
+                serde_json::json!({"a": 1, "A": 2}),
+                "failed to apply mapping 0: keys A and a both map to key a",
+            ),
+            (
+                "map_keys(.headers, length)",
+                serde_json::json!({"a": 1}),
+                "failed to apply mapping 0: function returned non-string value of type integer for key a",
+            ),
+            (
+                "map_values(.headers, trim)",
+                serde_json::json!("a"),
+                "failed to apply mapping 0: parameter headers passed to map_values is a non-map value of type string",
+            ),
+        ];
+
+        for (source, value, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("headers", Value::from(value));
+            let original = event.clone();
+
+            assert_eq!(
+                parser::parse(source).unwrap().execute(&mut event),
+                Err(exp.to_owned()),
+                "{}",
+                source
+            );
+            assert_eq!(event, original);
+        }
+    }
+
+    #[test]
+    fn only_fields_wide_event() {
+        // Whether a field is nested under a path, compared naively.
+        fn is_nested_under(key: &str, path: &str) -> bool {
+            key.strip_prefix(path)
+                .map_or(false, |rest| rest.starts_with('.') || rest.starts_with('['))
+        }
+
+        let mut event = Event::from("");
+        for i in 0..50 {
+            for j in 0..50 {
+                event
+                    .as_mut_log()
+                    .insert(format!("group{}.field{}", i, j), Value::from(j));
+            }
+            event
+                .as_mut_log()
+                .insert(format!("group{}_sibling", i), Value::from(i));
+            event.as_mut_log().insert(
+                format!("list{}", i),
+                Value::from(serde_json::json!([i, {"x": i}])),
+            );
+        }
+
+        let mut paths = Vec::new();
+        for i in (0..50).step_by(3) {
+            paths.push(format!("group{}", i));
+            paths.push(format!("group{}.field{}", i + 1, i));
+            paths.push(format!("list{}", i + 2));
+        }
+        paths.push("group49.*".to_owned());
+        paths.push("group48_sibling".to_owned());
+
+        let keeps = |key: &str| {
+            paths.iter().any(|path| match path.strip_suffix(".*") {
+                Some(parent) => is_nested_under(key, parent),
+                None => key == path || is_nested_under(key, path),
+            })
+        };
+        let expected = event
+            .as_log()
+            .keys()
+            .filter(|key| keeps(key))
+            .collect::<Vec<_>>();
+        assert!(expected.len() > 500);
+
+        let source = paths
+            .iter()
+            .map(|path| format!(".{}", path))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mapping = parser::parse(&format!("only_fields({})", source)).unwrap();
+        mapping.execute(&mut event).unwrap();
+
+        assert_eq!(event.as_log().keys().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn remove_nulls() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert(
+            "payload",
+            Value::from(serde_json::json!({
+                "a": null,
+                "b": {"c": null, "d": "", "e": {"f": null}},
+                "g": [1, null, {"h": null, "i": 0}, [null]],
+            })),
+        );
+        event.as_mut_log().insert("top", Value::Null);
+        event
+            .as_mut_log()
+            .insert("other", Value::from(serde_json::json!({"j": null})));
+
+        let mapping = parser::parse("remove_nulls(.payload)").unwrap();
+        mapping.execute(&mut event).unwrap();
+        assert_eq!(
+            event.as_log().get("payload"),
+            Some(&Value::from(serde_json::json!({
+                "b": {"d": "", "e": {}},
+                "g": [1, {"i": 0}, []],
+            })))
+        );
+        assert_eq!(event.as_log().get("top"), Some(&Value::Null));
+
+        let mapping = parser::parse("remove_nulls(.)").unwrap();
+        mapping.execute(&mut event).unwrap();
+        assert!(!event.as_log().contains("top"));
+        assert_eq!(
+            event.as_log().get("other"),
+            Some(&Value::from(serde_json::json!({})))
+        );
+
+        // Top-level keys are escaped to be used as paths.
+        let mut event = Event::from("");
+        event.as_mut_log().insert_flat("tags[0]", Value::Null);
+        event.as_mut_log().insert_flat(
+            "a[b].c\\d",
+            Value::from(serde_json::json!({"n": null, "m": 1})),
+        );
+
+        mapping.execute(&mut event).unwrap();
+        assert!(!event.as_log().as_map().contains_key("tags[0]"));
+        assert_eq!(
+            event.as_log().as_map().get("a[b].c\\d"),
+            Some(&Value::from(serde_json::json!({"m": 1})))
+        );
+    }
+
+    #[test]
+    fn flatten() {
+        let mapping = parser::parse("flatten(.payload)").unwrap();
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert(
+            "payload",
+            Value::from(serde_json::json!({
+                "a": {"b": 1, "c": {"d": true}},
+                "e": "f",
+            })),
+        );
+        mapping.execute(&mut event).unwrap();
+        assert_eq!(
+            event.as_log().get("payload"),
+            Some(&Value::from(serde_json::json!({
+                "a.b": 1,
+                "a.c.d": true,
+                "e": "f",
+            })))
+        );
+
+        let mapping = parser::parse(r#"flatten(.payload, separator = "_")"#).unwrap();
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert(
+            "payload",
+            Value::from(serde_json::json!({
+                "items": [{"name": "a"}, 2],
+                "empty": {"map": {}, "array": []},
+            })),
+        );
+        mapping.execute(&mut event).unwrap();
+        assert_eq!(
+            event.as_log().get("payload"),
+            Some(&Value::from(serde_json::json!({
+                "items[0]_name": "a",
+                "items[1]": 2,
+                "empty_map": {},
+                "empty_array": [],
+            })))
+        );
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("payload", Value::from("a.b"));
+        assert_eq!(
+            mapping.execute(&mut event),
+            Err(
+                "failed to apply mapping 0: parameter payload passed to flatten is a non-map value of type string"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn apply_if_present() {
+        let mapping = parser::parse(
+            r#"apply_if_present(.foo, normalize_numeric)
+               apply_if_present(.bar, normalize_numeric)"#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from("12"));
+        mapping.execute(&mut event).unwrap();
+
+        assert_eq!(event.as_log().get("foo"), Some(&Value::from(12)));
+        assert_eq!(event.as_log().get("bar"), None);
+
+        assert_eq!(
+            parser::parse("apply_if_present(.foo, chunk)").unwrap_err(),
+            r#"mapping parse error at byte 0
+ --> 1:1
+  |
+1 | apply_if_present(.foo, chunk)
+  | ^---------------------------^
+  |
+  = function 'chunk' requires more than one argument and can't be applied to a field"#
+        );
+    }
+
+    #[test]
+    fn exists_and_is_null() {
+        let mapping = parser::parse(
+            r#"if exists(.user.id) { .has_id = true } else { .has_id = false }
+               if is_null(.user.id) { .null_id = true } else { .null_id = false }"#,
+        )
+        .unwrap();
+
+        let cases = vec![
+            (None, false, false),
+            (Some(Value::Null), true, true),
+            (Some(Value::from(42)), true, false),
+        ];
+
+        for (id, has_id, null_id) in cases {
+            let mut event = Event::from("");
+            if let Some(id) = id {
+                event.as_mut_log().insert("user.id", id);
+            }
+
+            assert_eq!(mapping.execute(&mut event), Ok(Outcome::Completed));
+            assert_eq!(event.as_log().get("has_id"), Some(&Value::from(has_id)));
+            assert_eq!(event.as_log().get("null_id"), Some(&Value::from(null_id)));
+        }
+    }
+
+    #[test]
+    fn move_with_del() {
+        let mapping = parser::parse(
+            r#".host = del(.hostname)
+               .request = del(.http.request)
+               .user = del(.missing)"#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("hostname", Value::from("web-1"));
+        event.as_mut_log().insert(
+            "http",
+            Value::from(serde_json::json!({
+                "request": {"method": "GET", "headers": {"accept": "*/*"}},
+                "status": 200,
+            })),
+        );
+
+        assert_eq!(mapping.execute(&mut event), Ok(Outcome::Completed));
+
+        let log = event.as_log();
+        assert_eq!(log.get("host"), Some(&Value::from("web-1")));
+        assert_eq!(log.get("hostname"), None);
+        assert_eq!(
+            log.get("request"),
+            Some(&Value::from(serde_json::json!({
+                "method": "GET",
+                "headers": {"accept": "*/*"},
+            })))
+        );
+        assert_eq!(log.get("http.request"), None);
+        assert_eq!(log.get("http.status"), Some(&Value::from(200)));
+        assert_eq!(log.get("user"), Some(&Value::Null));
+        assert_eq!(log.get("missing"), None);
+
+        // Moving a field onto itself leaves it in place.
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from("bar"));
+        parser::parse(".foo = del(.foo)")
+            .unwrap()
+            .execute(&mut event)
+            .unwrap();
+        assert_eq!(event.as_log().get("foo"), Some(&Value::from("bar")));
+    }
+
+    #[test]
+    fn rename() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from("foo"));
+        event.as_mut_log().insert("bar", Value::from("bar"));
+        event.as_mut_log().insert("a.b", Value::from("nested"));
+
+        let mapping = parser::parse(
+            r#"rename(.foo, .baz)
+               rename(.missing, .qux)
+               rename(.baz, .bar)
+               rename(.a.b, .c)"#,
+        )
+        .unwrap();
+        mapping.execute(&mut event).unwrap();
+
+        assert_eq!(event.as_log().get("foo"), None);
+        assert_eq!(event.as_log().get("baz"), None);
+        assert_eq!(event.as_log().get("qux"), None);
+        assert_eq!(event.as_log().get("bar"), Some(&Value::from("foo")));
+        assert_eq!(event.as_log().get("a.b"), None);
+        assert_eq!(event.as_log().get("c"), Some(&Value::from("nested")));
+    }
+
+    #[test]
+    fn log_structured_values() {
+        let cases = vec![
+            (Value::from("foo \"bar\""), r#"foo "bar""#),
+            (
+                Value::from(serde_json::json!({"foo": {"bar": [1, "baz"]}, "qux": null})),
+                r#"{"foo":{"bar":[1,"baz"]},"qux":null}"#,
+            ),
+            (
+                Value::from(serde_json::json!([true, 1.5, "foo"])),
+                r#"[true,1.5,"foo"]"#,
+            ),
+            (Value::from(42), "42"),
+            (Value::Null, "null"),
+        ];
+
+        for (value, exp) in cases {
+            assert_eq!(render_log_message(value), exp);
+        }
+    }
+
+    #[test]
+    fn merge_union_arrays() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert(
+            "foo",
+            Value::from(serde_json::json!({
+                "tags": ["a", "b"],
+                "nested": {"tags": ["x", "y", "x"], "name": "foo"},
+            })),
+        );
+        event.as_mut_log().insert(
+            "bar",
+            Value::from(serde_json::json!({
+                "tags": ["b", "c"],
+                "nested": {"tags": ["y", "z"], "name": "bar"},
+            })),
+        );
+
+        let mut overwritten = event.clone();
+        parser::parse("merge(.foo, .bar, true)")
+            .unwrap()
+            .execute(&mut overwritten)
+            .unwrap();
+        assert_eq!(
+            overwritten.as_log().get("foo"),
+            Some(&Value::from(serde_json::json!({
+                "tags": ["b", "c"],
+                "nested": {"tags": ["y", "z"], "name": "bar"},
+            })))
+        );
+
+        parser::parse("merge(.foo, .bar, true, union_arrays = true)")
+            .unwrap()
+            .execute(&mut event)
+            .unwrap();
+        assert_eq!(
+            event.as_log().get("foo"),
+            Some(&Value::from(serde_json::json!({
+                "tags": ["a", "b", "c"],
+                "nested": {"tags": ["x", "y", "z"], "name": "bar"},
+            })))
+        );
+    }
+
+    #[test]
+    fn merge_literal_map() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert(
+            "foo",
+            Value::from(serde_json::json!({
+                "env": "dev",
+                "labels": {"team": "core", "tier": "web"},
+            })),
+        );
+        event.as_mut_log().insert(
+            "defaults",
+            Value::from(serde_json::json!({
+                "region": "us-east-1",
+                "labels": {"tier": "backend"},
+            })),
+        );
+        let literal = r#"{"region": "us-east-1", "labels": {"tier": "backend"}}"#;
+
+        for (deep, exp) in vec![
+            (
+                false,
+                serde_json::json!({
+                    "env": "dev",
+                    "region": "us-east-1",
+                    "labels": {"tier": "backend"},
+                }),
+            ),
+            (
+                true,
+                serde_json::json!({
+                    "env": "dev",
+                    "region": "us-east-1",
+                    "labels": {"team": "core", "tier": "backend"},
+                }),
+            ),
+        ] {
+            let mut from_literal = event.clone();
+            parser::parse(&format!("merge(.foo, {}, {})", literal, deep))
+                .unwrap()
+                .execute(&mut from_literal)
+                .unwrap();
+            assert_eq!(from_literal.as_log().get("foo"), Some(&Value::from(exp)));
+
+            let mut from_path = event.clone();
+            parser::parse(&format!("merge(.foo, .defaults, {})", deep))
+                .unwrap()
+                .execute(&mut from_path)
+                .unwrap();
+            assert_eq!(from_literal, from_path);
+        }
+
+        assert_eq!(
+            parser::parse(r#"merge(.foo, "region")"#)
+                .unwrap()
+                .execute(&mut event),
+            Err("failed to apply mapping 0: parameters passed to merge are non-map values".into())
+        );
+    }
+
+    #[test]
+    fn if_statement_chain() {
+        use query::Literal;
+
+        let chain = |first: Value, second: Value| {
+            IfStatement::chain(
+                vec![
+                    (
+                        Box::new(Literal::from(first)) as Box<dyn query::Function>,
+                        Box::new(Assignment::new(
+                            "branch".to_owned(),
+                            Box::new(Literal::from(Value::from(1))),
+                        )) as Box<dyn Function>,
+                    ),
+                    (
+                        Box::new(Literal::from(second)),
+                        Box::new(Assignment::new(
+                            "branch".to_owned(),
+                            Box::new(Literal::from(Value::from(2))),
+                        )),
+                    ),
+                ],
+                None,
+            )
+        };
+
+        let cases = vec![
+            (
+                IfStatement {
+                    else_statement: Some(Box::new(Assignment::new(
+                        "branch".to_owned(),
+                        Box::new(Literal::from(Value::from(3))),
+                    ))),
+                    ..chain(Value::Boolean(false), Value::Boolean(false))
+                },
+                Ok(Some(Value::from(3))),
+            ),
+            (
+                chain(Value::Boolean(true), Value::Boolean(true)),
+                Ok(Some(Value::from(1))),
+            ),
+            (
+                chain(Value::Boolean(false), Value::Boolean(true)),
+                Ok(Some(Value::from(2))),
+            ),
+            (
+                chain(Value::Boolean(false), Value::Boolean(false)),
+                Ok(None),
+            ),
+            (
+                chain(Value::Boolean(true), Value::from("not a boolean")),
+                Ok(Some(Value::from(1))),
+            ),
+            (
+                chain(Value::Boolean(false), Value::from("not a boolean")),
+                Err("query returned non-boolean value".to_string()),
+            ),
+        ];
+
+        for (statement, exp) in cases {
+            let mut event = Event::from("");
+            let result = statement
+                .apply(&mut event)
+                .map(|_| event.as_log().get("branch").cloned());
+
+            assert_eq!(result, exp);
+        }
+    }
+
+    #[test]
+    fn merge_maps_deeply_nested() {
+        const DEPTH: usize = 50_000;
+
+        fn nested(leaf: &str) -> BTreeMap<String, Value> {
+            let mut map = BTreeMap::new();
+            map.insert(leaf.to_owned(), Value::from(leaf.to_owned()));
+
+            for _ in 0..DEPTH {
+                let mut parent = BTreeMap::new();
+                parent.insert("child".to_owned(), Value::Map(map));
+                map = parent;
+            }
+
+            map
+        }
+
+        // Dropping or comparing the maps recursively would overflow the
+        // stack, so they are unnested by hand.
+        fn unnest(mut map: BTreeMap<String, Value>) -> (usize, BTreeMap<String, Value>) {
+            let mut depth = 0;
+            while let Some(Value::Map(child)) = map.remove("child") {
+                map = child;
+                depth += 1;
+            }
+            (depth, map)
+        }
+
+        let mut map1 = nested("foo");
+        let map2 = nested("bar");
+
+        merge_maps(&mut map1, &map2, true, false).unwrap();
+        unnest(map2);
+
+        let (depth, leaf) = unnest(map1);
+        assert_eq!(depth, DEPTH);
+        assert_eq!(leaf.get("foo"), Some(&Value::from("foo")));
+        assert_eq!(leaf.get("bar"), Some(&Value::from("bar")));
+    }
+
+    #[test]
+    fn coverage() {
+        let mapping = parser::parse(
+            r#".foo = "bar"
+               if .x == 1 {
+                 .y = 1
+               } else {
+                 if .x == 2 { .y = 2 }
+               }
+               if .x == 3 { .y = 3 }"#,
+        )
+        .unwrap()
+        .with_coverage();
+
+        for x in &[1, 1, 2, 4] {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("x", Value::from(*x));
+            mapping.execute(&mut event).unwrap();
+        }
+
+        let coverage = mapping.coverage().unwrap();
+        let branches = coverage
+            .branches()
+            .iter()
+            .map(|(location, branch)| (location.as_str(), branch.then_taken, branch.else_taken))
+            .collect::<Vec<_>>();
+
+        assert_eq!(branches, vec![("1", 2, 2), ("1.else", 1, 1), ("2", 0, 4)]);
+        assert_eq!(parser::parse(".foo = 1").unwrap().coverage(), None);
+    }
+
+    #[test]
+    fn shared_across_threads() {
+        let mapping = std::sync::Arc::new(
+            parser::parse(
+                r#".bar = .foo * 2
+                   if .bar > 10 { .big = true } else { .big = false }
+                   log(.bar, level = trace)"#,
+            )
+            .unwrap()
+            .with_coverage(),
+        );
+
+        let handles = (0..8)
+            .map(|i| {
+                let mapping = std::sync::Arc::clone(&mapping);
+                std::thread::spawn(move || {
+                    (0..100)
+                        .map(|_| {
+                            let mut event = Event::from("");
+                            event.as_mut_log().insert("foo", Value::from(i));
+                            mapping.execute(&mut event).unwrap();
+                            event.as_log().get("big").cloned()
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for (i, handle) in handles.into_iter().enumerate() {
+            let big = Value::Boolean(i * 2 > 10);
+            assert!(handle
+                .join()
+                .unwrap()
+                .iter()
+                .all(|v| v.as_ref() == Some(&big)));
+        }
+
+        let coverage = mapping.coverage().unwrap();
+        assert_eq!(
+            coverage.branches().get("1"),
+            Some(&BranchCoverage {
+                then_taken: 200,
+                else_taken: 600
+            })
+        );
+    }
+
+    #[test]
+    fn explain() {
+        let mapping = parser::parse(
+            r#".foo = "bar"
+               del(.bar, .baz)
+               if .x == 1 {
+                 .y = !.z
+               } else {
+                 merge(.a, .(b | c), true)
+               }
+               if .x > 5 + .y { log(.msg, level = warn) }
+               .qux = split(.foo, /o+/i)"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            mapping.explain(),
+            vec![
+                r#"assign .foo = literal "bar""#,
+                "delete .bar, .baz",
+                "if .x == literal 1 then assign .y = !.z else merge .(b | c) into .a (deep: literal true)",
+                "if .x > literal 5 + .y then log .msg at warn level else do nothing",
+                "assign .qux = split(.foo, regex /o+/i)",
+            ]
+        );
+    }
 }
@@ -7,18 +7,26 @@ use crate::{
             self,
             arithmetic::Arithmetic,
             arithmetic::Operator,
-            function::{Argument, ArgumentList, FunctionSignature, NotFn},
+            bound::Bound,
+            coalesce::Coalesce,
+            function::{
+                Argument, ArgumentList, DelFn, FirstMatchingFn, FunctionSignature, IndexWhereFn,
+                MergeAllFn, NotFn, ReduceFn,
+            },
             path::Path as QueryPath,
             query_value::QueryValue,
             regex::Regex,
-            Literal,
+            Array, Literal, Map,
         },
-        Assignment, Deletion, Function, IfStatement, LogFn, LogLevel, Mapping, MergeFn, Noop,
-        OnlyFields, Result,
+        Abort, ApplyIfPresentFn, Assignment, CoerceTimestampsFn, CoerceToSchemaFn, Deletion,
+        Flatten, Function, IfStatement, IncrementFn, LogFn, LogLevel, MapKeysFn, MapValuesFn,
+        Mapping, MappingSource, MergeFn, Noop, OnlyFields, RedactPathsFn, RemoveNullsFn, Rename,
+        Result, SnakeCaseKeysFn, StatementSource, TimestampRule,
     },
+    types::Conversion,
 };
 use pest::{
-    error::ErrorVariant,
+    error::{Error, ErrorVariant, InputLocation},
     iterators::{Pair, Pairs},
     Parser,
 };
@@ -62,26 +70,25 @@ fn target_path_from_pair(pair: Pair<Rule>) -> Result<String> {
 }
 
 fn quoted_path_from_pair(pair: Pair<Rule>) -> Result<String> {
-    let (first, mut other) = split_inner_rules_from_pair(pair)?;
+    let (first, other) = split_inner_rules_from_pair(pair)?;
     let base = inner_quoted_string_escaped_from_pair(first)?;
-    Ok(match other.next() {
-        Some(pair) => base + pair.as_str(),
-        None => base,
-    })
+    Ok(other.fold(base, |path, index| path + index.as_str()))
 }
 
 fn path_segments_from_pair(pair: Pair<Rule>) -> Result<Vec<Vec<String>>> {
     let mut segments = Vec::new();
     for segment in pair.into_inner() {
         match segment.as_rule() {
-            Rule::path_segment => segments.push(vec![segment.as_str().to_string()]),
-            Rule::quoted_path_segment => segments.push(vec![quoted_path_from_pair(segment)?]),
+            Rule::query_path_segment => segments.push(vec![segment.as_str().to_string()]),
+            Rule::quoted_query_path_segment => segments.push(vec![quoted_path_from_pair(segment)?]),
             Rule::path_coalesce => {
                 let mut options = Vec::new();
                 for option in segment.into_inner() {
                     match option.as_rule() {
-                        Rule::path_segment => options.push(option.as_str().to_string()),
-                        Rule::quoted_path_segment => options.push(quoted_path_from_pair(option)?),
+                        Rule::query_path_segment => options.push(option.as_str().to_string()),
+                        Rule::quoted_query_path_segment => {
+                            options.push(quoted_path_from_pair(option)?)
+                        }
                         _ => unexpected_parser_sytax!(option),
                     }
                 }
@@ -93,9 +100,22 @@ fn path_segments_from_pair(pair: Pair<Rule>) -> Result<Vec<Vec<String>>> {
     Ok(segments)
 }
 
+fn query_arithmetic_power_from_pairs(mut pairs: Pairs<Rule>) -> Result<Box<dyn query::Function>> {
+    let left = query_from_pair(pairs.next().ok_or(TOKEN_ERR)?)?;
+
+    match pairs.nth(1) {
+        Some(pair) => Ok(Box::new(Arithmetic::new(
+            left,
+            query_arithmetic_power_from_pairs(pair.into_inner())?,
+            Operator::Power,
+        ))),
+        None => Ok(left),
+    }
+}
+
 fn query_arithmetic_product_from_pairs(mut pairs: Pairs<Rule>) -> Result<Box<dyn query::Function>> {
-    let pair = pairs.next().ok_or(TOKEN_ERR)?;
-    let mut left = query_from_pair(pair)?;
+    let inner_pairs = pairs.next().ok_or(TOKEN_ERR)?.into_inner();
+    let mut left = query_arithmetic_power_from_pairs(inner_pairs)?;
     let mut op = Operator::Multiply;
 
     for pair in pairs {
@@ -109,7 +129,11 @@ fn query_arithmetic_product_from_pairs(mut pairs: Pairs<Rule>) -> Result<Box<dyn
                 };
             }
             _ => {
-                left = Box::new(Arithmetic::new(left, query_from_pair(pair)?, op.clone()));
+                left = Box::new(Arithmetic::new(
+                    left,
+                    query_arithmetic_power_from_pairs(pair.into_inner())?,
+                    op.clone(),
+                ));
             }
         }
     }
@@ -243,6 +267,12 @@ fn function_arguments_from_pairs(
             })?;
     }
 
+    check_arguments(&arguments, signature)?;
+
+    Ok(arguments)
+}
+
+fn check_arguments(arguments: &ArgumentList, signature: FunctionSignature) -> Result<()> {
     // check invalid arity
     if arguments.len() > signature.parameters().len() {
         return Err(format!(
@@ -278,9 +308,89 @@ fn function_arguments_from_pairs(
                 k,
                 signature.as_str()
             ))
-        })?;
+        })
+}
 
-    Ok(arguments)
+/// Builds a function reference, such as the predicate of `index_where`, which
+/// reads its leading arguments from the `bound` values, in order.
+fn predicate_from_pair(pair: Pair<Rule>, bound: &[Bound]) -> Result<Box<dyn query::Function>> {
+    let mut pairs = pair.into_inner();
+    let name = pairs.next().ok_or(TOKEN_ERR)?.as_str();
+    let signature = FunctionSignature::from_str(name)?;
+    let parameters = signature.parameters();
+
+    // The bound values are passed as the leading arguments, followed by any
+    // other arguments given.
+    let mut arguments = ArgumentList::new();
+    for (index, value) in bound.iter().enumerate() {
+        let resolver = Box::new(*value);
+        let parameter = parameters.get(index).cloned().ok_or(format!(
+            "function '{}' takes fewer than {} arguments",
+            name,
+            bound.len()
+        ))?;
+        let keyword = parameter.keyword.to_owned();
+        arguments.push(Argument::new(resolver, parameter), Some(keyword));
+    }
+
+    for (index, pair) in pairs.enumerate() {
+        let index = index + bound.len();
+        let parameter = parameters.get(index).cloned().ok_or(format!(
+            "unknown positional argument '{}' for function: '{}'",
+            index, name
+        ))?;
+        let keyword = parameter.keyword.to_owned();
+        let argument = Argument::new(argument_item_from_pair(pair)?, parameter);
+        arguments.push(argument, Some(keyword));
+    }
+
+    check_arguments(&arguments, signature)?;
+    signature.into_boxed_function(arguments)
+}
+
+fn index_where_from_pairs(mut pairs: Pairs<Rule>) -> Result<Box<dyn query::Function>> {
+    let value = query_arithmetic_from_pair(pairs.next().ok_or(TOKEN_ERR)?)?;
+    let element = Bound::new("element");
+    let predicate = predicate_from_pair(pairs.next().ok_or(TOKEN_ERR)?, &[element])?;
+
+    Ok(Box::new(IndexWhereFn::new(value, element, predicate)))
+}
+
+fn first_matching_from_pairs(pairs: Pairs<Rule>) -> Result<Box<dyn query::Function>> {
+    let mut values = Vec::new();
+    let element = Bound::new("element");
+    let mut predicate = None;
+
+    for pair in pairs {
+        match pair.as_rule() {
+            Rule::dot_path => values
+                .push(Box::new(QueryPath::from(path_segments_from_pair(pair)?))
+                    as Box<dyn query::Function>),
+            Rule::predicate => predicate = Some(predicate_from_pair(pair, &[element])?),
+            _ => unexpected_parser_sytax!(pair),
+        }
+    }
+
+    Ok(Box::new(FirstMatchingFn::new(
+        values,
+        element,
+        predicate.ok_or(TOKEN_ERR)?,
+    )))
+}
+
+fn reduce_from_pairs(mut pairs: Pairs<Rule>) -> Result<Box<dyn query::Function>> {
+    let value = query_arithmetic_from_pair(pairs.next().ok_or(TOKEN_ERR)?)?;
+    let (accumulator, element) = (Bound::new("accumulator"), Bound::new("element"));
+    let reducer = predicate_from_pair(pairs.next().ok_or(TOKEN_ERR)?, &[accumulator, element])?;
+    let seed = query_arithmetic_from_pair(pairs.next().ok_or(TOKEN_ERR)?)?;
+
+    Ok(Box::new(ReduceFn::new(
+        value,
+        accumulator,
+        element,
+        reducer,
+        seed,
+    )))
 }
 
 fn positional_item_from_pair(
@@ -384,6 +494,7 @@ fn inner_quoted_string_escaped_from_pair(pair: Pair<Rule>) -> Result<String> {
             match c {
                 '\\' => escaped_chars.push(c),
                 'n' => escaped_chars.push('\n'),
+                'r' => escaped_chars.push('\r'),
                 't' => escaped_chars.push('\t'),
                 '"' => escaped_chars.push('"'),
                 // This isn't reachable currently due to the explicit list of
@@ -402,6 +513,30 @@ fn inner_quoted_string_escaped_from_pair(pair: Pair<Rule>) -> Result<String> {
     Ok(escaped_chars.into_iter().collect())
 }
 
+fn map_from_pair(pair: Pair<Rule>) -> Result<Box<dyn query::Function>> {
+    let fields = pair
+        .into_inner()
+        .map(|entry| {
+            let (key, mut other) = split_inner_rules_from_pair(entry)?;
+            let key =
+                inner_quoted_string_escaped_from_pair(key.into_inner().next().ok_or(TOKEN_ERR)?)?;
+            let value = query_arithmetic_from_pair(other.next().ok_or(TOKEN_ERR)?)?;
+            Ok((key, value))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Box::new(Map::new(fields)))
+}
+
+fn array_from_pair(pair: Pair<Rule>) -> Result<Box<dyn query::Function>> {
+    let elements = pair
+        .into_inner()
+        .map(query_arithmetic_from_pair)
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Box::new(Array::new(elements)))
+}
+
 fn query_from_pair(pair: Pair<Rule>) -> Result<Box<dyn query::Function>> {
     Ok(match pair.as_rule() {
         Rule::not_operator => {
@@ -423,35 +558,69 @@ fn query_from_pair(pair: Pair<Rule>) -> Result<Box<dyn query::Function>> {
             Box::new(Literal::from(Value::from(v)))
         }
         Rule::dot_path => Box::new(QueryPath::from(path_segments_from_pair(pair)?)),
+        Rule::map => map_from_pair(pair)?,
+        Rule::array => array_from_pair(pair)?,
         Rule::group => query_arithmetic_from_pair(pair.into_inner().next().ok_or(TOKEN_ERR)?)?,
+        Rule::index_where => index_where_from_pairs(pair.into_inner())?,
+        Rule::first_matching => first_matching_from_pairs(pair.into_inner())?,
+        Rule::reduce => reduce_from_pairs(pair.into_inner())?,
+        Rule::coalesce => Box::new(Coalesce::new(
+            pair.into_inner()
+                .map(query_arithmetic_from_pair)
+                .collect::<Result<Vec<_>>>()?,
+        )),
+        Rule::merge_all => Box::new(MergeAllFn::new(
+            pair.into_inner()
+                .map(|pair| {
+                    path_segments_from_pair(pair)
+                        .map(|path| Box::new(QueryPath::from(path)) as Box<dyn query::Function>)
+                })
+                .collect::<Result<Vec<_>>>()?,
+        )),
         Rule::query_function => query_function_from_pairs(pair.into_inner())?,
         _ => unexpected_parser_sytax!(pair),
     })
 }
 
+/// Builds a single chain from the branches of `if A {} else if B {} else {}`,
+/// rather than nesting a conditional for each `else if`.
 fn if_statement_from_pairs(mut pairs: Pairs<Rule>) -> Result<Box<dyn Function>> {
-    let query = query_arithmetic_from_pair(pairs.next().ok_or(TOKEN_ERR)?)?;
-
-    let first = statement_from_pair(pairs.next().ok_or(TOKEN_ERR)?)?;
+    let mut branches = Vec::new();
+    let mut else_statement: Box<dyn Function> = Box::new(Noop {});
 
-    let second = match pairs.next() {
-        Some(pair) => statement_from_pair(pair)?,
-        None => Box::new(Noop {}),
-    };
+    while let Some(pair) = pairs.next() {
+        match pair.as_rule() {
+            Rule::query_arithmetic_boolean => {
+                let query = query_arithmetic_from_pair(pair)?;
+                let statement = statement_from_pair(pairs.next().ok_or(TOKEN_ERR)?)?;
+                branches.push((query, statement));
+            }
+            _ => else_statement = statement_from_pair(pair)?,
+        }
+    }
 
-    Ok(Box::new(IfStatement::new(query, first, second)))
+    Ok(Box::new(IfStatement::chain(branches, Some(else_statement))))
 }
 
 fn merge_function_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
     let (first, mut other) = split_inner_rules_from_pair(pair)?;
     let to_path = target_path_from_pair(first)?;
     let query2 = query_arithmetic_from_pair(other.next().ok_or(TOKEN_ERR)?)?;
-    let deep = match other.next() {
-        None => None,
-        Some(pair) => Some(query_arithmetic_from_pair(pair)?),
-    };
+    let mut deep = None;
+    let mut union_arrays = None;
 
-    Ok(Box::new(MergeFn::new(to_path, query2, deep)))
+    for pair in other {
+        match pair.as_rule() {
+            Rule::merge_union_arrays => {
+                union_arrays = Some(query_arithmetic_from_pair(
+                    pair.into_inner().next().ok_or(TOKEN_ERR)?,
+                )?)
+            }
+            _ => deep = Some(query_arithmetic_from_pair(pair)?),
+        }
+    }
+
+    Ok(Box::new(MergeFn::new(to_path, query2, deep, union_arrays)))
 }
 
 fn log_function_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
@@ -465,12 +634,191 @@ fn log_function_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
     Ok(Box::new(LogFn::new(msg, level)))
 }
 
+fn increment_function_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
+    let (first, mut other) = split_inner_rules_from_pair(pair)?;
+    let path = target_path_from_pair(first)?;
+    let delta = query_arithmetic_from_pair(other.next().ok_or(TOKEN_ERR)?)?;
+
+    Ok(Box::new(IncrementFn::new(path, delta)))
+}
+
+fn rename_function_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
+    let (first, mut other) = split_inner_rules_from_pair(pair)?;
+    let from = target_path_from_pair(first)?;
+    let to = target_path_from_pair(other.next().ok_or(TOKEN_ERR)?)?;
+
+    Ok(Box::new(Rename::new(from, to)))
+}
+
+fn only_fields_function_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
+    let paths = pair
+        .into_inner()
+        .map(|pair| {
+            let (first, mut other) = split_inner_rules_from_pair(pair)?;
+            let path = target_path_from_pair(first)?;
+            Ok(match other.next() {
+                Some(wildcard) => path + wildcard.as_str(),
+                None => path,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Box::new(OnlyFields::new(paths)))
+}
+
+fn flatten_function_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
+    let (first, mut other) = split_inner_rules_from_pair(pair)?;
+    let path = target_path_from_pair(first)?;
+    let separator = match other.next() {
+        Some(pair) => {
+            inner_quoted_string_escaped_from_pair(pair.into_inner().next().ok_or(TOKEN_ERR)?)?
+        }
+        None => ".".to_owned(),
+    };
+
+    Ok(Box::new(Flatten::new(path, separator)))
+}
+
+fn redact_paths_function_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
+    let mut paths = Vec::new();
+    let mut mask = None;
+
+    for pair in pair.into_inner() {
+        match pair.as_rule() {
+            Rule::target_path => paths.push(target_path_from_pair(pair)?),
+            _ => mask = Some(query_arithmetic_from_pair(pair)?),
+        }
+    }
+
+    Ok(Box::new(RedactPathsFn::new(paths, mask.ok_or(TOKEN_ERR)?)))
+}
+
+fn apply_if_present_function_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
+    let (first, mut other) = split_inner_rules_from_pair(pair)?;
+    let path = target_path_from_pair(first)?;
+    let name = other.next().ok_or(TOKEN_ERR)?.as_str();
+    let signature = FunctionSignature::from_str(name)?;
+
+    // The field is passed to the function as its first argument, so any
+    // other arguments have to be optional.
+    let parameter = signature
+        .parameters()
+        .first()
+        .cloned()
+        .ok_or(format!("function '{}' takes no arguments", name))?;
+    if signature.parameters().iter().skip(1).any(|p| p.required) {
+        return Err(format!(
+            "function '{}' requires more than one argument and can't be applied to a field",
+            name
+        ));
+    }
+
+    let mut arguments = ArgumentList::new();
+    let keyword = parameter.keyword.to_owned();
+    let resolver = Box::new(QueryPath::from(path.as_str()));
+    arguments.push(Argument::new(resolver, parameter), Some(keyword));
+
+    let function = signature.into_boxed_function(arguments)?;
+
+    Ok(Box::new(ApplyIfPresentFn::new(path, function)))
+}
+
+fn coerce_to_schema_function_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
+    let mut schema = Vec::new();
+    let mut dropped_path = None;
+
+    for pair in pair.into_inner() {
+        match pair.as_rule() {
+            Rule::schema_field => {
+                let (first, mut other) = split_inner_rules_from_pair(pair)?;
+                let path = target_path_from_pair(first)?;
+                let conversion = inner_quoted_string_escaped_from_pair(
+                    other
+                        .next()
+                        .ok_or(TOKEN_ERR)?
+                        .into_inner()
+                        .next()
+                        .ok_or(TOKEN_ERR)?,
+                )?
+                .parse::<Conversion>()
+                .map_err(|err| err.to_string())?;
+                schema.push((path, conversion));
+            }
+            Rule::target_path => dropped_path = Some(target_path_from_pair(pair)?),
+            _ => unexpected_parser_sytax!(pair),
+        }
+    }
+
+    Ok(Box::new(CoerceToSchemaFn::new(schema, dropped_path)))
+}
+
+fn coerce_timestamps_function_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
+    let mut rules = Vec::new();
+
+    for pair in pair.into_inner() {
+        let (first, mut other) = split_inner_rules_from_pair(pair)?;
+        let path = match first.as_rule() {
+            Rule::target_path => target_path_from_pair(first)?,
+            _ => {
+                let path = inner_quoted_string_escaped_from_pair(
+                    first.into_inner().next().ok_or(TOKEN_ERR)?,
+                )?;
+                path.strip_prefix('.').unwrap_or(&path).to_owned()
+            }
+        };
+        let rule = inner_quoted_string_escaped_from_pair(
+            other
+                .next()
+                .ok_or(TOKEN_ERR)?
+                .into_inner()
+                .next()
+                .ok_or(TOKEN_ERR)?,
+        )?;
+        rules.push((path, TimestampRule::parse(&rule)?));
+    }
+
+    Ok(Box::new(CoerceTimestampsFn::new(rules)))
+}
+
 fn function_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
     match pair.as_rule() {
         Rule::deletion => Ok(Box::new(Deletion::new(paths_from_pair(pair)?))),
-        Rule::only_fields => Ok(Box::new(OnlyFields::new(paths_from_pair(pair)?))),
+        Rule::only_fields => only_fields_function_from_pair(pair),
         Rule::merge => merge_function_from_pair(pair),
         Rule::log => log_function_from_pair(pair),
+        Rule::coerce_to_schema => coerce_to_schema_function_from_pair(pair),
+        Rule::coerce_timestamps => coerce_timestamps_function_from_pair(pair),
+        Rule::map_values => {
+            let (first, mut other) = split_inner_rules_from_pair(pair)?;
+            let path = target_path_from_pair(first)?;
+            let value = Bound::new("value");
+            let function = predicate_from_pair(other.next().ok_or(TOKEN_ERR)?, &[value])?;
+            Ok(Box::new(MapValuesFn::new(path, value, function)))
+        }
+        Rule::map_keys => {
+            let (first, mut other) = split_inner_rules_from_pair(pair)?;
+            let path = target_path_from_pair(first)?;
+            let key = Bound::new("key");
+            let function = predicate_from_pair(other.next().ok_or(TOKEN_ERR)?, &[key])?;
+            Ok(Box::new(MapKeysFn::new(path, key, function)))
+        }
+        Rule::increment => increment_function_from_pair(pair),
+        Rule::redact_paths => redact_paths_function_from_pair(pair),
+        Rule::apply_if_present => apply_if_present_function_from_pair(pair),
+        Rule::rename => rename_function_from_pair(pair),
+        Rule::abort => Ok(Box::new(Abort {})),
+        Rule::flatten => flatten_function_from_pair(pair),
+        Rule::remove_nulls => {
+            let pair = pair.into_inner().next().ok_or(TOKEN_ERR)?;
+            let path = match pair.as_rule() {
+                Rule::root_path => None,
+                _ => Some(target_path_from_pair(pair)?),
+            };
+            Ok(Box::new(RemoveNullsFn::new(path)))
+        }
+        Rule::snake_case_keys => Ok(Box::new(SnakeCaseKeysFn::new(target_path_from_pair(
+            pair.into_inner().next().ok_or(TOKEN_ERR)?,
+        )?))),
         _ => unexpected_parser_sytax!(pair),
     }
 }
@@ -486,7 +834,13 @@ fn statement_from_pair(pair: Pair<Rule>) -> Result<Box<dyn Function>> {
         Rule::assignment => {
             let mut inner_rules = pair.into_inner();
             let path = target_path_from_pair(inner_rules.next().ok_or(TOKEN_ERR)?)?;
-            let query = query_arithmetic_from_pair(inner_rules.next().ok_or(TOKEN_ERR)?)?;
+            let pair = inner_rules.next().ok_or(TOKEN_ERR)?;
+            let query: Box<dyn query::Function> = match pair.as_rule() {
+                Rule::del_query => Box::new(DelFn::new(target_path_from_pair(
+                    pair.into_inner().next().ok_or(TOKEN_ERR)?,
+                )?)),
+                _ => query_arithmetic_from_pair(pair)?,
+            };
             Ok(Box::new(Assignment::new(path, query)))
         }
         Rule::function => function_from_pair(pair.into_inner().next().ok_or(TOKEN_ERR)?),
@@ -502,24 +856,84 @@ fn split_inner_rules_from_pair(pair: Pair<Rule>) -> Result<(Pair<Rule>, Pairs<Ru
     Ok((first, inner))
 }
 
-fn mapping_from_pairs(pairs: Pairs<Rule>) -> Result<Mapping> {
+/// Returns the comments found in a stretch of source between two statements.
+fn comments_from_source(source: &str) -> Vec<String> {
+    source
+        .lines()
+        .filter_map(|line| {
+            // Comments can follow a statement on the same line, so anything
+            // before the comment marker is skipped.
+            line.find('#').map(|i| line[i..].trim_end().to_owned())
+        })
+        .collect()
+}
+
+fn mapping_from_pairs(pairs: Pairs<Rule>, input: &str, options: ParseOptions) -> Result<Mapping> {
     let mut assignments = Vec::<Box<dyn Function>>::new();
+    let mut statements = Vec::new();
+    let mut position = 0;
+
     for pair in pairs {
         match pair.as_rule() {
             // Rules expected at the root of a mapping statement.
             Rule::assignment | Rule::function | Rule::if_statement => {
-                assignments.push(statement_from_pair(pair)?);
+                if options.preserve_comments {
+                    let span = pair.as_span();
+                    statements.push(StatementSource::new(
+                        comments_from_source(&input[position..span.start()]),
+                        span.as_str().trim_end().to_owned(),
+                    ));
+                    position = span.end();
+                }
+
+                // Errors found while building a statement are reported at
+                // the position of the whole statement.
+                let span = pair.as_span();
+                let statement = statement_from_pair(pair).map_err(|message| {
+                    parse_error(Error::new_from_span(
+                        ErrorVariant::CustomError { message },
+                        span,
+                    ))
+                })?;
+                assignments.push(statement);
             }
             Rule::EOI => (),
             _ => unexpected_parser_sytax!(pair),
         }
     }
-    Ok(Mapping::new(assignments))
+
+    let mapping = Mapping::new(assignments);
+    if options.validate {
+        mapping.validate()?;
+    }
+
+    if options.preserve_comments {
+        let trailing_comments = comments_from_source(&input[position..]);
+        Ok(mapping.with_source(MappingSource::new(statements, trailing_comments)))
+    } else {
+        Ok(mapping)
+    }
+}
+
+/// Options that control how a mapping is parsed.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ParseOptions {
+    /// Retain comments and the original source of each statement, so that the
+    /// mapping can be written back out using `Mapping::to_source`.
+    pub preserve_comments: bool,
+
+    /// Reject mappings with type errors that are certain to occur whenever
+    /// they are executed, see `Mapping::validate`.
+    pub validate: bool,
 }
 
 pub fn parse(input: &str) -> Result<Mapping> {
+    parse_with_options(input, ParseOptions::default())
+}
+
+pub fn parse_with_options(input: &str, options: ParseOptions) -> Result<Mapping> {
     match MappingParser::parse(Rule::mapping, input) {
-        Ok(a) => mapping_from_pairs(a),
+        Ok(a) => mapping_from_pairs(a, input, options),
         // We need to do a bit of manual pruning of the error here as any
         // non-silent rule will be included in the list of candidates for a
         // parse error. Since we have several different sets of arithmetic
@@ -536,7 +950,8 @@ pub fn parse(input: &str) -> Result<Mapping> {
                     match positives[i] {
                         Rule::arithmetic_operator_boolean
                         | Rule::arithmetic_operator_compare
-                        | Rule::arithmetic_operator_sum => {
+                        | Rule::arithmetic_operator_sum
+                        | Rule::arithmetic_operator_power => {
                             positives.remove(i);
                         }
                         _ => {
@@ -549,15 +964,39 @@ pub fn parse(input: &str) -> Result<Mapping> {
                 Rule::arithmetic_operator_product => "operator".to_owned(),
                 _ => format!("{:?}", rule),
             });
-            Err(format!("mapping parse error\n{}", error))
+            Err(parse_error(error))
         }
     }
 }
 
+/// Formats an error with its position in the source, as a byte offset and as
+/// a line and column, followed by the offending source.
+fn parse_error(error: Error<Rule>) -> String {
+    let offset = match error.location {
+        InputLocation::Pos(offset) => offset,
+        InputLocation::Span((offset, _)) => offset,
+    };
+
+    format!("mapping parse error at byte {}\n{}", offset, error)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mapping::query::function::SplitFn;
+    use crate::mapping::query::function::{
+        AddFn, AutoParseFn, ChunkFn, CoerceArrayFn, ContainsFn, DecodeFlagsFn, DifferenceFn,
+        DowncaseFn, EncodeJsonFn, EndOfFn, EndsWithFn, EnsureTypeFn, ExistsFn, ExtractNumbersFn,
+        FirstFn, FlattenOneFn, ForceUtf8Fn, FormatBytesFn, FormatTimestampFn, GroupConsecutiveFn,
+        HttpStatusClassFn, InferLogLevelFn, IntersectFn, IsNullFn, IsValidUtf8Fn, JsonEscapeFn,
+        LastFn, LengthFn, LuhnValidFn, MapToTagArrayFn, MaskEmailFn, MonthNameFn,
+        NormalizeBooleanFn, NormalizeFn, NormalizeMacFn, NormalizeNewlinesFn, NormalizeNumericFn,
+        NowFn, ParseAnsiFn, ParseBytesSizeFn, ParseCookiesFn, ParseCsvFn, ParseDurationFn,
+        ParseIso8601DurationFn, ParseJsonArrayFn, ParseJsonFn, ParseKeyValueFn, ParseRegexFn,
+        ParseSemverFn, ParseTimestampFn, ParseUrlFn, ParseUserAgentFn, QuantizeTimestampFn,
+        ReplaceFn, RoundFn, SampleFn, SemverGteFn, SplitFn, StartOfFn, StripPrefixFn,
+        StripSuffixFn, ToBoolFn, ToFloatFn, ToIntFn, TransliterateFn, TrimFn, TruncateWordsFn,
+        UpcaseFn, WeekdayNameFn, WindowsFn,
+    };
 
     #[test]
     fn check_parser() {
@@ -874,6 +1313,76 @@ mod tests {
                     Box::new(Noop {}),
                 ))]),
             ),
+            (
+                r#"if .status >= 500 {
+                    .level = "error"
+                  } else if .status >= 400 {
+                    .level = "warn"
+                  } else if .slow {
+                    .level = "notice"
+                  } else {
+                    .level = "info"
+                  }"#,
+                Mapping::new(vec![Box::new(IfStatement::chain(
+                    vec![
+                        (
+                            Box::new(Arithmetic::new(
+                                Box::new(QueryPath::from("status")),
+                                Box::new(Literal::from(Value::from(500))),
+                                Operator::GreaterOrEqual,
+                            )) as Box<dyn query::Function>,
+                            Box::new(Assignment::new(
+                                "level".to_string(),
+                                Box::new(Literal::from(Value::from("error"))),
+                            )) as Box<dyn Function>,
+                        ),
+                        (
+                            Box::new(Arithmetic::new(
+                                Box::new(QueryPath::from("status")),
+                                Box::new(Literal::from(Value::from(400))),
+                                Operator::GreaterOrEqual,
+                            )),
+                            Box::new(Assignment::new(
+                                "level".to_string(),
+                                Box::new(Literal::from(Value::from("warn"))),
+                            )),
+                        ),
+                        (
+                            Box::new(QueryPath::from("slow")),
+                            Box::new(Assignment::new(
+                                "level".to_string(),
+                                Box::new(Literal::from(Value::from("notice"))),
+                            )),
+                        ),
+                    ],
+                    Some(Box::new(Assignment::new(
+                        "level".to_string(),
+                        Box::new(Literal::from(Value::from("info"))),
+                    ))),
+                ))]),
+            ),
+            (
+                r#"if .a { .x = 1 } else if .b { .x = 2 }"#,
+                Mapping::new(vec![Box::new(IfStatement::chain(
+                    vec![
+                        (
+                            Box::new(QueryPath::from("a")) as Box<dyn query::Function>,
+                            Box::new(Assignment::new(
+                                "x".to_string(),
+                                Box::new(Literal::from(Value::from(1))),
+                            )) as Box<dyn Function>,
+                        ),
+                        (
+                            Box::new(QueryPath::from("b")),
+                            Box::new(Assignment::new(
+                                "x".to_string(),
+                                Box::new(Literal::from(Value::from(2))),
+                            )),
+                        ),
+                    ],
+                    Some(Box::new(Noop {})),
+                ))]),
+            ),
             // function: only_fields
             (
                 "only_fields(.foo)",
@@ -892,6 +1401,7 @@ mod tests {
                     "bar".into(),
                     Box::new(QueryPath::from("baz")),
                     None,
+                    None,
                 ))]),
             ),
             (
@@ -900,6 +1410,7 @@ mod tests {
                     "bar".into(),
                     Box::new(QueryPath::from("baz")),
                     Some(Box::new(QueryPath::from("boz"))),
+                    None,
                 ))]),
             ),
             (
@@ -908,6 +1419,16 @@ mod tests {
                     "bar".into(),
                     Box::new(QueryPath::from("baz")),
                     Some(Box::new(Literal::from(Value::Boolean(true)))),
+                    None,
+                ))]),
+            ),
+            (
+                "merge(.bar, .baz, union_arrays = true)",
+                Mapping::new(vec![Box::new(MergeFn::new(
+                    "bar".into(),
+                    Box::new(QueryPath::from("baz")),
+                    None,
+                    Some(Box::new(Literal::from(Value::Boolean(true)))),
                 ))]),
             ),
             (
@@ -924,6 +1445,28 @@ mod tests {
                     Some(LogLevel::Debug),
                 ))]),
             ),
+            (
+                r#"coerce_to_schema({ .foo: "integer", .bar.baz: "timestamp|%F" })"#,
+                Mapping::new(vec![Box::new(CoerceToSchemaFn::new(
+                    vec![
+                        ("foo".to_string(), Conversion::Integer),
+                        (
+                            "bar.baz".to_string(),
+                            Conversion::TimestampFmt("%F".to_string()),
+                        ),
+                    ],
+                    None,
+                ))]),
+            ),
+            (
+                r#"coerce_to_schema({
+                    .foo: "float",
+                  }, dropped = .dropped)"#,
+                Mapping::new(vec![Box::new(CoerceToSchemaFn::new(
+                    vec![("foo".to_string(), Conversion::Float)],
+                    Some("dropped".to_string()),
+                ))]),
+            ),
             (
                 r#".foo = split(.bar, /a/i, 2)"#,
                 Mapping::new(vec![Box::new(Assignment::new(
@@ -937,13 +1480,1139 @@ mod tests {
                     )),
                 ))]),
             ),
-        ];
-
-        for (mapping, exp) in cases {
-            match parse(mapping) {
-                Ok(p) => assert_eq!(format!("{:?}", p), format!("{:?}", exp), "{}", mapping),
-                Err(error) => panic!("{}, mapping: {}", error, mapping),
-            }
-        }
+            (
+                ".foo = normalize_newlines(.bar)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(NormalizeNewlinesFn::new(
+                        Box::new(QueryPath::from("bar")),
+                        None,
+                    )),
+                ))]),
+            ),
+            (
+                r#".foo = normalize_newlines(.bar, ending = "\r\n")"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(NormalizeNewlinesFn::new(
+                        Box::new(QueryPath::from("bar")),
+                        Some(Box::new(Literal::from(Value::from("\r\n")))),
+                    )),
+                ))]),
+            ),
+            (
+                ".foo = first(.bar)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(FirstFn::new(Box::new(QueryPath::from("bar")))),
+                ))]),
+            ),
+            (
+                ".foo = last(.bar)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(LastFn::new(Box::new(QueryPath::from("bar")))),
+                ))]),
+            ),
+            (
+                r#".foo = decode_flags(.bar, {"A": 1, "B": 2})"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(DecodeFlagsFn::new(
+                        Box::new(QueryPath::from("bar")),
+                        Box::new(Map::new(vec![
+                            ("A".to_string(), Box::new(Literal::from(Value::from(1)))),
+                            ("B".to_string(), Box::new(Literal::from(Value::from(2)))),
+                        ])),
+                    )),
+                ))]),
+            ),
+            (
+                r#".foo = {
+                    "bar": .bar,
+                    "baz": "baz",
+                  }"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(Map::new(vec![
+                        ("bar".to_string(), Box::new(QueryPath::from("bar"))),
+                        (
+                            "baz".to_string(),
+                            Box::new(Literal::from(Value::from("baz"))),
+                        ),
+                    ])),
+                ))]),
+            ),
+            (
+                ".foo = {}",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(Map::new(vec![])),
+                ))]),
+            ),
+            (
+                ".foo = weekday_name(.bar)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(WeekdayNameFn::new(Box::new(QueryPath::from("bar")), None)),
+                ))]),
+            ),
+            (
+                ".foo = month_name(.bar, abbreviated = true)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(MonthNameFn::new(
+                        Box::new(QueryPath::from("bar")),
+                        Some(Box::new(Literal::from(Value::Boolean(true)))),
+                    )),
+                ))]),
+            ),
+            (
+                r#".foo = flatten_one(.bar, "__")"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(FlattenOneFn::new(
+                        Box::new(QueryPath::from("bar")),
+                        Some(Box::new(Literal::from(Value::from("__")))),
+                        None,
+                    )),
+                ))]),
+            ),
+            (
+                "if is_valid_utf8(.bar) { .foo = .bar } else { .foo = force_utf8(.bar) }",
+                Mapping::new(vec![Box::new(IfStatement::new(
+                    Box::new(IsValidUtf8Fn::new(Box::new(QueryPath::from("bar")))),
+                    Box::new(Assignment::new(
+                        "foo".to_string(),
+                        Box::new(QueryPath::from("bar")),
+                    )),
+                    Box::new(Assignment::new(
+                        "foo".to_string(),
+                        Box::new(ForceUtf8Fn::new(Box::new(QueryPath::from("bar")))),
+                    )),
+                ))]),
+            ),
+            (
+                ".foo = normalize(.bar, sort_arrays = true)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(NormalizeFn::new(
+                        Box::new(QueryPath::from("bar")),
+                        Some(Box::new(Literal::from(Value::Boolean(true)))),
+                    )),
+                ))]),
+            ),
+            (
+                ".foo = chunk(.bar, 100)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(ChunkFn::new(
+                        Box::new(QueryPath::from("bar")),
+                        Box::new(Literal::from(Value::from(100))),
+                    )),
+                ))]),
+            ),
+            (
+                ".foo = extract_numbers(.message)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(ExtractNumbersFn::new(Box::new(QueryPath::from("message")))),
+                ))]),
+            ),
+            (
+                "increment(.counts.errors, 1)",
+                Mapping::new(vec![Box::new(IncrementFn::new(
+                    "counts.errors".to_string(),
+                    Box::new(Literal::from(Value::from(1))),
+                ))]),
+            ),
+            (
+                ".foo = parse_semver(.version)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(ParseSemverFn::new(Box::new(QueryPath::from("version")))),
+                ))]),
+            ),
+            (
+                r#".foo = semver_gte(.version, "1.2.0")"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(SemverGteFn::new(
+                        Box::new(QueryPath::from("version")),
+                        Box::new(Literal::from(Value::from("1.2.0"))),
+                    )),
+                ))]),
+            ),
+            (
+                r#"redact_paths([".user.ssn", .card.number], "***")"#,
+                Mapping::new(vec![Box::new(RedactPathsFn::new(
+                    vec!["user.ssn".to_string(), "card.number".to_string()],
+                    Box::new(Literal::from(Value::from("***"))),
+                ))]),
+            ),
+            (
+                r#".foo = start_of(.ts, "day")
+                   .bar = end_of(.ts, unit = "month")"#,
+                Mapping::new(vec![
+                    Box::new(Assignment::new(
+                        "foo".to_string(),
+                        Box::new(StartOfFn::new(
+                            Box::new(QueryPath::from("ts")),
+                            Box::new(Literal::from(Value::from("day"))),
+                        )),
+                    )),
+                    Box::new(Assignment::new(
+                        "bar".to_string(),
+                        Box::new(EndOfFn::new(
+                            Box::new(QueryPath::from("ts")),
+                            Box::new(Literal::from(Value::from("month"))),
+                        )),
+                    )),
+                ]),
+            ),
+            (
+                ".foo = json_escape(.bar)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(JsonEscapeFn::new(Box::new(QueryPath::from("bar")))),
+                ))]),
+            ),
+            (
+                r#".foo = intersect(.a, .b)
+                   .bar = difference(.a, other = .b)"#,
+                Mapping::new(vec![
+                    Box::new(Assignment::new(
+                        "foo".to_string(),
+                        Box::new(IntersectFn::new(
+                            Box::new(QueryPath::from("a")),
+                            Box::new(QueryPath::from("b")),
+                        )),
+                    )),
+                    Box::new(Assignment::new(
+                        "bar".to_string(),
+                        Box::new(DifferenceFn::new(
+                            Box::new(QueryPath::from("a")),
+                            Box::new(QueryPath::from("b")),
+                        )),
+                    )),
+                ]),
+            ),
+            (
+                "snake_case_keys(.payload)",
+                Mapping::new(vec![Box::new(SnakeCaseKeysFn::new("payload".to_string()))]),
+            ),
+            (
+                r#".foo = normalize_numeric(.foo)
+                   .bar = normalize_boolean(.bar)"#,
+                Mapping::new(vec![
+                    Box::new(Assignment::new(
+                        "foo".to_string(),
+                        Box::new(NormalizeNumericFn::new(Box::new(QueryPath::from("foo")))),
+                    )),
+                    Box::new(Assignment::new(
+                        "bar".to_string(),
+                        Box::new(NormalizeBooleanFn::new(Box::new(QueryPath::from("bar")))),
+                    )),
+                ]),
+            ),
+            (
+                ".foo = mask_email(.email, mask_domain = true)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(MaskEmailFn::new(
+                        Box::new(QueryPath::from("email")),
+                        Some(Box::new(Literal::from(Value::Boolean(true)))),
+                    )),
+                ))]),
+            ),
+            (
+                ".foo = parse_json_array(.lines)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(ParseJsonArrayFn::new(Box::new(QueryPath::from("lines")))),
+                ))]),
+            ),
+            (
+                r#"# Set foo.
+                   .foo = "bar" # Not baz.
+                   # Done."#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(Literal::from(Value::from("bar"))),
+                ))]),
+            ),
+            (
+                ".foo = auto_parse(.message)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(AutoParseFn::new(Box::new(QueryPath::from("message")))),
+                ))]),
+            ),
+            (
+                "apply_if_present(.foo.bar, normalize_numeric)",
+                Mapping::new(vec![Box::new(ApplyIfPresentFn::new(
+                    "foo.bar".to_string(),
+                    Box::new(NormalizeNumericFn::new(Box::new(QueryPath::from(
+                        "foo.bar",
+                    )))),
+                ))]),
+            ),
+            (
+                ".foo = format_bytes(.size, si = true, precision = 2)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(FormatBytesFn::new(
+                        Box::new(QueryPath::from("size")),
+                        Some(Box::new(Literal::from(Value::Boolean(true)))),
+                        Some(Box::new(Literal::from(Value::from(2)))),
+                    )),
+                ))]),
+            ),
+            (
+                ".mac = normalize_mac(.mac)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "mac".to_string(),
+                    Box::new(NormalizeMacFn::new(Box::new(QueryPath::from("mac")))),
+                ))]),
+            ),
+            (
+                ".class = http_status_class(.status)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "class".to_string(),
+                    Box::new(HttpStatusClassFn::new(Box::new(QueryPath::from("status")))),
+                ))]),
+            ),
+            (
+                r#".count = ensure_type(.count, type = "integer")"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "count".to_string(),
+                    Box::new(EnsureTypeFn::new(
+                        Box::new(QueryPath::from("count")),
+                        Box::new(Literal::from(Value::from("integer"))),
+                    )),
+                ))]),
+            ),
+            (
+                r#".foo = index_where(.versions, semver_gte, "1.2.0")"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "foo".to_string(),
+                    Box::new(IndexWhereFn::new(
+                        Box::new(QueryPath::from("versions")),
+                        Bound::new("element"),
+                        Box::new(SemverGteFn::new(
+                            Box::new(Bound::new("element")),
+                            Box::new(Literal::from(Value::from("1.2.0"))),
+                        )),
+                    )),
+                ))]),
+            ),
+            (
+                r#".path = strip_prefix(.path, "/var/log/")
+                   .file = strip_suffix(.file, suffix = ".log")"#,
+                Mapping::new(vec![
+                    Box::new(Assignment::new(
+                        "path".to_string(),
+                        Box::new(StripPrefixFn::new(
+                            Box::new(QueryPath::from("path")),
+                            Box::new(Literal::from(Value::from("/var/log/"))),
+                        )),
+                    )),
+                    Box::new(Assignment::new(
+                        "file".to_string(),
+                        Box::new(StripSuffixFn::new(
+                            Box::new(QueryPath::from("file")),
+                            Box::new(Literal::from(Value::from(".log"))),
+                        )),
+                    )),
+                ]),
+            ),
+            (
+                "if luhn_valid(.pan) { .pan = \"redacted\" }",
+                Mapping::new(vec![Box::new(IfStatement::new(
+                    Box::new(LuhnValidFn::new(Box::new(QueryPath::from("pan")))),
+                    Box::new(Assignment::new(
+                        "pan".to_string(),
+                        Box::new(Literal::from(Value::from("redacted"))),
+                    )),
+                    Box::new(Noop {}),
+                ))]),
+            ),
+            (
+                r#".name = coalesce(.user.name, .user.login, "unknown")"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "name".to_string(),
+                    Box::new(Coalesce::new(vec![
+                        Box::new(QueryPath::from(vec![vec!["user"], vec!["name"]])),
+                        Box::new(QueryPath::from(vec![vec!["user"], vec!["login"]])),
+                        Box::new(Literal::from(Value::from("unknown"))),
+                    ])),
+                ))]),
+            ),
+            (
+                ".config = merge_all([.defaults, .env, .overrides])",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "config".to_string(),
+                    Box::new(MergeAllFn::new(vec![
+                        Box::new(QueryPath::from("defaults")),
+                        Box::new(QueryPath::from("env")),
+                        Box::new(QueryPath::from("overrides")),
+                    ])),
+                ))]),
+            ),
+            (
+                r#".summary = truncate_words(.description, 10, "...")"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "summary".to_string(),
+                    Box::new(TruncateWordsFn::new(
+                        Box::new(QueryPath::from("description")),
+                        Box::new(Literal::from(Value::from(10))),
+                        Some(Box::new(Literal::from(Value::from("...")))),
+                    )),
+                ))]),
+            ),
+            (
+                ".first = .items[0]\n.last = .\"items\"[-1]",
+                Mapping::new(vec![
+                    Box::new(Assignment::new(
+                        "first".to_string(),
+                        Box::new(QueryPath::from("items[0]")),
+                    )),
+                    Box::new(Assignment::new(
+                        "last".to_string(),
+                        Box::new(QueryPath::from("items[-1]")),
+                    )),
+                ]),
+            ),
+            (
+                "rename(.a.b, .\"c.d\")",
+                Mapping::new(vec![Box::new(Rename::new(
+                    "a.b".to_string(),
+                    "c\\.d".to_string(),
+                ))]),
+            ),
+            (
+                ".bucket = quantize_timestamp(.timestamp, 300)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "bucket".to_string(),
+                    Box::new(QuantizeTimestampFn::new(
+                        Box::new(QueryPath::from("timestamp")),
+                        Box::new(Literal::from(Value::from(300))),
+                    )),
+                ))]),
+            ),
+            (
+                r#".nums = coerce_array(.nums, "integer")"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "nums".to_string(),
+                    Box::new(CoerceArrayFn::new(
+                        Box::new(QueryPath::from("nums")),
+                        Box::new(Literal::from(Value::from("integer"))),
+                    )),
+                ))]),
+            ),
+            (
+                "if .level == \"debug\" { abort() }",
+                Mapping::new(vec![Box::new(IfStatement::new(
+                    Box::new(Arithmetic::new(
+                        Box::new(QueryPath::from("level")),
+                        Box::new(Literal::from(Value::from("debug"))),
+                        Operator::Equal,
+                    )),
+                    Box::new(Abort {}),
+                    Box::new(Noop {}),
+                ))]),
+            ),
+            (
+                r#".foo = upcase(.foo)
+                   .bar = downcase(trim(.bar))"#,
+                Mapping::new(vec![
+                    Box::new(Assignment::new(
+                        "foo".to_string(),
+                        Box::new(UpcaseFn::new(Box::new(QueryPath::from("foo")))),
+                    )),
+                    Box::new(Assignment::new(
+                        "bar".to_string(),
+                        Box::new(DowncaseFn::new(Box::new(TrimFn::new(Box::new(
+                            QueryPath::from("bar"),
+                        ))))),
+                    )),
+                ]),
+            ),
+            (
+                ".cookies = parse_cookies(.headers.cookie)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "cookies".to_string(),
+                    Box::new(ParseCookiesFn::new(Box::new(QueryPath::from(vec![
+                        vec!["headers"],
+                        vec!["cookie"],
+                    ])))),
+                ))]),
+            ),
+            (
+                r#"if to_int(.status) >= 400 && to_bool(.retry) { .delay = to_float(.delay) * 2 }"#,
+                Mapping::new(vec![Box::new(IfStatement::new(
+                    Box::new(Arithmetic::new(
+                        Box::new(Arithmetic::new(
+                            Box::new(ToIntFn::new(Box::new(QueryPath::from("status")))),
+                            Box::new(Literal::from(Value::from(400))),
+                            Operator::GreaterOrEqual,
+                        )),
+                        Box::new(ToBoolFn::new(Box::new(QueryPath::from("retry")))),
+                        Operator::And,
+                    )),
+                    Box::new(Assignment::new(
+                        "delay".to_string(),
+                        Box::new(Arithmetic::new(
+                            Box::new(ToFloatFn::new(Box::new(QueryPath::from("delay")))),
+                            Box::new(Literal::from(Value::from(2))),
+                            Operator::Multiply,
+                        )),
+                    )),
+                    Box::new(Noop {}),
+                ))]),
+            ),
+            (
+                r#".version = first_matching([.a, .b], semver_gte, "1.2.0")"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "version".to_string(),
+                    Box::new(FirstMatchingFn::new(
+                        vec![
+                            Box::new(QueryPath::from("a")),
+                            Box::new(QueryPath::from("b")),
+                        ],
+                        Bound::new("element"),
+                        Box::new(SemverGteFn::new(
+                            Box::new(Bound::new("element")),
+                            Box::new(Literal::from(Value::from("1.2.0"))),
+                        )),
+                    )),
+                ))]),
+            ),
+            (
+                r#"if contains(.message, "ERROR", true) { .route = "errors" }
+                   .log = ends_with(.file, ".log")"#,
+                Mapping::new(vec![
+                    Box::new(IfStatement::new(
+                        Box::new(ContainsFn::new(
+                            Box::new(QueryPath::from("message")),
+                            Box::new(Literal::from(Value::from("ERROR"))),
+                            Some(Box::new(Literal::from(Value::Boolean(true)))),
+                        )),
+                        Box::new(Assignment::new(
+                            "route".to_string(),
+                            Box::new(Literal::from(Value::from("errors"))),
+                        )),
+                        Box::new(Noop {}),
+                    )),
+                    Box::new(Assignment::new(
+                        "log".to_string(),
+                        Box::new(EndsWithFn::new(
+                            Box::new(QueryPath::from("file")),
+                            Box::new(Literal::from(Value::from(".log"))),
+                            None,
+                        )),
+                    )),
+                ]),
+            ),
+            (
+                ".agent = parse_user_agent(.headers.user_agent)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "agent".to_string(),
+                    Box::new(ParseUserAgentFn::new(Box::new(QueryPath::from(vec![
+                        vec!["headers"],
+                        vec!["user_agent"],
+                    ])))),
+                ))]),
+            ),
+            (
+                ".pairs = windows(.events, 2)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "pairs".to_string(),
+                    Box::new(WindowsFn::new(
+                        Box::new(QueryPath::from("events")),
+                        Box::new(Literal::from(Value::from(2))),
+                    )),
+                ))]),
+            ),
+            (
+                r#"flatten(.payload)
+                   flatten(.labels, separator = "__")"#,
+                Mapping::new(vec![
+                    Box::new(Flatten::new("payload".to_string(), ".".to_string())),
+                    Box::new(Flatten::new("labels".to_string(), "__".to_string())),
+                ]),
+            ),
+            (
+                "only_fields(.foo.*, .bar)",
+                Mapping::new(vec![Box::new(OnlyFields::new(vec![
+                    "foo.*".to_string(),
+                    "bar".to_string(),
+                ]))]),
+            ),
+            (
+                r#"remove_nulls(.)
+                   remove_nulls(.foo.bar)"#,
+                Mapping::new(vec![
+                    Box::new(RemoveNullsFn::new(None)),
+                    Box::new(RemoveNullsFn::new(Some("foo.bar".to_string()))),
+                ]),
+            ),
+            (
+                r#".card = replace(.card, "([0-9]{4})$", "****$1", regex = true)"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "card".to_string(),
+                    Box::new(
+                        ReplaceFn::new(
+                            Box::new(QueryPath::from("card")),
+                            Box::new(Literal::from(Value::from("([0-9]{4})$"))),
+                            Box::new(Literal::from(Value::from("****$1"))),
+                            Some(Box::new(Literal::from(Value::Boolean(true)))),
+                        )
+                        .unwrap(),
+                    ),
+                ))]),
+            ),
+            (
+                r#".level = infer_log_level(.message, default = "unknown")"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "level".to_string(),
+                    Box::new(InferLogLevelFn::new(
+                        Box::new(QueryPath::from("message")),
+                        None,
+                        Some(Box::new(Literal::from(Value::from("unknown")))),
+                    )),
+                ))]),
+            ),
+            (
+                ".received_at = now()",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "received_at".to_string(),
+                    Box::new(NowFn::new()),
+                ))]),
+            ),
+            (
+                r#".ts = format_timestamp(parse_timestamp(.ts, "%d/%b/%Y:%T %z"), "%+")"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "ts".to_string(),
+                    Box::new(FormatTimestampFn::new(
+                        Box::new(ParseTimestampFn::new(
+                            Box::new(QueryPath::from("ts")),
+                            Box::new(Literal::from(Value::from("%d/%b/%Y:%T %z"))),
+                            None,
+                        )),
+                        Box::new(Literal::from(Value::from("%+"))),
+                    )),
+                ))]),
+            ),
+            (
+                ".total = reduce(.nums, add, 0)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "total".to_string(),
+                    Box::new(ReduceFn::new(
+                        Box::new(QueryPath::from("nums")),
+                        Bound::new("accumulator"),
+                        Bound::new("element"),
+                        Box::new(AddFn::new(
+                            Box::new(Bound::new("accumulator")),
+                            Box::new(Bound::new("element")),
+                        )),
+                        Box::new(Literal::from(Value::from(0))),
+                    )),
+                ))]),
+            ),
+            (
+                r#"merge(.labels, {"env": "prod"}, true)"#,
+                Mapping::new(vec![Box::new(MergeFn::new(
+                    "labels".to_string(),
+                    Box::new(Map::new(vec![(
+                        "env".to_string(),
+                        Box::new(Literal::from(Value::from("prod"))),
+                    )])),
+                    Some(Box::new(Literal::from(Value::Boolean(true)))),
+                    None,
+                ))]),
+            ),
+            (
+                ".timeout = parse_iso8601_duration(.timeout)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "timeout".to_string(),
+                    Box::new(ParseIso8601DurationFn::new(Box::new(QueryPath::from(
+                        "timeout",
+                    )))),
+                ))]),
+            ),
+            (
+                "if length(.tags) > 10 { .truncated = true }",
+                Mapping::new(vec![Box::new(IfStatement::new(
+                    Box::new(Arithmetic::new(
+                        Box::new(LengthFn::new(Box::new(QueryPath::from("tags")))),
+                        Box::new(Literal::from(Value::from(10))),
+                        Operator::Greater,
+                    )),
+                    Box::new(Assignment::new(
+                        "truncated".to_string(),
+                        Box::new(Literal::from(Value::from(true))),
+                    )),
+                    Box::new(Noop {}),
+                ))]),
+            ),
+            (
+                "if sample(0.1) { .sampled = true }",
+                Mapping::new(vec![Box::new(IfStatement::new(
+                    Box::new(SampleFn::new(Box::new(Literal::from(Value::from(0.1))))),
+                    Box::new(Assignment::new(
+                        "sampled".to_string(),
+                        Box::new(Literal::from(Value::from(true))),
+                    )),
+                    Box::new(Noop {}),
+                ))]),
+            ),
+            (
+                ".payload = encode_json(parse_json(.message))",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "payload".to_string(),
+                    Box::new(EncodeJsonFn::new(
+                        Box::new(ParseJsonFn::new(Box::new(QueryPath::from("message")))),
+                        None,
+                    )),
+                ))]),
+            ),
+            (
+                ".payload = encode_json(.labels, pretty = true)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "payload".to_string(),
+                    Box::new(EncodeJsonFn::new(
+                        Box::new(QueryPath::from("labels")),
+                        Some(Box::new(Literal::from(Value::Boolean(true)))),
+                    )),
+                ))]),
+            ),
+            (
+                ".tags = map_to_tag_array(.labels)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "tags".to_string(),
+                    Box::new(MapToTagArrayFn::new(Box::new(QueryPath::from("labels")))),
+                ))]),
+            ),
+            (
+                ".rate = round(.bytes / .seconds)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "rate".to_string(),
+                    Box::new(RoundFn::new(Box::new(Arithmetic::new(
+                        Box::new(QueryPath::from("bytes")),
+                        Box::new(QueryPath::from("seconds")),
+                        Operator::Divide,
+                    )))),
+                ))]),
+            ),
+            (
+                ".limit = parse_bytes_size(.limit)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "limit".to_string(),
+                    Box::new(ParseBytesSizeFn::new(Box::new(QueryPath::from("limit")))),
+                ))]),
+            ),
+            (
+                ".new = del(.old.field)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "new".to_string(),
+                    Box::new(DelFn::new("old.field".to_string())),
+                ))]),
+            ),
+            (
+                "if exists(.user.id) { del(.user) }",
+                Mapping::new(vec![Box::new(IfStatement::new(
+                    Box::new(ExistsFn::new(Box::new(QueryPath::from(vec![
+                        vec!["user"],
+                        vec!["id"],
+                    ])))),
+                    Box::new(Deletion::new(vec!["user".to_string()])),
+                    Box::new(Noop {}),
+                ))]),
+            ),
+            (
+                ".missing_name = is_null(.name)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "missing_name".to_string(),
+                    Box::new(IsNullFn::new(Box::new(QueryPath::from("name")))),
+                ))]),
+            ),
+            (
+                ".runs = group_consecutive(.statuses)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "runs".to_string(),
+                    Box::new(GroupConsecutiveFn::new(Box::new(QueryPath::from(
+                        "statuses",
+                    )))),
+                ))]),
+            ),
+            (
+                ".ansi = parse_ansi(.message)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "ansi".to_string(),
+                    Box::new(ParseAnsiFn::new(Box::new(QueryPath::from("message")))),
+                ))]),
+            ),
+            (
+                r#"coerce_timestamps({".ts": "%Y-%m-%d", .event.time: "unix_seconds"})"#,
+                Mapping::new(vec![Box::new(CoerceTimestampsFn::new(vec![
+                    ("ts".to_string(), TimestampRule::parse("%Y-%m-%d").unwrap()),
+                    ("event.time".to_string(), TimestampRule::Unix(1)),
+                ]))]),
+            ),
+            (
+                "map_values(.headers, trim)",
+                Mapping::new(vec![Box::new(MapValuesFn::new(
+                    "headers".to_string(),
+                    Bound::new("value"),
+                    Box::new(TrimFn::new(Box::new(Bound::new("value")))),
+                ))]),
+            ),
+            (
+                "map_keys(.headers, upcase)",
+                Mapping::new(vec![Box::new(MapKeysFn::new(
+                    "headers".to_string(),
+                    Bound::new("key"),
+                    Box::new(UpcaseFn::new(Box::new(Bound::new("key")))),
+                ))]),
+            ),
+            (
+                ".name_ascii = transliterate(.name)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "name_ascii".to_string(),
+                    Box::new(TransliterateFn::new(Box::new(QueryPath::from("name")))),
+                ))]),
+            ),
+            (
+                ".bucket = .id % 16",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "bucket".to_string(),
+                    Box::new(Arithmetic::new(
+                        Box::new(QueryPath::from("id")),
+                        Box::new(Literal::from(Value::from(16))),
+                        Operator::Modulo,
+                    )),
+                ))]),
+            ),
+            (
+                ".backoff = 100 * 2 ** .attempt ** 2",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "backoff".to_string(),
+                    Box::new(Arithmetic::new(
+                        Box::new(Literal::from(Value::from(100))),
+                        Box::new(Arithmetic::new(
+                            Box::new(Literal::from(Value::from(2))),
+                            Box::new(Arithmetic::new(
+                                Box::new(QueryPath::from("attempt")),
+                                Box::new(Literal::from(Value::from(2))),
+                                Operator::Power,
+                            )),
+                            Operator::Power,
+                        )),
+                        Operator::Multiply,
+                    )),
+                ))]),
+            ),
+            (
+                r#".parsed = parse_regex(.message, /^(?P<level>\w+) (?P<msg>.*)$/)"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "parsed".to_string(),
+                    Box::new(ParseRegexFn::new(
+                        Box::new(QueryPath::from("message")),
+                        Box::new(Literal::from(QueryValue::from(
+                            Regex::new(
+                                r"^(?P<level>\w+) (?P<msg>.*)$".to_string(),
+                                false,
+                                false,
+                                false,
+                            )
+                            .unwrap(),
+                        ))),
+                    )),
+                ))]),
+            ),
+            (
+                r#".fields = parse_key_value(.message, field_delimiter = ",")"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "fields".to_string(),
+                    Box::new(ParseKeyValueFn::new(
+                        Box::new(QueryPath::from("message")),
+                        None,
+                        Some(Box::new(Literal::from(Value::from(",")))),
+                    )),
+                ))]),
+            ),
+            (
+                r#".fields = parse_csv(.message, ["ts", "host", .status_field])"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "fields".to_string(),
+                    Box::new(ParseCsvFn::new(
+                        Box::new(QueryPath::from("message")),
+                        Some(Box::new(Array::new(vec![
+                            Box::new(Literal::from(Value::from("ts"))),
+                            Box::new(Literal::from(Value::from("host"))),
+                            Box::new(QueryPath::from("status_field")),
+                        ]))),
+                        None,
+                    )),
+                ))]),
+            ),
+            (
+                r#".fields = parse_csv(.message, delimiter = ";")"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "fields".to_string(),
+                    Box::new(ParseCsvFn::new(
+                        Box::new(QueryPath::from("message")),
+                        None,
+                        Some(Box::new(Literal::from(Value::from(";")))),
+                    )),
+                ))]),
+            ),
+            (
+                r#".ts = parse_timestamp(.ts, "%d/%b/%Y:%T", timezone = "+01:00")"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "ts".to_string(),
+                    Box::new(ParseTimestampFn::new(
+                        Box::new(QueryPath::from("ts")),
+                        Box::new(Literal::from(Value::from("%d/%b/%Y:%T"))),
+                        Some(Box::new(Literal::from(Value::from("+01:00")))),
+                    )),
+                ))]),
+            ),
+            (
+                ".url = parse_url(.request_uri)",
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "url".to_string(),
+                    Box::new(ParseUrlFn::new(Box::new(QueryPath::from("request_uri")))),
+                ))]),
+            ),
+            (
+                r#".elapsed = parse_duration(.elapsed, "seconds")"#,
+                Mapping::new(vec![Box::new(Assignment::new(
+                    "elapsed".to_string(),
+                    Box::new(ParseDurationFn::new(
+                        Box::new(QueryPath::from("elapsed")),
+                        Box::new(Literal::from(Value::from("seconds"))),
+                    )),
+                ))]),
+            ),
+        ];
+
+        for (mapping, exp) in cases {
+            match parse(mapping) {
+                Ok(p) => assert_eq!(format!("{:?}", p), format!("{:?}", exp), "{}", mapping),
+                Err(error) => panic!("{}, mapping: {}", error, mapping),
+            }
+        }
+    }
+
+    #[test]
+    fn check_type_errors() {
+        let cases = vec![
+            (
+                r#"if "hello" { .foo = "bar" }"#,
+                Some(r#"invalid mapping 0: condition literal "hello" is not a boolean"#),
+            ),
+            (
+                r#".foo = 1 + "x""#,
+                Some(r#"invalid mapping 0: unable to add right-hand field type Bytes(b"x")"#),
+            ),
+            (
+                r#".foo = "bar"
+                   .baz = (1 + 2) * false"#,
+                Some("invalid mapping 1: unable to multiply right-hand field type Boolean(false)"),
+            ),
+            (
+                r#"if .foo { .bar = "a" } else { if 5 { .bar = "b" } }"#,
+                Some("invalid mapping 0: condition literal 5 is not a boolean"),
+            ),
+            (
+                r#".x = upcase(1 + "x")"#,
+                Some(r#"invalid mapping 0: unable to add right-hand field type Bytes(b"x")"#),
+            ),
+            (
+                r#"if contains(.a, 1 + "x") { .b = true }"#,
+                Some(r#"invalid mapping 0: unable to add right-hand field type Bytes(b"x")"#),
+            ),
+            // Field types aren't known until the mapping is executed.
+            (r#".foo = .bar + "x""#, None),
+            (r#".x = upcase(.bar + "x")"#, None),
+            (r#"if 1 == 1 { .foo = "bar" }"#, None),
+        ];
+
+        let options = ParseOptions {
+            validate: true,
+            ..Default::default()
+        };
+
+        for (mapping, exp) in cases {
+            assert_eq!(
+                parse_with_options(mapping, options).err().as_deref(),
+                exp,
+                "mapping: {}",
+                mapping
+            );
+        }
+    }
+
+    #[test]
+    fn check_parser_errors() {
+        let cases = vec![
+            (
+                r#".foo = "bar"#,
+                r#"mapping parse error at byte 7
+ --> 1:8
+  |
+1 | .foo = "bar
+  |        ^---
+  |
+  = expected"#,
+            ),
+            (
+                r#".foo = "bar"
+.baz = nope(.qux)"#,
+                r#"mapping parse error at byte 13
+ --> 2:1
+  |
+2 | .baz = nope(.qux)
+  | ^---------------^
+  |
+  = unknown function 'nope'"#,
+            ),
+            (
+                ".foo = .bar baz",
+                r#"mapping parse error at byte 12
+ --> 1:13
+  |
+1 | .foo = .bar baz
+  |             ^---
+  |
+  = expected"#,
+            ),
+            (
+                // Regexes are compiled when the mapping is parsed.
+                r#".parsed = parse_regex(.message, /(?P<level\w+)/)"#,
+                r#"mapping parse error at byte 0
+ --> 1:1
+  |
+1 | .parsed = parse_regex(.message, /(?P<level\w+)/)
+  | ^----------------------------------------------^
+  |
+  = invalid regex: "#,
+            ),
+            (
+                r#".card = replace(.card, "([0-9]{4}", "****$1", regex = true)"#,
+                r#"mapping parse error at byte 0
+ --> 1:1
+  |
+1 | .card = replace(.card, "([0-9]{4}", "****$1", regex = true)
+  | ^---------------------------------------------------------^
+  |
+  = invalid regex pattern "([0-9]{4}": regex parse error:"#,
+            ),
+            (
+                r#".card = replace(.card, .pattern, "****", regex = true)"#,
+                r#"mapping parse error at byte 0
+ --> 1:1
+  |
+1 | .card = replace(.card, .pattern, "****", regex = true)
+  | ^----------------------------------------------------^
+  |
+  = parameter pattern passed to replace must be a literal"#,
+            ),
+        ];
+
+        for (mapping, exp) in cases {
+            let error = parse(mapping).err().unwrap();
+            assert!(
+                error.starts_with(exp),
+                "mapping: {}\nerror: {}",
+                mapping,
+                error
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "mapping-grok")]
+    fn check_parser_grok() {
+        use crate::mapping::query::function::ParseGrokFn;
+        use std::collections::BTreeMap;
+
+        let mapping = r#".parsed = parse_grok(.message, "%{INT:id}-%{WORD:status}")"#;
+        let exp = Mapping::new(vec![Box::new(Assignment::new(
+            "parsed".to_string(),
+            Box::new(
+                ParseGrokFn::new(
+                    Box::new(QueryPath::from("message")),
+                    "%{INT:id}-%{WORD:status}",
+                    BTreeMap::new(),
+                )
+                .unwrap(),
+            ),
+        ))]);
+        assert_eq!(
+            format!("{:?}", parse(mapping).unwrap()),
+            format!("{:?}", exp)
+        );
+
+        let mapping = r#".parsed = parse_grok(.message, .pattern)"#;
+        let exp = r#"mapping parse error at byte 0
+ --> 1:1
+  |
+1 | .parsed = parse_grok(.message, .pattern)
+  | ^--------------------------------------^
+  |
+  = parameter pattern passed to parse_grok must be a literal"#;
+        let error = parse(mapping).err().unwrap();
+        assert!(error.starts_with(exp), "error: {}", error);
+    }
+
+    #[test]
+    #[cfg(feature = "mapping-syslog")]
+    fn check_parser_syslog() {
+        use crate::mapping::query::function::ParseSyslogFn;
+
+        let mapping = ".syslog = parse_syslog(.message)";
+        let exp = Mapping::new(vec![Box::new(Assignment::new(
+            "syslog".to_string(),
+            Box::new(ParseSyslogFn::new(Box::new(QueryPath::from("message")))),
+        ))]);
+        assert_eq!(
+            format!("{:?}", parse(mapping).unwrap()),
+            format!("{:?}", exp)
+        );
+    }
+
+    #[test]
+    fn preserve_comments() {
+        let source = r#"
+# Tag the event.
+.foo = "bar" # inline
+if .x == 1 {
+  # Inside a block.
+  del(.y)
+}
+# Trailing."#;
+
+        let options = ParseOptions {
+            preserve_comments: true,
+            ..Default::default()
+        };
+        let mapping = parse_with_options(source, options).unwrap();
+        let expected = r#"# Tag the event.
+.foo = "bar" # inline
+if .x == 1 {
+  # Inside a block.
+  del(.y)
+}
+# Trailing."#;
+        assert_eq!(mapping.to_source().as_deref(), Some(expected));
+
+        // Writing the mapping back out is stable.
+        let mapping = parse_with_options(expected, options).unwrap();
+        assert_eq!(mapping.to_source().as_deref(), Some(expected));
+
+        assert_eq!(parse(source).unwrap().to_source(), None);
     }
 }
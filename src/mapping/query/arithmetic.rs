@@ -5,9 +5,11 @@ use crate::{
     mapping::Result,
 };
 use bytes::BytesMut;
+use std::convert::TryFrom;
 
 #[derive(Debug, Clone)]
 pub(in crate::mapping) enum Operator {
+    Power,
     Multiply,
     Divide,
     Modulo,
@@ -23,6 +25,28 @@ pub(in crate::mapping) enum Operator {
     Or,
 }
 
+impl std::fmt::Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let op = match self {
+            Operator::Power => "**",
+            Operator::Multiply => "*",
+            Operator::Divide => "/",
+            Operator::Modulo => "%",
+            Operator::Add => "+",
+            Operator::Subtract => "-",
+            Operator::Equal => "==",
+            Operator::NotEqual => "!=",
+            Operator::Greater => ">",
+            Operator::GreaterOrEqual => ">=",
+            Operator::Less => "<",
+            Operator::LessOrEqual => "<=",
+            Operator::And => "&&",
+            Operator::Or => "||",
+        };
+        write!(f, "{}", op)
+    }
+}
+
 #[derive(Debug)]
 pub(in crate::mapping) struct Arithmetic {
     left: Box<dyn Function>,
@@ -141,13 +165,62 @@ impl Function for Arithmetic {
                 }
             }
 
-            Operator::Modulo => match left {
-                Value::Integer(il) => match right {
-                    Value::Integer(ir) => Value::Integer(il % ir),
-                    vr => return Err(format!("unable to modulo right-hand field type {:?}", vr)),
-                },
-                vl => return Err(format!("unable to modulo left-hand field type {:?}", vl)),
-            },
+            Operator::Power => {
+                let (left, right) = coerce_number_types(left, right);
+                match left {
+                    Value::Float(fl) => match right {
+                        Value::Float(fr) => Value::Float(fl.powf(fr)),
+                        vr => {
+                            return Err(format!(
+                                "unable to raise to right-hand field type {:?}",
+                                vr
+                            ))
+                        }
+                    },
+                    // A negative exponent gives a fraction, which is only
+                    // representable as a float.
+                    Value::Integer(il) => match right {
+                        Value::Integer(ir) if ir < 0 => Value::Float((il as f64).powf(ir as f64)),
+                        Value::Integer(ir) => u32::try_from(ir)
+                            .ok()
+                            .and_then(|ir| il.checked_pow(ir))
+                            .map(Value::Integer)
+                            .ok_or_else(|| format!("{} ** {} overflows an integer", il, ir))?,
+                        vr => {
+                            return Err(format!(
+                                "unable to raise to right-hand field type {:?}",
+                                vr
+                            ))
+                        }
+                    },
+                    vl => return Err(format!("unable to raise left-hand field type {:?}", vl)),
+                }
+            }
+
+            Operator::Modulo => {
+                let (left, right) = coerce_number_types(left, right);
+                match left {
+                    Value::Float(fl) => match right {
+                        Value::Float(fr) => {
+                            if fr == 0.0 {
+                                return Err("unable to modulo by zero".to_owned());
+                            }
+                            Value::Float(fl % fr)
+                        }
+                        vr => {
+                            return Err(format!("unable to modulo right-hand field type {:?}", vr))
+                        }
+                    },
+                    Value::Integer(il) => match right {
+                        Value::Integer(0) => return Err("unable to modulo by zero".to_owned()),
+                        Value::Integer(ir) => Value::Integer(il.wrapping_rem(ir)),
+                        vr => {
+                            return Err(format!("unable to modulo right-hand field type {:?}", vr))
+                        }
+                    },
+                    vl => return Err(format!("unable to modulo left-hand field type {:?}", vl)),
+                }
+            }
 
             Operator::Add => {
                 let (left, right) = coerce_number_types(left, right);
@@ -229,6 +302,32 @@ impl Function for Arithmetic {
         }
         .into())
     }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.left.describe(),
+            self.op,
+            self.right.describe()
+        )
+    }
+
+    fn is_constant(&self) -> bool {
+        self.left.is_constant() && self.right.is_constant()
+    }
+
+    /// Operations on constants always give the same result, so any error
+    /// they would fail with can be reported before running the mapping.
+    fn validate(&self) -> Result<()> {
+        self.left.validate()?;
+        self.right.validate()?;
+
+        if self.is_constant() {
+            self.execute(&Event::from(""))?;
+        }
+
+        Ok(())
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -304,6 +403,114 @@ mod tests {
                     Operator::Modulo,
                 ),
             ),
+            (
+                Event::from(""),
+                Ok(Value::Float(1.5)),
+                Arithmetic::new(
+                    Box::new(Literal::from(Value::Float(13.5))),
+                    Box::new(Literal::from(Value::Integer(4))),
+                    Operator::Modulo,
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::Integer(-1)),
+                Arithmetic::new(
+                    Box::new(Literal::from(Value::Integer(-13))),
+                    Box::new(Literal::from(Value::Integer(4))),
+                    Operator::Modulo,
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::Integer(0)),
+                Arithmetic::new(
+                    Box::new(Literal::from(Value::Integer(i64::MIN))),
+                    Box::new(Literal::from(Value::Integer(-1))),
+                    Operator::Modulo,
+                ),
+            ),
+            (
+                Event::from(""),
+                Err("unable to modulo by zero".into()),
+                Arithmetic::new(
+                    Box::new(Literal::from(Value::Integer(13))),
+                    Box::new(Literal::from(Value::Integer(0))),
+                    Operator::Modulo,
+                ),
+            ),
+            (
+                Event::from(""),
+                Err("unable to modulo by zero".into()),
+                Arithmetic::new(
+                    Box::new(Literal::from(Value::Float(13.0))),
+                    Box::new(Literal::from(Value::Integer(0))),
+                    Operator::Modulo,
+                ),
+            ),
+            (
+                Event::from(""),
+                Err("unable to modulo right-hand field type Boolean(true)".into()),
+                Arithmetic::new(
+                    Box::new(Literal::from(Value::Integer(13))),
+                    Box::new(Literal::from(Value::Boolean(true))),
+                    Operator::Modulo,
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::Integer(1024)),
+                Arithmetic::new(
+                    Box::new(Literal::from(Value::Integer(2))),
+                    Box::new(Literal::from(Value::Integer(10))),
+                    Operator::Power,
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::Float(0.25)),
+                Arithmetic::new(
+                    Box::new(Literal::from(Value::Integer(2))),
+                    Box::new(Literal::from(Value::Integer(-2))),
+                    Operator::Power,
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::Float(1.5)),
+                Arithmetic::new(
+                    Box::new(Literal::from(Value::Float(2.25))),
+                    Box::new(Literal::from(Value::Float(0.5))),
+                    Operator::Power,
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::Float(6.25)),
+                Arithmetic::new(
+                    Box::new(Literal::from(Value::Float(2.5))),
+                    Box::new(Literal::from(Value::Integer(2))),
+                    Operator::Power,
+                ),
+            ),
+            (
+                Event::from(""),
+                Err("2 ** 64 overflows an integer".into()),
+                Arithmetic::new(
+                    Box::new(Literal::from(Value::Integer(2))),
+                    Box::new(Literal::from(Value::Integer(64))),
+                    Operator::Power,
+                ),
+            ),
+            (
+                Event::from(""),
+                Err("unable to raise left-hand field type Bytes(b\"2\")".into()),
+                Arithmetic::new(
+                    Box::new(Literal::from(Value::from("2"))),
+                    Box::new(Literal::from(Value::Integer(2))),
+                    Operator::Power,
+                ),
+            ),
             (
                 Event::from(""),
                 Ok(Value::Integer(17)),
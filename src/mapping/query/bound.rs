@@ -0,0 +1,109 @@
+use super::Function;
+use crate::{
+    event::{Event, Value},
+    mapping::{query::query_value::QueryValue, Result},
+};
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A value passed to a function reference, such as each element of the array
+/// given to `index_where`, which the reference reads as one of its arguments.
+///
+/// The function that calls the reference binds the value for the duration of
+/// the call, see [`Bound::bind`]. Queries are shared by every execution of a
+/// mapping, which can run concurrently, so the values are bound on the thread
+/// of the execution rather than held by the query itself.
+#[derive(Clone, Copy)]
+pub(in crate::mapping) struct Bound {
+    id: usize,
+    name: &'static str,
+}
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    // The values bound on this thread, innermost last.
+    static BOUND: RefCell<Vec<(usize, Value)>> = RefCell::new(Vec::new());
+}
+
+/// Unbinds the innermost value once a call has finished, even if it panicked.
+struct Guard;
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        BOUND.with(|bound| bound.borrow_mut().pop());
+    }
+}
+
+impl Bound {
+    /// Creates an argument, named for use in descriptions, e.g. `element`.
+    pub(in crate::mapping) fn new(name: &'static str) -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            name,
+        }
+    }
+
+    /// Runs `f` with the argument bound to `value`.
+    pub(in crate::mapping) fn bind<T>(&self, value: Value, f: impl FnOnce() -> T) -> T {
+        BOUND.with(|bound| bound.borrow_mut().push((self.id, value)));
+        let _guard = Guard;
+
+        f()
+    }
+}
+
+impl Function for Bound {
+    fn execute(&self, _: &Event) -> Result<QueryValue> {
+        BOUND
+            .with(|bound| {
+                bound
+                    .borrow()
+                    .iter()
+                    .rev()
+                    .find(|(id, _)| *id == self.id)
+                    .map(|(_, value)| value.clone())
+            })
+            .map(QueryValue::Value)
+            .ok_or_else(|| format!("no value is bound to the {}", self.name))
+    }
+
+    fn describe(&self) -> String {
+        format!("the {}", self.name)
+    }
+}
+
+// The id is left out, so that the queries built by separate parses compare
+// equal in tests.
+impl fmt::Debug for Bound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Bound").field(&self.name).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_bound() {
+        let event = Event::from("");
+        let outer = Bound::new("element");
+        let inner = Bound::new("element");
+
+        assert_eq!(
+            outer.execute(&event),
+            Err("no value is bound to the element".to_string())
+        );
+
+        outer.bind(Value::from(1), || {
+            inner.bind(Value::from(2), || {
+                assert_eq!(outer.execute(&event), Ok(Value::from(1).into()));
+                assert_eq!(inner.execute(&event), Ok(Value::from(2).into()));
+            });
+            assert_eq!(outer.execute(&event), Ok(Value::from(1).into()));
+            assert!(inner.execute(&event).is_err());
+        });
+    }
+}
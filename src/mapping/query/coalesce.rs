@@ -0,0 +1,115 @@
+use super::{query_value::QueryValue, Function};
+use crate::{
+    event::{Event, Value},
+    mapping::Result,
+};
+
+/// Resolves to the first of its queries that returns a non-null value, e.g.
+/// `coalesce(.user.name, .user.login, "unknown")`.
+///
+/// Queries for paths that don't exist in the event are skipped, any other
+/// error is returned. If no query returns a non-null value, the result is
+/// null.
+#[derive(Debug)]
+pub(in crate::mapping) struct Coalesce {
+    queries: Vec<Box<dyn Function>>,
+}
+
+impl Coalesce {
+    pub(in crate::mapping) fn new(queries: Vec<Box<dyn Function>>) -> Self {
+        Self { queries }
+    }
+}
+
+impl Function for Coalesce {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        for query in &self.queries {
+            match query.resolve(ctx)? {
+                None | Some(QueryValue::Value(Value::Null)) => continue,
+                Some(value @ QueryValue::Value(_)) => return Ok(value),
+                Some(value) => {
+                    return Err(format!(
+                        "coalesce can not return a {}, only values",
+                        value.kind()
+                    ))
+                }
+            }
+        }
+
+        Ok(Value::Null.into())
+    }
+
+    fn describe(&self) -> String {
+        let queries = self
+            .queries
+            .iter()
+            .map(|query| query.describe())
+            .collect::<Vec<_>>();
+
+        format!("the first non-null of {}", queries.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{path::Path, Literal};
+
+    #[test]
+    fn check_coalesce() {
+        let cases = vec![
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::Null);
+                    event
+                },
+                Ok(Value::Null),
+                Coalesce::new(vec![
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Path::from(vec![vec!["bar"]])),
+                    Box::new(Literal::from(Value::Null)),
+                ]),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from("foo"));
+                    event.as_mut_log().insert("bar", Value::from("bar"));
+                    event
+                },
+                Ok(Value::from("foo")),
+                Coalesce::new(vec![
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Path::from(vec![vec!["bar"]])),
+                    Box::new(Literal::from(Value::from("fallback"))),
+                ]),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("bar", Value::from("bar"));
+                    event
+                },
+                Ok(Value::from("bar")),
+                Coalesce::new(vec![
+                    Box::new(Path::from(vec![vec!["foo"], vec!["baz"]])),
+                    Box::new(Path::from(vec![vec!["bar"]])),
+                    Box::new(Literal::from(Value::from("fallback"))),
+                ]),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from("fallback")),
+                Coalesce::new(vec![
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from("fallback"))),
+                ]),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+}
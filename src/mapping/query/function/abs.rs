@@ -0,0 +1,87 @@
+use super::prelude::*;
+
+/// Returns the absolute value of a number, keeping its type.
+#[derive(Debug)]
+pub(in crate::mapping) struct AbsFn {
+    value: Box<dyn Function>,
+}
+
+impl AbsFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for AbsFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let value = required_value!(ctx, self.value,
+            Value::Integer(v) => match v.checked_abs() {
+                Some(v) => Value::Integer(v),
+                None => return Err(format!("unable to take the absolute value of {}", v)),
+            },
+            Value::Float(v) => Value::Float(v.abs()),
+        );
+
+        Ok(value.into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| {
+                matches!(
+                    v,
+                    QueryValue::Value(Value::Integer(_)) | QueryValue::Value(Value::Float(_))
+                )
+            },
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for AbsFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{function::Argument, path::Path};
+
+    #[test]
+    fn check_abs() {
+        let cases = vec![
+            (Value::from(-2.5), Ok(Value::from(2.5))),
+            (Value::from(2.5), Ok(Value::from(2.5))),
+            (Value::from(-3), Ok(Value::from(3))),
+            (Value::from(0), Ok(Value::from(0))),
+            (
+                Value::from(i64::MIN),
+                Err(format!("unable to take the absolute value of {}", i64::MIN)),
+            ),
+            (
+                Value::from("1.5"),
+                Err("invalid argument type 'string' for parameter 'value'".to_string()),
+            ),
+        ];
+
+        for (value, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", value);
+
+            let query = AbsFn::new(Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                AbsFn::parameters()[0].clone(),
+            )));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
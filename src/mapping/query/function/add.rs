@@ -0,0 +1,121 @@
+use super::prelude::*;
+use crate::mapping::query::arithmetic::{Arithmetic, Operator};
+
+/// Adds two values as the `+` operator does, so that addition can be passed
+/// by reference, e.g. to `reduce`.
+#[derive(Debug)]
+pub(in crate::mapping) struct AddFn {
+    query: Arithmetic,
+}
+
+impl AddFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>, other: Box<dyn Function>) -> Self {
+        Self {
+            query: Arithmetic::new(value, other, Operator::Add),
+        }
+    }
+}
+
+impl Function for AddFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        self.query.execute(ctx)
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| {
+                    matches!(
+                        v,
+                        QueryValue::Value(Value::Integer(_))
+                            | QueryValue::Value(Value::Float(_))
+                            | QueryValue::Value(Value::Bytes(_))
+                    )
+                },
+                required: true,
+            },
+            Parameter {
+                keyword: "other",
+                accepts: |v| {
+                    matches!(
+                        v,
+                        QueryValue::Value(Value::Integer(_))
+                            | QueryValue::Value(Value::Float(_))
+                            | QueryValue::Value(Value::Bytes(_))
+                    )
+                },
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for AddFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let other = arguments.required("other")?;
+
+        Ok(Self {
+            query: Arithmetic::new(value, other, Operator::Add),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{function::Argument, path::Path};
+
+    #[test]
+    fn check_add() {
+        let cases = vec![
+            (
+                Event::from(""),
+                Ok(Value::Integer(5)),
+                AddFn::new(
+                    Box::new(Literal::from(Value::Integer(2))),
+                    Box::new(Literal::from(Value::Integer(3))),
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::Float(5.5)),
+                AddFn::new(
+                    Box::new(Literal::from(Value::Integer(2))),
+                    Box::new(Literal::from(Value::Float(3.5))),
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from("foobar")),
+                AddFn::new(
+                    Box::new(Literal::from(Value::from("foo"))),
+                    Box::new(Literal::from(Value::from("bar"))),
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::Boolean(true));
+                    event
+                },
+                Err("invalid argument type 'boolean' for parameter 'value'".to_string()),
+                AddFn::new(
+                    Box::new(Argument::new(
+                        Box::new(Path::from(vec![vec!["foo"]])),
+                        AddFn::parameters()[0].clone(),
+                    )),
+                    Box::new(Literal::from(Value::Integer(3))),
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use super::prelude::*;
+use std::collections::BTreeMap;
+
+/// The field added to the parsed map to indicate which parser succeeded.
+const FORMAT_FIELD: &str = "_format";
+
+#[derive(Debug)]
+pub(in crate::mapping) struct AutoParseFn {
+    value: Box<dyn Function>,
+}
+
+impl AutoParseFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+fn looks_like_json(message: &str) -> bool {
+    message.starts_with('{') && message.ends_with('}')
+}
+
+fn looks_like_logfmt(message: &str) -> bool {
+    message.contains('=')
+}
+
+fn looks_like_key_value(message: &str) -> bool {
+    message.contains('=') || message.contains(':')
+}
+
+/// Parses a JSON object. Any other JSON value is rejected, as it can't be
+/// returned as a map.
+fn parse_json(message: &str) -> Option<BTreeMap<String, Value>> {
+    match super::parse_json::parse_json(message.as_bytes()) {
+        Ok(Value::Map(map)) => Some(map),
+        _ => None,
+    }
+}
+
+/// Parses space separated `key=value` pairs, where values can be double
+/// quoted. Every token must be a pair for the message to be logfmt.
+fn parse_logfmt(message: &str) -> Option<BTreeMap<String, Value>> {
+    pairs(super::parse_key_value::parse_key_value(message, "=", " ").ok()?)
+}
+
+/// Parses `key=value` or `key: value` pairs separated by commas or
+/// semicolons. Values may be wrapped in double quotes. The delimiters that
+/// split the message into the most pairs are used.
+fn parse_key_value(message: &str) -> Option<BTreeMap<String, Value>> {
+    let delimiters = [("=", ","), (":", ","), ("=", ";"), (":", ";")];
+
+    // Reversed, as `max_by_key` picks the last of equal maps.
+    delimiters
+        .iter()
+        .rev()
+        .filter_map(|(key_value_delimiter, field_delimiter)| {
+            super::parse_key_value::parse_key_value(message, key_value_delimiter, field_delimiter)
+                .ok()
+                .and_then(pairs)
+        })
+        .max_by_key(BTreeMap::len)
+}
+
+/// Rejects a parsed message unless every field is a `key=value` pair with a
+/// single word key. Otherwise, almost any text would be accepted, as a field
+/// with no delimiter is parsed as a key set to `true`.
+fn pairs(map: BTreeMap<String, Value>) -> Option<BTreeMap<String, Value>> {
+    let valid = !map.is_empty()
+        && map.iter().all(|(key, value)| {
+            !key.is_empty()
+                && !key.contains(char::is_whitespace)
+                && !matches!(value, Value::Boolean(_))
+        });
+
+    if valid {
+        Some(map)
+    } else {
+        None
+    }
+}
+
+impl Function for AutoParseFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let message = String::from_utf8_lossy(&bytes);
+        let message = message.trim();
+
+        // Parsers are tried from the strictest format to the loosest.
+        let parsers: &[(
+            &str,
+            fn(&str) -> bool,
+            fn(&str) -> Option<BTreeMap<String, Value>>,
+        )] = &[
+            ("json", looks_like_json, parse_json),
+            ("logfmt", looks_like_logfmt, parse_logfmt),
+            ("key_value", looks_like_key_value, parse_key_value),
+        ];
+
+        parsers
+            .iter()
+            .filter(|(_, looks_like, _)| looks_like(message))
+            .find_map(|(format, _, parse)| parse(message).map(|map| (format, map)))
+            .map(|(format, mut map)| {
+                map.insert(FORMAT_FIELD.to_owned(), Value::from(format.to_string()));
+                Value::Map(map).into()
+            })
+            .ok_or_else(|| "unable to detect the format of the value".to_owned())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for AutoParseFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_auto_parse() {
+        let cases = vec![
+            (
+                r#"{"level": "info", "count": 2}"#,
+                Ok(Value::from(
+                    json!({"level": "info", "count": 2, "_format": "json"}),
+                )),
+            ),
+            (
+                r#"level=info msg="hello \"world\"" count=2"#,
+                Ok(Value::from(json!({
+                    "level": "info",
+                    "msg": "hello \"world\"",
+                    "count": "2",
+                    "_format": "logfmt",
+                }))),
+            ),
+            (
+                r#"level: info, msg: "hello world""#,
+                Ok(Value::from(json!({
+                    "level": "info",
+                    "msg": "hello world",
+                    "_format": "key_value",
+                }))),
+            ),
+            (
+                // Looks like JSON, but isn't.
+                r#"{level=info}"#,
+                Ok(Value::from(json!({"{level": "info}", "_format": "logfmt"}))),
+            ),
+            (
+                r#"user: alice; action: "log in""#,
+                Ok(Value::from(json!({
+                    "user": "alice",
+                    "action": "log in",
+                    "_format": "key_value",
+                }))),
+            ),
+            (
+                // Only some of the words are pairs.
+                "retrying in status=5s",
+                Err("unable to detect the format of the value".to_string()),
+            ),
+            (
+                "just a plain message",
+                Err("unable to detect the format of the value".to_string()),
+            ),
+            (
+                "[1, 2]",
+                Err("unable to detect the format of the value".to_string()),
+            ),
+        ];
+
+        for (message, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(message));
+
+            let query = AutoParseFn::new(Box::new(Path::from(vec![vec!["foo"]])));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
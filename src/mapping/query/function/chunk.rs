@@ -0,0 +1,143 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ChunkFn {
+    value: Box<dyn Function>,
+    size: Box<dyn Function>,
+}
+
+impl ChunkFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>, size: Box<dyn Function>) -> Self {
+        Self { value, size }
+    }
+}
+
+impl Function for ChunkFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let array = required_value!(ctx, self.value, Value::Array(v) => v);
+        let size = required_value!(ctx, self.size, Value::Integer(v) => v);
+
+        if size <= 0 {
+            return Err(format!("chunk size must be positive, got {}", size));
+        }
+
+        let chunks = array
+            .chunks(size as usize)
+            .map(|chunk| Value::Array(chunk.to_vec()))
+            .collect::<Vec<_>>();
+
+        Ok(Value::Array(chunks).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Array(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "size",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Integer(_))),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for ChunkFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let size = arguments.required("size")?;
+
+        Ok(Self { value, size })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_chunk() {
+        let cases = vec![
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from(json!([1, 2, 3, 4])));
+                    event
+                },
+                Ok(Value::from(json!([[1, 2], [3, 4]]))),
+                ChunkFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from(2))),
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from(json!([1, "two", 3, 4, 5])));
+                    event
+                },
+                Ok(Value::from(json!([[1, "two", 3], [4, 5]]))),
+                ChunkFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from(3))),
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from(json!([])));
+                    event
+                },
+                Ok(Value::from(json!([]))),
+                ChunkFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from(3))),
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from(json!([1])));
+                    event
+                },
+                Err("chunk size must be positive, got 0".to_string()),
+                ChunkFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from(0))),
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from("bar"));
+                    event
+                },
+                Err("invalid argument type 'string' for parameter 'value'".to_string()),
+                ChunkFn::new(
+                    Box::new(Argument::new(
+                        Box::new(Path::from(vec![vec!["foo"]])),
+                        ChunkFn::parameters()[0].clone(),
+                    )),
+                    Box::new(Literal::from(Value::from(2))),
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use super::prelude::*;
+use crate::mapping::coerce_value;
+use crate::types::Conversion;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct CoerceArrayFn {
+    value: Box<dyn Function>,
+    conversion: Box<dyn Function>,
+}
+
+impl CoerceArrayFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>, conversion: Box<dyn Function>) -> Self {
+        Self { value, conversion }
+    }
+}
+
+impl Function for CoerceArrayFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let array = required_value!(ctx, self.value, Value::Array(v) => v);
+        let conversion = required_value!(ctx, self.conversion, Value::Bytes(v) => v);
+        let conversion = String::from_utf8_lossy(&conversion);
+        let conversion = conversion
+            .parse::<Conversion>()
+            .map_err(|err| err.to_string())?;
+
+        array
+            .iter()
+            .enumerate()
+            .map(|(i, element)| {
+                coerce_value(element, &conversion).ok_or_else(|| {
+                    format!(
+                        "unable to coerce {} element at index {} to {:?}",
+                        element.kind(),
+                        i,
+                        conversion
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(|array| Value::Array(array).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Array(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "type",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for CoerceArrayFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let conversion = arguments.required("type")?;
+
+        Ok(Self { value, conversion })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_coerce_array() {
+        let cases = vec![
+            (
+                json!(["1", 2, "-3"]),
+                "integer",
+                Ok(Value::from(json!([1, 2, -3]))),
+            ),
+            (
+                json!(["true", "no", false]),
+                "boolean",
+                Ok(Value::from(json!([true, false, false]))),
+            ),
+            (json!([]), "integer", Ok(Value::from(json!([])))),
+            (
+                json!(["1", "two", "3"]),
+                "integer",
+                Err("unable to coerce string element at index 1 to Integer".to_string()),
+            ),
+            (
+                json!([1, [2]]),
+                "integer",
+                Err("unable to coerce array element at index 1 to Integer".to_string()),
+            ),
+            (
+                json!([1]),
+                "number",
+                Err("Unknown conversion name \"number\"".to_string()),
+            ),
+        ];
+
+        for (input, conversion, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(input));
+            let query = CoerceArrayFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                Box::new(Literal::from(Value::from(conversion))),
+            );
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from("1"));
+        let query = CoerceArrayFn::new(
+            Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                CoerceArrayFn::parameters()[0].clone(),
+            )),
+            Box::new(Literal::from(Value::from("integer"))),
+        );
+
+        assert_eq!(
+            query.execute(&event),
+            Err("invalid argument type 'string' for parameter 'value'".to_string())
+        );
+    }
+}
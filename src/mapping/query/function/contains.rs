@@ -0,0 +1,140 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ContainsFn {
+    value: Box<dyn Function>,
+    substring: Box<dyn Function>,
+    case_insensitive: Option<Box<dyn Function>>,
+}
+
+impl ContainsFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        substring: Box<dyn Function>,
+        case_insensitive: Option<Box<dyn Function>>,
+    ) -> Self {
+        Self {
+            value,
+            substring,
+            case_insensitive,
+        }
+    }
+}
+
+fn lowercase(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).to_lowercase()
+}
+
+impl Function for ContainsFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let pattern = required_value!(ctx, self.substring, Value::Bytes(v) => v);
+        let case_insensitive =
+            optional_value!(ctx, self.case_insensitive, Value::Boolean(v) => v).unwrap_or(false);
+
+        // Every byte string contains the empty string, though `windows` would
+        // panic on a window size of zero.
+        let result = if pattern.is_empty() {
+            true
+        } else if case_insensitive {
+            lowercase(&bytes).contains(&lowercase(&pattern))
+        } else {
+            bytes
+                .windows(pattern.len())
+                .any(|window| window == &pattern[..])
+        };
+
+        Ok(Value::Boolean(result).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "substring",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "case_insensitive",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Boolean(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for ContainsFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let substring = arguments.required("substring")?;
+        let case_insensitive = arguments.optional("case_insensitive");
+
+        Ok(Self {
+            value,
+            substring,
+            case_insensitive,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_contains() {
+        let cases = vec![
+            ("a fatal ERROR occurred", "ERROR", None, true),
+            ("a fatal ERROR occurred", "warning", None, false),
+            ("a fatal ERROR occurred", "error", None, false),
+            ("a fatal ERROR occurred", "error", Some(true), true),
+            ("a fatal ERROR occurred", "", None, true),
+        ];
+
+        for (value, substring, case_insensitive, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(value));
+
+            let query = ContainsFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                Box::new(Literal::from(Value::from(substring))),
+                case_insensitive.map(|v| Box::new(Literal::from(Value::Boolean(v))) as _),
+            );
+
+            assert_eq!(
+                query.execute(&event),
+                Ok(QueryValue::Value(Value::Boolean(exp)))
+            );
+        }
+    }
+
+    #[test]
+    fn check_contains_non_string() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from(5));
+
+        let query = ContainsFn::new(
+            Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                ContainsFn::parameters()[0].clone(),
+            )),
+            Box::new(Literal::from(Value::from("5"))),
+            None,
+        );
+
+        assert_eq!(
+            query.execute(&event),
+            Err("invalid argument type 'integer' for parameter 'value'".to_string())
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use super::prelude::*;
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct DecodeFlagsFn {
+    value: Box<dyn Function>,
+    flags: Box<dyn Function>,
+}
+
+impl DecodeFlagsFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>, flags: Box<dyn Function>) -> Self {
+        Self { value, flags }
+    }
+}
+
+impl Function for DecodeFlagsFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let value = required_value!(ctx, self.value, Value::Integer(v) => v);
+        let flags = required_value!(ctx, self.flags, Value::Map(v) => v);
+
+        flags
+            .into_iter()
+            .map(|(name, bits)| match bits {
+                Value::Integer(bits) if bits != 0 => {
+                    let set = value & bits == bits;
+                    Ok((name, Value::Boolean(set)))
+                }
+                _ => Err(format!("flag {} must be a non-zero integer bitmask", name)),
+            })
+            .collect::<Result<BTreeMap<_, _>>>()
+            .map(|map| Value::Map(map).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Integer(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "flags",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Map(_))),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for DecodeFlagsFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let flags = arguments.required("flags")?;
+
+        Ok(Self { value, flags })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_decode_flags() {
+        let cases = vec![
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from(5));
+                    event
+                },
+                Ok(Value::from(json!({"A": true, "B": false, "C": true}))),
+                DecodeFlagsFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from(json!({"A": 1, "B": 2, "C": 4})))),
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from(2));
+                    event
+                },
+                Ok(Value::from(json!({"A": false, "AB": false, "B": true}))),
+                DecodeFlagsFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from(json!({"A": 1, "AB": 3, "B": 2})))),
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from(1));
+                    event
+                },
+                Err("flag A must be a non-zero integer bitmask".to_string()),
+                DecodeFlagsFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from(json!({"A": "1"})))),
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use super::prelude::*;
+
+/// Resolves to the value of a field, which the assignment it is the value of
+/// then removes from the event, so that `.new = del(.old)` moves a field. A
+/// missing field resolves to null.
+#[derive(Debug)]
+pub(in crate::mapping) struct DelFn {
+    path: String,
+}
+
+impl DelFn {
+    pub(in crate::mapping) fn new(path: String) -> Self {
+        Self { path }
+    }
+}
+
+impl Function for DelFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let value = ctx.as_log().get(&self.path).cloned();
+
+        Ok(value.unwrap_or(Value::Null).into())
+    }
+
+    fn describe(&self) -> String {
+        format!("the value of .{}, which is removed", self.path)
+    }
+
+    fn removed_path(&self) -> Option<&str> {
+        Some(&self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_del() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo.bar", Value::from("baz"));
+
+        let cases = vec![
+            ("foo.bar", Value::from("baz")),
+            ("foo.missing", Value::Null),
+            ("missing", Value::Null),
+        ];
+
+        for (path, exp) in cases {
+            let query = DelFn::new(path.to_owned());
+
+            assert_eq!(query.execute(&event), Ok(exp.into()));
+            assert_eq!(query.removed_path(), Some(path));
+        }
+    }
+}
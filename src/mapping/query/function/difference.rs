@@ -0,0 +1,110 @@
+use super::prelude::*;
+
+/// Returns the elements of the first array that are not in the second.
+///
+/// Elements are compared by structural equality and keep the order of the
+/// first array. The result is deduplicated, so it can be treated as a set.
+#[derive(Debug)]
+pub(in crate::mapping) struct DifferenceFn {
+    value: Box<dyn Function>,
+    other: Box<dyn Function>,
+}
+
+impl DifferenceFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>, other: Box<dyn Function>) -> Self {
+        Self { value, other }
+    }
+}
+
+impl Function for DifferenceFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let array = required_value!(ctx, self.value, Value::Array(v) => v);
+        let other = required_value!(ctx, self.other, Value::Array(v) => v);
+
+        let mut result: Vec<Value> = Vec::new();
+        for value in array {
+            if !other.contains(&value) && !result.contains(&value) {
+                result.push(value);
+            }
+        }
+
+        Ok(Value::Array(result).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Array(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "other",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Array(_))),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for DifferenceFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let other = arguments.required("other")?;
+
+        Ok(Self { value, other })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_difference() {
+        let cases = vec![
+            (
+                json!(["a", "b", {"c": 1}, "a", 2]),
+                json!([2, {"c": 1}, "z"]),
+                Ok(Value::from(json!(["a", "b"]))),
+            ),
+            (
+                json!(["a", "b"]),
+                json!(["c", "d"]),
+                Ok(Value::from(json!(["a", "b"]))),
+            ),
+            (
+                json!(["a", "b"]),
+                json!(["a", "b"]),
+                Ok(Value::from(json!([]))),
+            ),
+            (
+                json!("a"),
+                json!(["a", "b"]),
+                Err("invalid argument type 'string' for parameter 'value'".to_string()),
+            ),
+        ];
+
+        for (value, other, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(value));
+            event.as_mut_log().insert("bar", Value::from(other));
+
+            let query = DifferenceFn::new(
+                Box::new(Argument::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    DifferenceFn::parameters()[0].clone(),
+                )),
+                Box::new(Path::from(vec![vec!["bar"]])),
+            );
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use super::prelude::*;
+
+/// Serializes any value to a JSON string, compact unless `pretty` is set.
+/// Timestamps are written as RFC 3339 strings.
+#[derive(Debug)]
+pub(in crate::mapping) struct EncodeJsonFn {
+    value: Box<dyn Function>,
+    pretty: Option<Box<dyn Function>>,
+}
+
+impl EncodeJsonFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        pretty: Option<Box<dyn Function>>,
+    ) -> Self {
+        Self { value, pretty }
+    }
+}
+
+impl Function for EncodeJsonFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let value = required!(ctx, self.value, QueryValue::Value(v) => v);
+        let pretty = optional_value!(ctx, self.pretty, Value::Boolean(v) => v).unwrap_or(false);
+
+        let json = if pretty {
+            serde_json::to_string_pretty(&value)
+        } else {
+            serde_json::to_string(&value)
+        };
+
+        json.map(|json| Value::from(json).into())
+            .map_err(|err| format!("unable to encode value as JSON: {}", err))
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "pretty",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Boolean(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for EncodeJsonFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let pretty = arguments.optional("pretty");
+
+        Ok(Self { value, pretty })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{
+        function::{Argument, ParseJsonFn},
+        path::Path,
+        Literal,
+    };
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    #[test]
+    fn check_encode_json() {
+        let cases = vec![
+            (
+                Value::from(json!({"user": {"id": 1, "tags": ["a", "b"], "admin": false}})),
+                Ok(Value::from(
+                    r#"{"user":{"admin":false,"id":1,"tags":["a","b"]}}"#,
+                )),
+            ),
+            (
+                Value::from(json!([1, 2.5, null])),
+                Ok(Value::from("[1,2.5,null]")),
+            ),
+            (
+                Value::from("say \"hi\""),
+                Ok(Value::from(r#""say \"hi\"""#)),
+            ),
+            (Value::from(42), Ok(Value::from("42"))),
+            (Value::from(true), Ok(Value::from("true"))),
+            (Value::Null, Ok(Value::from("null"))),
+            (
+                Value::from(Utc.ymd(2021, 1, 4).and_hms(12, 0, 0)),
+                Ok(Value::from(r#""2021-01-04T12:00:00Z""#)),
+            ),
+        ];
+
+        for (value, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", value);
+
+            let query = EncodeJsonFn::new(Box::new(Path::from(vec![vec!["foo"]])), None);
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+
+    #[test]
+    fn pretty() {
+        let cases = vec![
+            (
+                Value::from(json!({"user": {"id": 1, "tags": ["a"]}})),
+                Some(true),
+                "{\n  \"user\": {\n    \"id\": 1,\n    \"tags\": [\n      \"a\"\n    ]\n  }\n}",
+            ),
+            (
+                Value::from(json!({"user": {"id": 1, "tags": ["a"]}})),
+                Some(false),
+                r#"{"user":{"id":1,"tags":["a"]}}"#,
+            ),
+            (Value::from("scalar"), Some(true), r#""scalar""#),
+        ];
+
+        for (value, pretty, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", value);
+
+            let query = EncodeJsonFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                pretty.map(|v| Box::new(Literal::from(Value::from(v))) as _),
+            );
+
+            assert_eq!(query.execute(&event), Ok(Value::from(exp).into()));
+        }
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from(1));
+        let query = EncodeJsonFn::new(
+            Box::new(Path::from(vec![vec!["foo"]])),
+            Some(Box::new(Argument::new(
+                Box::new(Literal::from(Value::from("yes"))),
+                EncodeJsonFn::parameters()[1].clone(),
+            ))),
+        );
+
+        assert_eq!(
+            query.execute(&event),
+            Err("invalid argument type 'string' for parameter 'pretty'".to_string())
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let value = Value::from(json!({
+            "message": "hello",
+            "nested": {"list": [1, "two", {"three": 3.5}], "empty": {}},
+            "missing": null,
+        }));
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", value.clone());
+
+        let query = ParseJsonFn::new(Box::new(EncodeJsonFn::new(
+            Box::new(Path::from(vec![vec!["foo"]])),
+            None,
+        )));
+
+        assert_eq!(query.execute(&event), Ok(value.into()));
+    }
+}
@@ -0,0 +1,161 @@
+use super::prelude::*;
+use super::start_of::Period;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct EndOfFn {
+    value: Box<dyn Function>,
+    unit: Box<dyn Function>,
+}
+
+impl EndOfFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>, unit: Box<dyn Function>) -> Self {
+        Self { value, unit }
+    }
+}
+
+impl Function for EndOfFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let timestamp = required_value!(ctx, self.value, Value::Timestamp(v) => v);
+        let unit = required_value!(ctx, self.unit, Value::Bytes(v) => v);
+
+        Ok(Value::Timestamp(Period::parse(&unit)?.end(timestamp)?).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Timestamp(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "unit",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for EndOfFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let unit = arguments.required("unit")?;
+
+        Ok(Self { value, unit })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn check_end_of() {
+        let cases = vec![
+            (
+                Utc.ymd(2020, 2, 12).and_hms(13, 45, 10),
+                "day",
+                Ok(Value::from(Utc.ymd(2020, 2, 12).and_hms_nano(
+                    23,
+                    59,
+                    59,
+                    999_999_999,
+                ))),
+            ),
+            (
+                Utc.ymd(2020, 2, 12).and_hms(13, 45, 10),
+                "week",
+                Ok(Value::from(Utc.ymd(2020, 2, 16).and_hms_nano(
+                    23,
+                    59,
+                    59,
+                    999_999_999,
+                ))),
+            ),
+            (
+                // February of a leap year.
+                Utc.ymd(2020, 2, 12).and_hms(13, 45, 10),
+                "month",
+                Ok(Value::from(Utc.ymd(2020, 2, 29).and_hms_nano(
+                    23,
+                    59,
+                    59,
+                    999_999_999,
+                ))),
+            ),
+            (
+                Utc.ymd(2021, 2, 12).and_hms(13, 45, 10),
+                "month",
+                Ok(Value::from(Utc.ymd(2021, 2, 28).and_hms_nano(
+                    23,
+                    59,
+                    59,
+                    999_999_999,
+                ))),
+            ),
+            (
+                Utc.ymd(2020, 12, 31).and_hms(23, 59, 59),
+                "month",
+                Ok(Value::from(Utc.ymd(2020, 12, 31).and_hms_nano(
+                    23,
+                    59,
+                    59,
+                    999_999_999,
+                ))),
+            ),
+            (
+                Utc.ymd(2020, 2, 12).and_hms(13, 45, 10),
+                "year",
+                Ok(Value::from(Utc.ymd(2020, 12, 31).and_hms_nano(
+                    23,
+                    59,
+                    59,
+                    999_999_999,
+                ))),
+            ),
+            (
+                // The last day chrono can represent.
+                chrono::MAX_DATE.and_hms(0, 0, 0),
+                "month",
+                Err(format!(
+                    "the end of the month of {} is out of range",
+                    chrono::MAX_DATE.and_hms(0, 0, 0).to_rfc3339()
+                )),
+            ),
+            (
+                chrono::MAX_DATE.and_hms(0, 0, 0),
+                "year",
+                Err(format!(
+                    "the end of the year of {} is out of range",
+                    chrono::MAX_DATE.and_hms(0, 0, 0).to_rfc3339()
+                )),
+            ),
+            (
+                Utc.ymd(2020, 2, 12).and_hms(13, 45, 10),
+                "quarter",
+                Err(
+                    "unknown period unit 'quarter', expected one of day, week, month or year"
+                        .to_string(),
+                ),
+            ),
+        ];
+
+        for (timestamp, unit, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(timestamp));
+
+            let query = EndOfFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                Box::new(Literal::from(Value::from(unit))),
+            );
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct EndsWithFn {
+    value: Box<dyn Function>,
+    suffix: Box<dyn Function>,
+    case_insensitive: Option<Box<dyn Function>>,
+}
+
+impl EndsWithFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        suffix: Box<dyn Function>,
+        case_insensitive: Option<Box<dyn Function>>,
+    ) -> Self {
+        Self {
+            value,
+            suffix,
+            case_insensitive,
+        }
+    }
+}
+
+fn lowercase(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).to_lowercase()
+}
+
+impl Function for EndsWithFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let pattern = required_value!(ctx, self.suffix, Value::Bytes(v) => v);
+        let case_insensitive =
+            optional_value!(ctx, self.case_insensitive, Value::Boolean(v) => v).unwrap_or(false);
+
+        let result = if case_insensitive {
+            lowercase(&bytes).ends_with(&lowercase(&pattern))
+        } else {
+            bytes.ends_with(&pattern)
+        };
+
+        Ok(Value::Boolean(result).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "suffix",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "case_insensitive",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Boolean(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for EndsWithFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let suffix = arguments.required("suffix")?;
+        let case_insensitive = arguments.optional("case_insensitive");
+
+        Ok(Self {
+            value,
+            suffix,
+            case_insensitive,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_ends_with() {
+        let cases = vec![
+            ("/var/log/syslog.log", ".log", None, true),
+            ("/var/log/syslog.log", ".txt", None, false),
+            ("/var/log/syslog.LOG", ".log", None, false),
+            ("/var/log/syslog.LOG", ".log", Some(true), true),
+            ("log", "syslog", None, false),
+        ];
+
+        for (value, suffix, case_insensitive, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(value));
+
+            let query = EndsWithFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                Box::new(Literal::from(Value::from(suffix))),
+                case_insensitive.map(|v| Box::new(Literal::from(Value::Boolean(v))) as _),
+            );
+
+            assert_eq!(
+                query.execute(&event),
+                Ok(QueryValue::Value(Value::Boolean(exp)))
+            );
+        }
+    }
+
+    #[test]
+    fn check_ends_with_non_string() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from(5));
+
+        let query = EndsWithFn::new(
+            Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                EndsWithFn::parameters()[0].clone(),
+            )),
+            Box::new(Literal::from(Value::from("5"))),
+            None,
+        );
+
+        assert_eq!(
+            query.execute(&event),
+            Err("invalid argument type 'integer' for parameter 'value'".to_string())
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use super::prelude::*;
+
+/// The type names accepted by `ensure_type`, as returned by `Value::kind`.
+const KINDS: &[&str] = &[
+    "string",
+    "integer",
+    "float",
+    "boolean",
+    "timestamp",
+    "map",
+    "array",
+    "null",
+];
+
+#[derive(Debug)]
+pub(in crate::mapping) struct EnsureTypeFn {
+    value: Box<dyn Function>,
+    kind: Box<dyn Function>,
+}
+
+impl EnsureTypeFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>, kind: Box<dyn Function>) -> Self {
+        Self { value, kind }
+    }
+}
+
+impl Function for EnsureTypeFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let value = required!(ctx, self.value, QueryValue::Value(v) => v);
+        let kind = required_value!(ctx, self.kind, Value::Bytes(v) => v);
+        let kind = String::from_utf8_lossy(&kind);
+
+        if !KINDS.contains(&kind.as_ref()) {
+            return Err(format!(
+                "unknown type '{}', expected one of {}",
+                kind,
+                KINDS.join(", ")
+            ));
+        }
+
+        if value.kind() != kind {
+            return Err(format!(
+                "expected a value of type {}, got {}",
+                kind,
+                value.kind()
+            ));
+        }
+
+        Ok(value.into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "type",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for EnsureTypeFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let kind = arguments.required("type")?;
+
+        Ok(Self { value, kind })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_ensure_type() {
+        let cases = vec![
+            (Value::from(5), "integer", Ok(Value::from(5))),
+            (Value::from("5"), "string", Ok(Value::from("5"))),
+            (Value::Null, "null", Ok(Value::Null)),
+            (
+                Value::from("5"),
+                "integer",
+                Err("expected a value of type integer, got string".to_string()),
+            ),
+            (
+                Value::from(5.0),
+                "integer",
+                Err("expected a value of type integer, got float".to_string()),
+            ),
+            (
+                Value::from(5),
+                "number",
+                Err("unknown type 'number', expected one of string, integer, float, boolean, timestamp, map, array, null".to_string()),
+            ),
+        ];
+
+        for (value, kind, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", value);
+
+            let query = EnsureTypeFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                Box::new(Literal::from(Value::from(kind))),
+            );
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use super::prelude::*;
+
+/// Returns whether a path is present in the event, even if its value is null.
+/// Unlike other functions, a missing path isn't an error.
+#[derive(Debug)]
+pub(in crate::mapping) struct ExistsFn {
+    value: Box<dyn Function>,
+}
+
+impl ExistsFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for ExistsFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let value = self.value.resolve(ctx)?;
+
+        Ok(Value::Boolean(value.is_some()).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |_| true,
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ExistsFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_exists() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert("user.id", Value::from(42));
+        event.as_mut_log().insert("user.name", Value::Null);
+
+        let cases = vec![
+            (vec!["user", "id"], Ok(Value::from(true))),
+            (vec!["user", "name"], Ok(Value::from(true))),
+            (vec!["user"], Ok(Value::from(true))),
+            (vec!["user", "email"], Ok(Value::from(false))),
+            (vec!["account", "id"], Ok(Value::from(false))),
+        ];
+
+        for (path, exp) in cases {
+            let query = ExistsFn::new(Box::new(Path::from(
+                path.into_iter().map(|p| vec![p]).collect::<Vec<_>>(),
+            )));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
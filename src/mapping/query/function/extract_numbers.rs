@@ -0,0 +1,118 @@
+use super::prelude::*;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref NUMBER_RE: Regex = Regex::new(r"-?\d+(\.\d+)?").unwrap();
+}
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ExtractNumbersFn {
+    value: Box<dyn Function>,
+}
+
+impl ExtractNumbersFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for ExtractNumbersFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let string = String::from_utf8_lossy(&bytes);
+
+        let numbers = NUMBER_RE
+            .captures_iter(&string)
+            .filter_map(|captures| {
+                let token = captures.get(0)?.as_str();
+
+                // Integers too large for an i64 are returned as floats.
+                match captures.get(1) {
+                    None => token
+                        .parse::<i64>()
+                        .map(Value::Integer)
+                        .or_else(|_| token.parse::<f64>().map(Value::Float))
+                        .ok(),
+                    Some(_) => token.parse::<f64>().map(Value::Float).ok(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Value::Array(numbers).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ExtractNumbersFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_extract_numbers() {
+        let cases = vec![
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from("took 42 requests in 3.14s, -7 failed"));
+                    event
+                },
+                Ok(Value::Array(vec![
+                    Value::Integer(42),
+                    Value::Float(3.14),
+                    Value::Integer(-7),
+                ])),
+                ExtractNumbersFn::new(Box::new(Path::from(vec![vec!["foo"]]))),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from("no numbers here"));
+                    event
+                },
+                Ok(Value::Array(vec![])),
+                ExtractNumbersFn::new(Box::new(Path::from(vec![vec!["foo"]]))),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from(42));
+                    event
+                },
+                Err("invalid argument type 'integer' for parameter 'value'".to_string()),
+                ExtractNumbersFn::new(Box::new(Argument::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    ExtractNumbersFn::parameters()[0].clone(),
+                ))),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use super::prelude::*;
+use crate::mapping::query::bound::Bound;
+
+/// Returns the value of the first of several queries for which a predicate
+/// function returns true, or null if there is none, e.g.
+/// `first_matching([.a, .b, .c], semver_gte, "1.2.0")`.
+///
+/// The predicate is supplied the same way as for `index_where`: it reads
+/// each candidate value as its first argument, bound to `element`. Paths that
+/// don't exist in the event are skipped.
+#[derive(Debug)]
+pub(in crate::mapping) struct FirstMatchingFn {
+    values: Vec<Box<dyn Function>>,
+    element: Bound,
+    predicate: Box<dyn Function>,
+}
+
+impl FirstMatchingFn {
+    pub(in crate::mapping) fn new(
+        values: Vec<Box<dyn Function>>,
+        element: Bound,
+        predicate: Box<dyn Function>,
+    ) -> Self {
+        Self {
+            values,
+            element,
+            predicate,
+        }
+    }
+}
+
+impl Function for FirstMatchingFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        for query in &self.values {
+            let value = match query.resolve(ctx)? {
+                Some(QueryValue::Value(value)) => value,
+                Some(v) => {
+                    return Err(format!(
+                        "unable to match {} value, expected a value",
+                        v.kind()
+                    ))
+                }
+                None => continue,
+            };
+
+            match self
+                .element
+                .bind(value.clone(), || self.predicate.execute(ctx))?
+            {
+                QueryValue::Value(Value::Boolean(true)) => return Ok(value.into()),
+                QueryValue::Value(Value::Boolean(false)) => (),
+                v => {
+                    return Err(format!(
+                        "predicate returned non-boolean value of type {}",
+                        v.kind()
+                    ))
+                }
+            }
+        }
+
+        Ok(Value::Null.into())
+    }
+
+    fn describe(&self) -> String {
+        let values = self
+            .values
+            .iter()
+            .map(|query| query.describe())
+            .collect::<Vec<_>>();
+
+        format!(
+            "the first of {} matching {}",
+            values.join(", "),
+            self.predicate.describe()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::SemverGteFn;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_first_matching() {
+        let first_matching = |paths: &[&str]| {
+            let element = Bound::new("element");
+            FirstMatchingFn::new(
+                paths
+                    .iter()
+                    .map(|path| Box::new(Path::from(vec![vec![*path]])) as _)
+                    .collect(),
+                element,
+                Box::new(SemverGteFn::new(
+                    Box::new(element),
+                    Box::new(Literal::from(Value::from("1.2.0"))),
+                )),
+            )
+        };
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("a", Value::from("1.0.0"));
+        event.as_mut_log().insert("b", Value::from("1.4.0"));
+        event.as_mut_log().insert("c", Value::from("2.0.0"));
+
+        let cases = vec![
+            (vec!["a", "b", "c"], Ok(Value::from("1.4.0"))),
+            (vec!["missing", "a", "c"], Ok(Value::from("2.0.0"))),
+            (vec!["a", "missing"], Ok(Value::Null)),
+        ];
+
+        for (paths, exp) in cases {
+            assert_eq!(
+                first_matching(&paths).execute(&event),
+                exp.map(QueryValue::Value)
+            );
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use super::prelude::*;
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct FlattenOneFn {
+    value: Box<dyn Function>,
+    separator: Option<Box<dyn Function>>,
+    overwrite: Option<Box<dyn Function>>,
+}
+
+impl FlattenOneFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        separator: Option<Box<dyn Function>>,
+        overwrite: Option<Box<dyn Function>>,
+    ) -> Self {
+        Self {
+            value,
+            separator,
+            overwrite,
+        }
+    }
+}
+
+impl Function for FlattenOneFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let map = required_value!(ctx, self.value, Value::Map(v) => v);
+        let separator = optional_value!(ctx, self.separator, Value::Bytes(v) => v)
+            .map(|v| String::from_utf8_lossy(&v).into_owned())
+            .unwrap_or_else(|| ".".to_owned());
+        let overwrite =
+            optional_value!(ctx, self.overwrite, Value::Boolean(v) => v).unwrap_or(false);
+
+        let mut flattened = BTreeMap::new();
+        let mut insert = |key: String, value: Value| -> Result<()> {
+            // Fields are visited in key order, so when overwriting, the
+            // last field in that order wins.
+            if flattened.insert(key.clone(), value).is_some() && !overwrite {
+                return Err(format!("flattened key {} already exists", key));
+            }
+            Ok(())
+        };
+
+        for (key, value) in map {
+            match value {
+                Value::Map(children) => {
+                    for (child_key, child_value) in children {
+                        insert(format!("{}{}{}", key, separator, child_key), child_value)?;
+                    }
+                }
+                value => insert(key, value)?,
+            }
+        }
+
+        Ok(Value::Map(flattened).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Map(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "separator",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: false,
+            },
+            Parameter {
+                keyword: "overwrite",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Boolean(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for FlattenOneFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let separator = arguments.optional("separator");
+        let overwrite = arguments.optional("overwrite");
+
+        Ok(Self {
+            value,
+            separator,
+            overwrite,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_flatten_one() {
+        let cases = vec![
+            (
+                {
+                    let mut event = Event::from("");
+                    let map = json!({"parent": {"child": {"grandchild": 1}, "sibling": 2},
+                                     "key": "val"});
+                    event.as_mut_log().insert("foo", Value::from(map));
+                    event
+                },
+                Ok(Value::from(json!({"parent__child": {"grandchild": 1},
+                                      "parent__sibling": 2,
+                                      "key": "val"}))),
+                FlattenOneFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Some(Box::new(Literal::from(Value::from("__")))),
+                    None,
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    let map = json!({"parent": {"child": 1}});
+                    event.as_mut_log().insert("foo", Value::from(map));
+                    event
+                },
+                Ok(Value::from(json!({"parent.child": 1}))),
+                FlattenOneFn::new(Box::new(Path::from(vec![vec!["foo"]])), None, None),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    let map = json!({"a": {"b": 1}, "a_b": 2});
+                    event.as_mut_log().insert("foo", Value::from(map));
+                    event
+                },
+                Err("flattened key a_b already exists".to_string()),
+                FlattenOneFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Some(Box::new(Literal::from(Value::from("_")))),
+                    None,
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    let map = json!({"a": {"b": 1}, "a_b": 2});
+                    event.as_mut_log().insert("foo", Value::from(map));
+                    event
+                },
+                Ok(Value::from(json!({"a_b": 2}))),
+                FlattenOneFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Some(Box::new(Literal::from(Value::from("_")))),
+                    Some(Box::new(Literal::from(Value::Boolean(true)))),
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+}
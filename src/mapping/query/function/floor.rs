@@ -0,0 +1,80 @@
+use super::prelude::*;
+
+/// Rounds a number down to the nearest integer. Floats are kept as floats, and
+/// integers are returned unchanged.
+#[derive(Debug)]
+pub(in crate::mapping) struct FloorFn {
+    value: Box<dyn Function>,
+}
+
+impl FloorFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for FloorFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let value = required_value!(ctx, self.value,
+            Value::Integer(v) => Value::Integer(v),
+            Value::Float(v) => Value::Float(v.floor()),
+        );
+
+        Ok(value.into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| {
+                matches!(
+                    v,
+                    QueryValue::Value(Value::Integer(_)) | QueryValue::Value(Value::Float(_))
+                )
+            },
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for FloorFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{function::Argument, path::Path};
+
+    #[test]
+    fn check_floor() {
+        let cases = vec![
+            (Value::from(2.7), Ok(Value::from(2.0))),
+            (Value::from(-2.1), Ok(Value::from(-3.0))),
+            (Value::from(7), Ok(Value::from(7))),
+            (
+                Value::from("1.5"),
+                Err("invalid argument type 'string' for parameter 'value'".to_string()),
+            ),
+        ];
+
+        for (value, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", value);
+
+            let query = FloorFn::new(Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                FloorFn::parameters()[0].clone(),
+            )));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ForceUtf8Fn {
+    value: Box<dyn Function>,
+}
+
+impl ForceUtf8Fn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for ForceUtf8Fn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+
+        // Valid input is returned as is, without copying the underlying bytes.
+        if std::str::from_utf8(&bytes).is_ok() {
+            return Ok(Value::Bytes(bytes).into());
+        }
+
+        Ok(Value::from(String::from_utf8_lossy(&bytes).into_owned()).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ForceUtf8Fn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+    use bytes::Bytes;
+
+    #[test]
+    fn check_force_utf8() {
+        let cases = vec![
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from("Zürich ✓"));
+                    event
+                },
+                Ok(Value::from("Zürich ✓")),
+                ForceUtf8Fn::new(Box::new(Path::from(vec![vec!["foo"]]))),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from(Bytes::from_static(b"foo\xffbar\xc3")));
+                    event
+                },
+                Ok(Value::from("foo\u{fffd}bar\u{fffd}")),
+                ForceUtf8Fn::new(Box::new(Path::from(vec![vec!["foo"]]))),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+}
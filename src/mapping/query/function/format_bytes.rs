@@ -0,0 +1,180 @@
+use super::prelude::*;
+
+pub(super) const BINARY_UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+pub(super) const SI_UNITS: &[&str] = &["B", "kB", "MB", "GB", "TB", "PB", "EB"];
+
+/// The largest number of decimal places a size can be formatted with.
+const MAX_PRECISION: i64 = 20;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct FormatBytesFn {
+    value: Box<dyn Function>,
+    si: Option<Box<dyn Function>>,
+    precision: Option<Box<dyn Function>>,
+}
+
+impl FormatBytesFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        si: Option<Box<dyn Function>>,
+        precision: Option<Box<dyn Function>>,
+    ) -> Self {
+        Self {
+            value,
+            si,
+            precision,
+        }
+    }
+}
+
+impl Function for FormatBytesFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let size = required_value!(ctx, self.value,
+            Value::Integer(v) => v as f64,
+            Value::Float(v) => v,
+        );
+        let si = optional_value!(ctx, self.si, Value::Boolean(v) => v).unwrap_or(false);
+        let precision = optional_value!(ctx, self.precision, Value::Integer(v) => v).unwrap_or(1);
+
+        if size < 0.0 {
+            return Err(format!("unable to format negative size {}", size));
+        }
+
+        if precision < 0 {
+            return Err(format!("precision must be non-negative, got {}", precision));
+        }
+
+        if precision > MAX_PRECISION {
+            return Err(format!(
+                "precision must be at most {}, got {}",
+                MAX_PRECISION, precision
+            ));
+        }
+
+        let (base, units) = if si {
+            (1000.0, SI_UNITS)
+        } else {
+            (1024.0, BINARY_UNITS)
+        };
+
+        let mut size = size;
+        let mut unit = 0;
+        while size >= base && unit < units.len() - 1 {
+            size /= base;
+            unit += 1;
+        }
+
+        // Sizes below the first threshold are shown as given, so that a whole
+        // number of bytes has no decimal places.
+        let formatted = if unit == 0 {
+            format!("{} {}", size, units[unit])
+        } else {
+            format!("{:.*} {}", precision as usize, size, units[unit])
+        };
+
+        Ok(Value::from(formatted).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| {
+                    matches!(
+                        v,
+                        QueryValue::Value(Value::Integer(_)) | QueryValue::Value(Value::Float(_))
+                    )
+                },
+                required: true,
+            },
+            Parameter {
+                keyword: "si",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Boolean(_))),
+                required: false,
+            },
+            Parameter {
+                keyword: "precision",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Integer(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for FormatBytesFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let si = arguments.optional("si");
+        let precision = arguments.optional("precision");
+
+        Ok(Self {
+            value,
+            si,
+            precision,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_format_bytes() {
+        let cases = vec![
+            (Value::from(0), None, None, Ok("0 B")),
+            (Value::from(1023), None, None, Ok("1023 B")),
+            (Value::from(1024), None, None, Ok("1.0 KiB")),
+            (Value::from(1536), None, None, Ok("1.5 KiB")),
+            (Value::from(1_048_575), None, Some(3), Ok("1023.999 KiB")),
+            (Value::from(1_048_576), None, None, Ok("1.0 MiB")),
+            (Value::from(5_767_168), None, Some(0), Ok("6 MiB")),
+            (Value::from(1536), Some(true), None, Ok("1.5 kB")),
+            (Value::from(999), Some(true), None, Ok("999 B")),
+            (Value::from(2_500_000), Some(true), Some(2), Ok("2.50 MB")),
+            (Value::from(1536.0), None, None, Ok("1.5 KiB")),
+            (Value::from(1.5), None, None, Ok("1.5 B")),
+            (
+                Value::from(1536),
+                None,
+                Some(20),
+                Ok("1.50000000000000000000 KiB"),
+            ),
+            (
+                Value::from(-1),
+                None,
+                None,
+                Err("unable to format negative size -1"),
+            ),
+            (
+                Value::from(1),
+                None,
+                Some(-1),
+                Err("precision must be non-negative, got -1"),
+            ),
+            (
+                Value::from(1),
+                None,
+                Some(i64::MAX),
+                Err("precision must be at most 20, got 9223372036854775807"),
+            ),
+        ];
+
+        for (value, si, precision, exp) in cases {
+            let query = FormatBytesFn::new(
+                Box::new(Literal::from(value)),
+                si.map(|v| Box::new(Literal::from(Value::Boolean(v))) as Box<dyn Function>),
+                precision.map(|v| Box::new(Literal::from(Value::from(v))) as Box<dyn Function>),
+            );
+
+            assert_eq!(
+                query.execute(&Event::from("")),
+                exp.map(|v| QueryValue::Value(Value::from(v)))
+                    .map_err(ToOwned::to_owned)
+            );
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use super::prelude::*;
+use chrono::format::{strftime::StrftimeItems, Item};
+
+#[derive(Debug)]
+pub(in crate::mapping) struct FormatTimestampFn {
+    value: Box<dyn Function>,
+    format: Box<dyn Function>,
+}
+
+impl FormatTimestampFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>, format: Box<dyn Function>) -> Self {
+        Self { value, format }
+    }
+}
+
+impl Function for FormatTimestampFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let timestamp = required_value!(ctx, self.value, Value::Timestamp(v) => v);
+        let format = required_value!(ctx, self.format, Value::Bytes(v) => v);
+        let format = String::from_utf8_lossy(&format);
+
+        // Rendering an invalid format panics, so it is checked up front.
+        let items = StrftimeItems::new(&format)
+            .map(|item| match item {
+                Item::Error => Err(format!("invalid timestamp format {:?}", format)),
+                _ => Ok(item),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Value::from(timestamp.format_with_items(items.into_iter()).to_string()).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Timestamp(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "format",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for FormatTimestampFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let format = arguments.required("format")?;
+
+        Ok(Self { value, format })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::ParseTimestampFn;
+    use crate::mapping::query::{function::Argument, path::Path};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn check_format_timestamp() {
+        let cases = vec![
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from(Utc.ymd(2019, 10, 16).and_hms(11, 0, 0)));
+                    event
+                },
+                Ok(Value::from("2019-10-16T11:00:00Z")),
+                FormatTimestampFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from("%Y-%m-%dT%H:%M:%SZ"))),
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert(
+                        "foo",
+                        Value::from(Utc.ymd(2019, 10, 16).and_hms_micro(11, 0, 0, 123_456)),
+                    );
+                    event
+                },
+                Ok(Value::from("2019-10-16T11:00:00.123Z")),
+                FormatTimestampFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from("%Y-%m-%dT%H:%M:%S%.3fZ"))),
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from(Utc.ymd(2019, 10, 16).and_hms(11, 0, 0)));
+                    event
+                },
+                Err("invalid timestamp format \"%Q\"".to_string()),
+                FormatTimestampFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from("%Q"))),
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from("2019-10-16T11:00:00Z"));
+                    event
+                },
+                Err("invalid argument type 'string' for parameter 'value'".to_string()),
+                FormatTimestampFn::new(
+                    Box::new(Argument::new(
+                        Box::new(Path::from(vec![vec!["foo"]])),
+                        FormatTimestampFn::parameters()[0].clone(),
+                    )),
+                    Box::new(Literal::from(Value::from("%Y-%m-%dT%H:%M:%SZ"))),
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut event = Event::from("");
+        event
+            .as_mut_log()
+            .insert("foo", Value::from("2019-10-16T11:00:00+0200"));
+
+        let format = "%Y-%m-%dT%H:%M:%S%z";
+        let query = FormatTimestampFn::new(
+            Box::new(ParseTimestampFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                Box::new(Literal::from(Value::from(format))),
+                None,
+            )),
+            Box::new(Literal::from(Value::from(format))),
+        );
+
+        assert_eq!(
+            query.execute(&event),
+            Ok(Value::from("2019-10-16T09:00:00+0000").into())
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use super::prelude::*;
+
+/// Groups runs of equal consecutive elements of an array into `[value, count]`
+/// pairs, e.g. `["ok", "ok", "err"]` becomes `[["ok", 2], ["err", 1]]`.
+#[derive(Debug)]
+pub(in crate::mapping) struct GroupConsecutiveFn {
+    value: Box<dyn Function>,
+}
+
+impl GroupConsecutiveFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for GroupConsecutiveFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let array = required_value!(ctx, self.value, Value::Array(v) => v);
+
+        let mut runs: Vec<(Value, i64)> = Vec::new();
+        for element in array {
+            match runs.last_mut() {
+                Some((value, count)) if *value == element => *count += 1,
+                _ => runs.push((element, 1)),
+            }
+        }
+
+        let runs = runs
+            .into_iter()
+            .map(|(value, count)| Value::Array(vec![value, Value::Integer(count)]))
+            .collect();
+
+        Ok(Value::Array(runs).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Array(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for GroupConsecutiveFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{function::Argument, path::Path};
+    use serde_json::json;
+
+    #[test]
+    fn check_group_consecutive() {
+        let cases = vec![
+            (
+                json!(["ok", "ok", "ok", "err", "ok", "ok"]),
+                Ok(Value::from(json!([["ok", 3], ["err", 1], ["ok", 2]]))),
+            ),
+            (json!(["ok"]), Ok(Value::from(json!([["ok", 1]])))),
+            (json!([]), Ok(Value::from(json!([])))),
+            (
+                json!([{"a": 1}, {"a": 1}, {"a": 2}, 1, 1.0, null, null]),
+                Ok(Value::from(json!([
+                    [{"a": 1}, 2],
+                    [{"a": 2}, 1],
+                    [1, 1],
+                    [1.0, 1],
+                    [null, 2]
+                ]))),
+            ),
+            (
+                json!("ok"),
+                Err("invalid argument type 'string' for parameter 'value'".to_string()),
+            ),
+        ];
+
+        for (value, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(value));
+
+            let query = GroupConsecutiveFn::new(Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                GroupConsecutiveFn::parameters()[0].clone(),
+            )));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct HttpStatusClassFn {
+    value: Box<dyn Function>,
+}
+
+impl HttpStatusClassFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for HttpStatusClassFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let status = required_value!(ctx, self.value,
+            Value::Integer(v) => v,
+            Value::Bytes(v) => {
+                let status = String::from_utf8_lossy(&v);
+                status
+                    .trim()
+                    .parse::<i64>()
+                    .map_err(|_| format!("invalid HTTP status code: {}", status))?
+            },
+        );
+
+        if !(100..=599).contains(&status) {
+            return Err(format!("invalid HTTP status code: {}", status));
+        }
+
+        Ok(Value::from(format!("{}xx", status / 100)).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| {
+                matches!(
+                    v,
+                    QueryValue::Value(Value::Integer(_)) | QueryValue::Value(Value::Bytes(_))
+                )
+            },
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for HttpStatusClassFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_http_status_class() {
+        let cases = vec![
+            (Value::from(200), Ok("2xx")),
+            (Value::from(404), Ok("4xx")),
+            (Value::from("503"), Ok("5xx")),
+            (Value::from(100), Ok("1xx")),
+            (Value::from(999), Err("invalid HTTP status code: 999")),
+            (Value::from(99), Err("invalid HTTP status code: 99")),
+            (Value::from("OK"), Err("invalid HTTP status code: OK")),
+        ];
+
+        for (status, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", status);
+
+            let query = HttpStatusClassFn::new(Box::new(Path::from(vec![vec!["foo"]])));
+
+            assert_eq!(
+                query.execute(&event),
+                exp.map(|v| QueryValue::Value(Value::from(v)))
+                    .map_err(ToOwned::to_owned)
+            );
+        }
+    }
+}
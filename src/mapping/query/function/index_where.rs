@@ -0,0 +1,143 @@
+use super::prelude::*;
+use crate::mapping::query::bound::Bound;
+
+/// Returns the index of the first element of an array for which a predicate
+/// function returns true, or -1 if there is none.
+///
+/// The predicate reads each element as its first argument, bound to
+/// `element`, so any other arguments of the predicate can still refer to
+/// fields of the event.
+#[derive(Debug)]
+pub(in crate::mapping) struct IndexWhereFn {
+    value: Box<dyn Function>,
+    element: Bound,
+    predicate: Box<dyn Function>,
+}
+
+impl IndexWhereFn {
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        element: Bound,
+        predicate: Box<dyn Function>,
+    ) -> Self {
+        Self {
+            value,
+            element,
+            predicate,
+        }
+    }
+}
+
+impl Function for IndexWhereFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let array = match self.value.execute(ctx)? {
+            QueryValue::Value(Value::Array(array)) => array,
+            v => {
+                return Err(format!(
+                    "unable to search {} value, expected an array",
+                    v.kind()
+                ))
+            }
+        };
+
+        for (i, element) in array.into_iter().enumerate() {
+            match self.element.bind(element, || self.predicate.execute(ctx))? {
+                QueryValue::Value(Value::Boolean(true)) => {
+                    return Ok(Value::Integer(i as i64).into())
+                }
+                QueryValue::Value(Value::Boolean(false)) => (),
+                v => {
+                    return Err(format!(
+                        "predicate returned non-boolean value of type {}",
+                        v.kind()
+                    ))
+                }
+            }
+        }
+
+        Ok(Value::Integer(-1).into())
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "index of the first element of {} matching {}",
+            self.value.describe(),
+            self.predicate.describe()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::{NormalizeNumericFn, SemverGteFn};
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_index_where() {
+        let element = Bound::new("element");
+        let semver_gte = || {
+            Box::new(SemverGteFn::new(
+                Box::new(element),
+                Box::new(Path::from(vec![vec!["min"]])),
+            ))
+        };
+
+        let cases = vec![
+            (
+                json!(["1.0.0", "1.1.9", "1.2.0", "2.0.0"]),
+                Ok(Value::from(2)),
+                IndexWhereFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    element,
+                    semver_gte(),
+                ),
+            ),
+            (
+                json!(["1.0.0", "1.1.9"]),
+                Ok(Value::from(-1)),
+                IndexWhereFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    element,
+                    semver_gte(),
+                ),
+            ),
+            (
+                json!([]),
+                Ok(Value::from(-1)),
+                IndexWhereFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    element,
+                    semver_gte(),
+                ),
+            ),
+            (
+                json!(["1"]),
+                Err("predicate returned non-boolean value of type integer".to_string()),
+                IndexWhereFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    element,
+                    Box::new(NormalizeNumericFn::new(Box::new(element))),
+                ),
+            ),
+            (
+                json!("1.2.0"),
+                Err("unable to search string value, expected an array".to_string()),
+                IndexWhereFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    element,
+                    semver_gte(),
+                ),
+            ),
+        ];
+
+        for (value, exp, query) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(value));
+            event.as_mut_log().insert("min", Value::from("1.2.0"));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
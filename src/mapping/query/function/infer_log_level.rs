@@ -0,0 +1,182 @@
+use super::prelude::*;
+use std::collections::BTreeMap;
+
+/// The tokens recognized when no mapping is given, and the levels they map
+/// to.
+const DEFAULT_LEVELS: &[(&str, &str)] = &[
+    ("TRACE", "trace"),
+    ("DEBUG", "debug"),
+    ("INFO", "info"),
+    ("NOTICE", "info"),
+    ("WARN", "warn"),
+    ("WARNING", "warn"),
+    ("ERR", "error"),
+    ("ERROR", "error"),
+    ("CRIT", "fatal"),
+    ("CRITICAL", "fatal"),
+    ("FATAL", "fatal"),
+];
+
+/// The level returned when no default is given.
+const DEFAULT_LEVEL: &str = "info";
+
+#[derive(Debug)]
+pub(in crate::mapping) struct InferLogLevelFn {
+    value: Box<dyn Function>,
+    levels: Option<Box<dyn Function>>,
+    default: Option<Box<dyn Function>>,
+}
+
+impl InferLogLevelFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        levels: Option<Box<dyn Function>>,
+        default: Option<Box<dyn Function>>,
+    ) -> Self {
+        Self {
+            value,
+            levels,
+            default,
+        }
+    }
+}
+
+impl Function for InferLogLevelFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let default = optional_value!(ctx, self.default, Value::Bytes(v) => v);
+
+        // Tokens are matched case-insensitively, so they're kept upper case.
+        let levels = match optional_value!(ctx, self.levels, Value::Map(v) => v) {
+            Some(levels) => levels
+                .into_iter()
+                .map(|(token, level)| match level {
+                    Value::Bytes(level) => Ok((token.to_uppercase(), Value::Bytes(level))),
+                    v => Err(format!(
+                        "level for token {} must be a string, got {}",
+                        token,
+                        v.kind()
+                    )),
+                })
+                .collect::<Result<BTreeMap<_, _>>>()?,
+            None => DEFAULT_LEVELS
+                .iter()
+                .map(|(token, level)| ((*token).to_owned(), Value::from((*level).to_owned())))
+                .collect(),
+        };
+
+        // The first recognized word of the message determines the level, as
+        // later words are more likely to be part of the message itself.
+        let message = String::from_utf8_lossy(&bytes);
+        let level = message
+            .split(|c: char| !c.is_alphanumeric())
+            .find_map(|word| levels.get(&word.to_uppercase()));
+
+        Ok(match (level, default) {
+            (Some(level), _) => level.clone(),
+            (None, Some(default)) => Value::Bytes(default),
+            (None, None) => Value::from(DEFAULT_LEVEL.to_owned()),
+        }
+        .into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "levels",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Map(_))),
+                required: false,
+            },
+            Parameter {
+                keyword: "default",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for InferLogLevelFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let levels = arguments.optional("levels");
+        let default = arguments.optional("default");
+
+        Ok(Self {
+            value,
+            levels,
+            default,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_infer_log_level() {
+        let cases = vec![
+            (
+                "2020-12-01 ERROR failed to connect",
+                None,
+                None,
+                Ok("error"),
+            ),
+            ("[warn] disk almost full", None, None, Ok("warn")),
+            ("FATAL: out of memory, error", None, None, Ok("fatal")),
+            ("server started", None, None, Ok("info")),
+            ("server started", None, Some("unknown"), Ok("unknown")),
+            // Only whole words are recognized.
+            ("no errors found", None, Some("unknown"), Ok("unknown")),
+            (
+                "E1201 failed to connect",
+                Some(json!({"E1201": "error", "W1201": "warn"})),
+                None,
+                Ok("error"),
+            ),
+            // A custom mapping replaces the built-in one.
+            (
+                "ERROR failed to connect",
+                Some(json!({"E1201": "error"})),
+                Some("unknown"),
+                Ok("unknown"),
+            ),
+            (
+                "E1201 failed to connect",
+                Some(json!({"E1201": 3})),
+                None,
+                Err("level for token E1201 must be a string, got integer"),
+            ),
+        ];
+
+        for (message, levels, default, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(message));
+
+            let query = InferLogLevelFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                levels.map(|v| Box::new(Literal::from(Value::from(v))) as _),
+                default.map(|v| Box::new(Literal::from(Value::from(v))) as _),
+            );
+
+            assert_eq!(
+                query.execute(&event),
+                exp.map(|v| QueryValue::Value(Value::from(v)))
+                    .map_err(|e| e.to_string()),
+                "{}",
+                message
+            );
+        }
+    }
+}
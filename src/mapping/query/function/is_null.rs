@@ -0,0 +1,69 @@
+use super::prelude::*;
+
+/// Returns whether a path is present in the event with a null value. A missing
+/// path isn't an error, and isn't null.
+#[derive(Debug)]
+pub(in crate::mapping) struct IsNullFn {
+    value: Box<dyn Function>,
+}
+
+impl IsNullFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for IsNullFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let value = self.value.resolve(ctx)?;
+
+        Ok(Value::Boolean(matches!(value, Some(QueryValue::Value(Value::Null)))).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |_| true,
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for IsNullFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_is_null() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert("user.id", Value::from(42));
+        event.as_mut_log().insert("user.name", Value::Null);
+
+        let cases = vec![
+            (vec!["user", "id"], Ok(Value::from(false))),
+            (vec!["user", "name"], Ok(Value::from(true))),
+            (vec!["user", "email"], Ok(Value::from(false))),
+            (vec!["account", "id"], Ok(Value::from(false))),
+        ];
+
+        for (path, exp) in cases {
+            let query = IsNullFn::new(Box::new(Path::from(
+                path.into_iter().map(|p| vec![p]).collect::<Vec<_>>(),
+            )));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
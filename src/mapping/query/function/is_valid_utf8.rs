@@ -0,0 +1,76 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct IsValidUtf8Fn {
+    value: Box<dyn Function>,
+}
+
+impl IsValidUtf8Fn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for IsValidUtf8Fn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+
+        Ok(Value::Boolean(std::str::from_utf8(&bytes).is_ok()).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for IsValidUtf8Fn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+    use bytes::Bytes;
+
+    #[test]
+    fn check_is_valid_utf8() {
+        let cases = vec![
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from("Zürich ✓"));
+                    event
+                },
+                Ok(Value::Boolean(true)),
+                IsValidUtf8Fn::new(Box::new(Path::from(vec![vec!["foo"]]))),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from(Bytes::from_static(b"foo\xffbar\xc3")));
+                    event
+                },
+                Ok(Value::Boolean(false)),
+                IsValidUtf8Fn::new(Box::new(Path::from(vec![vec!["foo"]]))),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct JsonEscapeFn {
+    value: Box<dyn Function>,
+}
+
+impl JsonEscapeFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for JsonEscapeFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let string = String::from_utf8_lossy(&bytes);
+
+        // Serializing a string always produces a quoted JSON string, of
+        // which we only want the escaped contents.
+        let escaped = serde_json::to_string(&string).map_err(|err| err.to_string())?;
+        let escaped = &escaped[1..escaped.len() - 1];
+
+        Ok(Value::from(escaped.to_owned()).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for JsonEscapeFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_json_escape() {
+        let cases = vec![
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from("say \"hi\"\nC:\\temp\t\u{1}"));
+                    event
+                },
+                Ok(Value::from(r#"say \"hi\"\nC:\\temp\t\u0001"#)),
+                JsonEscapeFn::new(Box::new(Path::from(vec![vec!["foo"]]))),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from("plain"));
+                    event
+                },
+                Ok(Value::from("plain")),
+                JsonEscapeFn::new(Box::new(Path::from(vec![vec!["foo"]]))),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from(""));
+                    event
+                },
+                Ok(Value::from("")),
+                JsonEscapeFn::new(Box::new(Path::from(vec![vec!["foo"]]))),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+}
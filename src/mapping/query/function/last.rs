@@ -0,0 +1,84 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct LastFn {
+    value: Box<dyn Function>,
+}
+
+impl LastFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for LastFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let array = required_value!(ctx, self.value, Value::Array(v) => v);
+
+        Ok(array.last().cloned().unwrap_or(Value::Null).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Array(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for LastFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{function::Argument, path::Path};
+
+    #[test]
+    fn check_last() {
+        let cases = vec![
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert(
+                        "foo",
+                        Value::from(vec![Value::from(1), Value::from(2), Value::from(3)]),
+                    );
+                    event
+                },
+                Ok(Value::from(3)),
+                LastFn::new(Box::new(Path::from(vec![vec!["foo"]]))),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::Array(vec![]));
+                    event
+                },
+                Ok(Value::Null),
+                LastFn::new(Box::new(Path::from(vec![vec!["foo"]]))),
+            ),
+            (
+                Event::from(""),
+                Err("invalid argument type 'string' for parameter 'value'".to_string()),
+                LastFn::new(Box::new(Argument::new(
+                    Box::new(Literal::from(Value::from("foo"))),
+                    LastFn::parameters()[0].clone(),
+                ))),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use super::prelude::*;
+
+/// Returns the number of bytes in a string, or the number of elements in an
+/// array or entries in a map.
+///
+/// Other values don't have a meaningful length, so they are rejected rather
+/// than given one.
+#[derive(Debug)]
+pub(in crate::mapping) struct LengthFn {
+    value: Box<dyn Function>,
+}
+
+impl LengthFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for LengthFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let length = required_value!(ctx, self.value,
+            Value::Bytes(v) => v.len(),
+            Value::Array(v) => v.len(),
+            Value::Map(v) => v.len(),
+        );
+
+        Ok(Value::Integer(length as i64).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| {
+                matches!(
+                    v,
+                    QueryValue::Value(Value::Bytes(_))
+                        | QueryValue::Value(Value::Array(_))
+                        | QueryValue::Value(Value::Map(_))
+                )
+            },
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for LengthFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{function::Argument, path::Path};
+    use serde_json::json;
+
+    #[test]
+    fn check_length() {
+        let cases = vec![
+            (Value::from("hello"), Ok(Value::from(5))),
+            (Value::from(""), Ok(Value::from(0))),
+            // Bytes are counted, not characters.
+            (Value::from("héllo ☃"), Ok(Value::from(10))),
+            (Value::from(json!([1, "two", null])), Ok(Value::from(3))),
+            (Value::from(json!([])), Ok(Value::from(0))),
+            (
+                Value::from(json!({"a": 1, "b": {"c": 2}})),
+                Ok(Value::from(2)),
+            ),
+            (
+                Value::from(42),
+                Err("invalid argument type 'integer' for parameter 'value'".to_string()),
+            ),
+            (
+                Value::from(true),
+                Err("invalid argument type 'boolean' for parameter 'value'".to_string()),
+            ),
+        ];
+
+        for (value, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", value);
+
+            let query = LengthFn::new(Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                LengthFn::parameters()[0].clone(),
+            )));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
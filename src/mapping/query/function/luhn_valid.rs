@@ -0,0 +1,112 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct LuhnValidFn {
+    value: Box<dyn Function>,
+}
+
+impl LuhnValidFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+/// Validates the checksum of a number using the Luhn algorithm.
+///
+/// Spaces and dashes are ignored. Any other non-digit character, or a lack of
+/// digits, makes the number invalid.
+fn luhn_valid(bytes: &[u8]) -> bool {
+    let digits = bytes
+        .iter()
+        .filter(|b| !matches!(b, b' ' | b'-'))
+        .rev()
+        .map(|b| match b {
+            b'0'..=b'9' => Some(u32::from(b - b'0')),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>();
+
+    match digits {
+        Some(digits) if !digits.is_empty() => {
+            let sum: u32 = digits
+                .into_iter()
+                .enumerate()
+                .map(|(i, digit)| match (i % 2, digit * 2) {
+                    (0, _) => digit,
+                    (_, doubled) if doubled > 9 => doubled - 9,
+                    (_, doubled) => doubled,
+                })
+                .sum();
+
+            sum % 10 == 0
+        }
+        _ => false,
+    }
+}
+
+impl Function for LuhnValidFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+
+        Ok(Value::Boolean(luhn_valid(&bytes)).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for LuhnValidFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_luhn_valid() {
+        let cases = vec![
+            ("4539 3195 0343 6467", Ok(Value::Boolean(true))),
+            ("4539-3195-0343-6467", Ok(Value::Boolean(true))),
+            ("79927398713", Ok(Value::Boolean(true))),
+            ("79927398710", Ok(Value::Boolean(false))),
+            ("4539 3195 0343 6468", Ok(Value::Boolean(false))),
+            ("4539x3195", Ok(Value::Boolean(false))),
+            (" - ", Ok(Value::Boolean(false))),
+        ];
+
+        for (input, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(input));
+            let query = LuhnValidFn::new(Box::new(Path::from(vec![vec!["foo"]])));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from(42));
+        let query = LuhnValidFn::new(Box::new(Argument::new(
+            Box::new(Path::from(vec![vec!["foo"]])),
+            LuhnValidFn::parameters()[0].clone(),
+        )));
+
+        assert_eq!(
+            query.execute(&event),
+            Err("invalid argument type 'integer' for parameter 'value'".to_string())
+        );
+    }
+}
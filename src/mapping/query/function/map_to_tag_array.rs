@@ -0,0 +1,105 @@
+use super::prelude::*;
+
+/// Converts a map into an array of `"key:value"` strings, as expected by
+/// metrics sinks that take tags as a list, e.g. `{"env": "prod"}` becomes
+/// `["env:prod"]`.
+///
+/// Tags are ordered by key. Scalar values are written as strings and null
+/// values are written as a bare key, as for a tag without a value. Nested maps
+/// and arrays are rejected, as they have no meaningful representation as a
+/// tag.
+#[derive(Debug)]
+pub(in crate::mapping) struct MapToTagArrayFn {
+    value: Box<dyn Function>,
+}
+
+impl MapToTagArrayFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for MapToTagArrayFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let map = required_value!(ctx, self.value, Value::Map(v) => v);
+
+        map.into_iter()
+            .map(|(key, value)| match value {
+                Value::Null => Ok(Value::from(key)),
+                Value::Map(_) | Value::Array(_) => Err(format!(
+                    "unable to convert {} value of tag {} to a string",
+                    value.kind(),
+                    key
+                )),
+                value => Ok(Value::from(format!("{}:{}", key, value.to_string_lossy()))),
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(|array| Value::Array(array).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Map(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for MapToTagArrayFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{function::Argument, path::Path};
+    use serde_json::json;
+
+    #[test]
+    fn check_map_to_tag_array() {
+        let cases = vec![
+            (
+                json!({"service": "api", "env": "prod", "shard": 3, "canary": false}),
+                Ok(Value::from(json!([
+                    "canary:false",
+                    "env:prod",
+                    "service:api",
+                    "shard:3"
+                ]))),
+            ),
+            (
+                json!({"region": "eu", "debug": null}),
+                Ok(Value::from(json!(["debug", "region:eu"]))),
+            ),
+            (json!({}), Ok(Value::from(json!([])))),
+            (
+                json!({"env": "prod", "owners": ["a", "b"]}),
+                Err("unable to convert array value of tag owners to a string".to_string()),
+            ),
+            (
+                json!("env:prod"),
+                Err("invalid argument type 'string' for parameter 'value'".to_string()),
+            ),
+        ];
+
+        for (value, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(value));
+
+            let query = MapToTagArrayFn::new(Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                MapToTagArrayFn::parameters()[0].clone(),
+            )));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
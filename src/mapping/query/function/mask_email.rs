@@ -0,0 +1,125 @@
+use super::prelude::*;
+
+const MASK: &str = "***";
+
+#[derive(Debug)]
+pub(in crate::mapping) struct MaskEmailFn {
+    value: Box<dyn Function>,
+    mask_domain: Option<Box<dyn Function>>,
+}
+
+impl MaskEmailFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        mask_domain: Option<Box<dyn Function>>,
+    ) -> Self {
+        Self { value, mask_domain }
+    }
+}
+
+/// Keeps the first character of `part` and replaces the rest with a fixed
+/// mask, so that the length of the original isn't revealed.
+fn mask(part: &str) -> String {
+    let mut chars = part.chars();
+    chars.next().map(String::from).unwrap_or_default() + MASK
+}
+
+impl Function for MaskEmailFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let mask_domain =
+            optional_value!(ctx, self.mask_domain, Value::Boolean(v) => v).unwrap_or(false);
+
+        let email = String::from_utf8_lossy(&bytes);
+        let at = email
+            .rfind('@')
+            .filter(|&at| at > 0 && at < email.len() - 1)
+            .ok_or_else(|| format!("unable to mask invalid email address: {}", email))?;
+        let (local, domain) = (&email[..at], &email[at + 1..]);
+
+        // The top-level domain is kept when masking the domain, as it is
+        // rarely identifying on its own.
+        let domain = match (mask_domain, domain.rfind('.')) {
+            (false, _) => domain.to_owned(),
+            (true, Some(dot)) => mask(&domain[..dot]) + &domain[dot..],
+            (true, None) => mask(domain),
+        };
+
+        Ok(Value::from(format!("{}@{}", mask(local), domain)).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "mask_domain",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Boolean(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for MaskEmailFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let mask_domain = arguments.optional("mask_domain");
+
+        Ok(Self { value, mask_domain })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_mask_email() {
+        let cases = vec![
+            ("john.doe@example.com", None, Ok("j***@example.com")),
+            ("j@example.com", None, Ok("j***@example.com")),
+            ("john@mail.example.com", Some(true), Ok("j***@m***.com")),
+            ("john@localhost", Some(true), Ok("j***@l***")),
+            (
+                "john.example.com",
+                None,
+                Err("unable to mask invalid email address: john.example.com"),
+            ),
+            (
+                "@example.com",
+                None,
+                Err("unable to mask invalid email address: @example.com"),
+            ),
+            (
+                "john@",
+                None,
+                Err("unable to mask invalid email address: john@"),
+            ),
+        ];
+
+        for (email, mask_domain, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(email));
+
+            let query = MaskEmailFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                mask_domain
+                    .map(|v| Box::new(Literal::from(Value::Boolean(v))) as Box<dyn Function>),
+            );
+
+            assert_eq!(
+                query.execute(&event),
+                exp.map(|v| QueryValue::Value(Value::from(v)))
+                    .map_err(ToOwned::to_owned)
+            );
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use super::prelude::*;
+use crate::mapping::merge_maps;
+use std::collections::BTreeMap;
+
+/// Deep merges the maps of several fields into one, e.g.
+/// `merge_all([.defaults, .env, .overrides])`. Fields later in the list take
+/// precedence over earlier ones.
+#[derive(Debug)]
+pub(in crate::mapping) struct MergeAllFn {
+    maps: Vec<Box<dyn Function>>,
+}
+
+impl MergeAllFn {
+    pub(in crate::mapping) fn new(maps: Vec<Box<dyn Function>>) -> Self {
+        Self { maps }
+    }
+}
+
+impl Function for MergeAllFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let mut merged = BTreeMap::new();
+
+        for query in &self.maps {
+            match query.execute(ctx)? {
+                QueryValue::Value(Value::Map(map)) => merge_maps(&mut merged, &map, true, false)?,
+                v => {
+                    return Err(format!(
+                        "unable to merge {} value of {}, expected a map",
+                        v.kind(),
+                        query.describe()
+                    ))
+                }
+            }
+        }
+
+        Ok(Value::Map(merged).into())
+    }
+
+    fn describe(&self) -> String {
+        let maps = self
+            .maps
+            .iter()
+            .map(|query| query.describe())
+            .collect::<Vec<_>>();
+
+        format!("the deep merge of {}", maps.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_merge_all() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert(
+            "defaults",
+            Value::from(json!({"port": 80, "tls": {"enabled": false, "verify": true}})),
+        );
+        event.as_mut_log().insert(
+            "env",
+            Value::from(json!({"port": 8080, "tls": {"enabled": true}})),
+        );
+        event
+            .as_mut_log()
+            .insert("overrides", Value::from(json!({"port": 9090})));
+        event.as_mut_log().insert("name", Value::from("foo"));
+
+        let merge_all = |paths: &[&str]| {
+            MergeAllFn::new(
+                paths
+                    .iter()
+                    .map(|path| Box::new(Path::from(vec![vec![*path]])) as _)
+                    .collect(),
+            )
+            .execute(&event)
+        };
+
+        let cases = vec![
+            (
+                vec!["defaults", "env", "overrides"],
+                Ok(Value::from(
+                    json!({"port": 9090, "tls": {"enabled": true, "verify": true}}),
+                )),
+            ),
+            (
+                vec!["overrides", "env", "defaults"],
+                Ok(Value::from(
+                    json!({"port": 80, "tls": {"enabled": false, "verify": true}}),
+                )),
+            ),
+            (
+                vec!["defaults", "name"],
+                Err("unable to merge string value of .name, expected a map".to_string()),
+            ),
+        ];
+
+        for (paths, exp) in cases {
+            assert_eq!(merge_all(&paths), exp.map(QueryValue::Value));
+        }
+    }
+}
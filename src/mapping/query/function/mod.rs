@@ -1,8 +1,18 @@
 #![macro_use]
 
+mod del;
+mod first_matching;
+mod index_where;
+mod merge_all;
 mod not;
+mod reduce;
 
+pub(in crate::mapping) use del::DelFn;
+pub(in crate::mapping) use first_matching::FirstMatchingFn;
+pub(in crate::mapping) use index_where::IndexWhereFn;
+pub(in crate::mapping) use merge_all::MergeAllFn;
 pub(in crate::mapping) use not::NotFn;
+pub(in crate::mapping) use reduce::ReduceFn;
 
 use super::Function;
 use crate::mapping::{query::query_value::QueryValue, Result};
@@ -10,6 +20,7 @@ use crate::Event;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::str::FromStr;
+use std::sync::Arc;
 
 /// Commonly used types when building new functions.
 mod prelude {
@@ -73,15 +84,15 @@ macro_rules! optional_value {
 }
 
 macro_rules! build_signatures {
-    ($($name:ident => $func:ident),* $(,)?) => {
-        $(mod $name;)*
+    ($($(#[$attr:meta])* $name:ident => $func:ident),* $(,)?) => {
+        $($(#[$attr])* mod $name;)*
 
-        $(pub(in crate::mapping) use self::$name::$func;)*
+        $($(#[$attr])* pub(in crate::mapping) use self::$name::$func;)*
 
         #[derive(Debug, Copy, Clone, Eq, PartialEq)]
         #[allow(clippy::enum_variant_names)]
         pub(in crate::mapping) enum FunctionSignature {
-            $($func,)*
+            $($(#[$attr])* $func,)*
         }
 
         impl FromStr for FunctionSignature {
@@ -89,7 +100,7 @@ macro_rules! build_signatures {
 
             fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
                 let func = match s {
-                    $(stringify!($name) => Self::$func,)*
+                    $($(#[$attr])* stringify!($name) => Self::$func,)*
                     _ => return Err(format!("unknown function '{}'", s)),
                 };
 
@@ -100,21 +111,26 @@ macro_rules! build_signatures {
         impl FunctionSignature {
             pub fn as_str(&self) -> &str {
                 match self {
-                    $(Self::$func => stringify!($name),)*
+                    $($(#[$attr])* Self::$func => stringify!($name),)*
                 }
             }
 
             pub fn parameters(&self) -> &[Parameter] {
                 match self {
-                    $(Self::$func => $func::parameters(),)*
+                    $($(#[$attr])* Self::$func => $func::parameters(),)*
                 }
             }
 
             pub fn into_boxed_function(self, arguments: ArgumentList) -> Result<Box<dyn Function>> {
-                match self {
-                    $(Self::$func => $func::try_from(arguments)
+                let description = format!("{}({})", self.as_str(), arguments.describe());
+                let validated = arguments.arguments.clone();
+
+                let function = match self {
+                    $($(#[$attr])* Self::$func => $func::try_from(arguments)
                         .map(|func| Box::new(func) as Box<dyn Function>),)*
-                }
+                }?;
+
+                Ok(Box::new(Call::new(description, validated, function)))
             }
         }
     };
@@ -124,6 +140,84 @@ macro_rules! build_signatures {
 build_signatures! {
     split => SplitFn,
     flatten => FlattenFn,
+    normalize_newlines => NormalizeNewlinesFn,
+    first => FirstFn,
+    last => LastFn,
+    decode_flags => DecodeFlagsFn,
+    weekday_name => WeekdayNameFn,
+    month_name => MonthNameFn,
+    flatten_one => FlattenOneFn,
+    is_valid_utf8 => IsValidUtf8Fn,
+    force_utf8 => ForceUtf8Fn,
+    normalize => NormalizeFn,
+    chunk => ChunkFn,
+    extract_numbers => ExtractNumbersFn,
+    parse_semver => ParseSemverFn,
+    semver_gte => SemverGteFn,
+    start_of => StartOfFn,
+    end_of => EndOfFn,
+    json_escape => JsonEscapeFn,
+    intersect => IntersectFn,
+    difference => DifferenceFn,
+    normalize_numeric => NormalizeNumericFn,
+    normalize_boolean => NormalizeBooleanFn,
+    mask_email => MaskEmailFn,
+    parse_json_array => ParseJsonArrayFn,
+    auto_parse => AutoParseFn,
+    format_bytes => FormatBytesFn,
+    normalize_mac => NormalizeMacFn,
+    http_status_class => HttpStatusClassFn,
+    ensure_type => EnsureTypeFn,
+    strip_prefix => StripPrefixFn,
+    strip_suffix => StripSuffixFn,
+    luhn_valid => LuhnValidFn,
+    truncate_words => TruncateWordsFn,
+    quantize_timestamp => QuantizeTimestampFn,
+    coerce_array => CoerceArrayFn,
+    upcase => UpcaseFn,
+    downcase => DowncaseFn,
+    trim => TrimFn,
+    parse_cookies => ParseCookiesFn,
+    to_int => ToIntFn,
+    to_float => ToFloatFn,
+    to_bool => ToBoolFn,
+    contains => ContainsFn,
+    starts_with => StartsWithFn,
+    ends_with => EndsWithFn,
+    parse_user_agent => ParseUserAgentFn,
+    windows => WindowsFn,
+    replace => ReplaceFn,
+    infer_log_level => InferLogLevelFn,
+    now => NowFn,
+    random_int => RandomIntFn,
+    parse_timestamp => ParseTimestampFn,
+    format_timestamp => FormatTimestampFn,
+    add => AddFn,
+    parse_iso8601_duration => ParseIso8601DurationFn,
+    length => LengthFn,
+    sample => SampleFn,
+    encode_json => EncodeJsonFn,
+    parse_json => ParseJsonFn,
+    map_to_tag_array => MapToTagArrayFn,
+    abs => AbsFn,
+    round => RoundFn,
+    floor => FloorFn,
+    ceil => CeilFn,
+    parse_bytes_size => ParseBytesSizeFn,
+    exists => ExistsFn,
+    is_null => IsNullFn,
+    group_consecutive => GroupConsecutiveFn,
+    parse_ansi => ParseAnsiFn,
+    transliterate => TransliterateFn,
+    parse_regex => ParseRegexFn,
+    #[cfg(feature = "mapping-grok")]
+    parse_grok => ParseGrokFn,
+    parse_key_value => ParseKeyValueFn,
+    parse_csv => ParseCsvFn,
+    #[cfg(feature = "mapping-syslog")]
+    parse_syslog => ParseSyslogFn,
+    parse_url => ParseUrlFn,
+    parse_duration => ParseDurationFn,
 }
 
 /// A parameter definition accepted by a function.
@@ -196,6 +290,15 @@ impl ArgumentList {
         self.arguments.len()
     }
 
+    /// Describe the provided arguments, in the order they were given.
+    pub fn describe(&self) -> String {
+        self.arguments
+            .iter()
+            .map(Function::describe)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
     fn take(&mut self, keyword: &str) -> Option<Box<dyn Function>> {
         self.arguments
             .iter()
@@ -205,8 +308,9 @@ impl ArgumentList {
     }
 }
 
+#[derive(Clone)]
 pub(in crate::mapping) struct Argument {
-    resolver: Box<dyn Function>,
+    resolver: Arc<dyn Function>,
     parameter: Parameter,
 }
 
@@ -220,17 +324,15 @@ impl std::fmt::Debug for Argument {
 impl Argument {
     pub fn new(resolver: Box<dyn Function>, parameter: Parameter) -> Self {
         Self {
-            resolver,
+            resolver: resolver.into(),
             parameter,
         }
     }
 }
 
-impl Function for Argument {
-    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
-        let value = self.resolver.execute(ctx)?;
-
-        // Ask the parameter if it accepts the given value.
+impl Argument {
+    /// Asks the parameter if it accepts the given value.
+    fn check(&self, value: QueryValue) -> Result<QueryValue> {
         if !(self.parameter.accepts)(&value) {
             return Err(format!(
                 "invalid argument type '{}' for parameter '{}'",
@@ -242,3 +344,75 @@ impl Function for Argument {
         Ok(value)
     }
 }
+
+impl Function for Argument {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        self.check(self.resolver.execute(ctx)?)
+    }
+
+    fn resolve(&self, ctx: &Event) -> Result<Option<QueryValue>> {
+        self.resolver
+            .resolve(ctx)?
+            .map(|value| self.check(value))
+            .transpose()
+    }
+
+    fn is_constant(&self) -> bool {
+        self.resolver.is_constant()
+    }
+
+    fn validate(&self) -> Result<()> {
+        self.resolver.validate()
+    }
+
+    fn describe(&self) -> String {
+        self.resolver.describe()
+    }
+}
+
+/// A call to one of the built-in functions.
+///
+/// This wraps the function to keep track of how it was called, so that the
+/// call can be described, and its arguments validated, without each function
+/// having to do so itself. The arguments share their queries with the ones
+/// the function was built from.
+pub(in crate::mapping) struct Call {
+    description: String,
+    arguments: Vec<Argument>,
+    function: Box<dyn Function>,
+}
+
+// delegates to function to satisfy tests in `mapping::parser`.
+impl std::fmt::Debug for Call {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.function.fmt(f)
+    }
+}
+
+impl Call {
+    pub fn new(description: String, arguments: Vec<Argument>, function: Box<dyn Function>) -> Self {
+        Self {
+            description,
+            arguments,
+            function,
+        }
+    }
+}
+
+impl Function for Call {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        self.function.execute(ctx)
+    }
+
+    fn validate(&self) -> Result<()> {
+        for argument in &self.arguments {
+            argument.validate()?;
+        }
+
+        self.function.validate()
+    }
+
+    fn describe(&self) -> String {
+        self.description.clone()
+    }
+}
@@ -0,0 +1,159 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct NormalizeFn {
+    value: Box<dyn Function>,
+    sort_arrays: Option<Box<dyn Function>>,
+}
+
+impl NormalizeFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        sort_arrays: Option<Box<dyn Function>>,
+    ) -> Self {
+        Self { value, sort_arrays }
+    }
+}
+
+/// Recursively normalizes a value.
+///
+/// Map keys are always sorted, as maps are stored as a `BTreeMap`. If
+/// `sort_arrays` is true, array elements are (after being normalized
+/// themselves) sorted by their JSON encoding, which gives a stable order
+/// across all value types.
+fn normalize(value: Value, sort_arrays: bool) -> Value {
+    match value {
+        Value::Map(map) => Value::Map(
+            map.into_iter()
+                .map(|(k, v)| (k, normalize(v, sort_arrays)))
+                .collect(),
+        ),
+        Value::Array(array) => {
+            let array = array.into_iter().map(|v| normalize(v, sort_arrays));
+
+            if sort_arrays {
+                let mut keyed = array
+                    .map(|v| (serde_json::to_string(&v).unwrap_or_default(), v))
+                    .collect::<Vec<_>>();
+                keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+                Value::Array(keyed.into_iter().map(|(_, v)| v).collect())
+            } else {
+                Value::Array(array.collect())
+            }
+        }
+        value => value,
+    }
+}
+
+impl Function for NormalizeFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let value = required!(ctx, self.value, QueryValue::Value(v) => v);
+        let sort_arrays =
+            optional_value!(ctx, self.sort_arrays, Value::Boolean(v) => v).unwrap_or(false);
+
+        Ok(normalize(value, sort_arrays).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "sort_arrays",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Boolean(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for NormalizeFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let sort_arrays = arguments.optional("sort_arrays");
+
+        Ok(Self { value, sort_arrays })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_normalize() {
+        let cases = vec![
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert(
+                        "foo",
+                        Value::from(json!({"b": [{"y": 1, "x": [2, 1]}, "a"], "a": 1})),
+                    );
+                    event
+                },
+                Ok(Value::from(
+                    json!({"a": 1, "b": [{"x": [2, 1], "y": 1}, "a"]}),
+                )),
+                NormalizeFn::new(Box::new(Path::from(vec![vec!["foo"]])), None),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert(
+                        "foo",
+                        Value::from(json!({"b": [{"y": 1, "x": [2, 1]}, "a"], "a": 1})),
+                    );
+                    event
+                },
+                Ok(Value::from(
+                    json!({"a": 1, "b": ["a", {"x": [1, 2], "y": 1}]}),
+                )),
+                NormalizeFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Some(Box::new(Literal::from(Value::Boolean(true)))),
+                ),
+            ),
+            (
+                Event::from(""),
+                Ok(Value::from("foo")),
+                NormalizeFn::new(Box::new(Literal::from(Value::from("foo"))), None),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+
+    #[test]
+    fn normalize_equivalent_values() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert(
+            "foo",
+            Value::from(json!({"a": [3, {"c": true, "b": null}], "d": "e"})),
+        );
+        event.as_mut_log().insert(
+            "bar",
+            Value::from(json!({"d": "e", "a": [{"b": null, "c": true}, 3]})),
+        );
+
+        let normalize = |path| {
+            NormalizeFn::new(
+                Box::new(Path::from(vec![vec![path]])),
+                Some(Box::new(Literal::from(Value::Boolean(true)))),
+            )
+            .execute(&event)
+        };
+
+        assert_eq!(normalize("foo"), normalize("bar"));
+    }
+}
@@ -0,0 +1,71 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct NormalizeBooleanFn {
+    value: Box<dyn Function>,
+}
+
+impl NormalizeBooleanFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for NormalizeBooleanFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let value = required!(ctx, self.value, QueryValue::Value(v) => v);
+
+        let value = match value {
+            Value::Bytes(bytes) if bytes.eq_ignore_ascii_case(b"true") => Value::Boolean(true),
+            Value::Bytes(bytes) if bytes.eq_ignore_ascii_case(b"false") => Value::Boolean(false),
+            value => value,
+        };
+
+        Ok(value.into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(_)),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for NormalizeBooleanFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_normalize_boolean() {
+        let cases = vec![
+            (Value::from("true"), Value::from(true)),
+            (Value::from("FALSE"), Value::from(false)),
+            (Value::from(true), Value::from(true)),
+            (Value::from("yes"), Value::from("yes")),
+            (Value::from(1), Value::from(1)),
+        ];
+
+        for (value, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", value);
+
+            let query = NormalizeBooleanFn::new(Box::new(Path::from(vec![vec!["foo"]])));
+
+            assert_eq!(query.execute(&event), Ok(QueryValue::Value(exp)));
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct NormalizeMacFn {
+    value: Box<dyn Function>,
+}
+
+impl NormalizeMacFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+/// Returns the twelve hex digits of a MAC address written as six colon or
+/// hyphen separated octets, three dot separated groups of four digits, or
+/// twelve digits without any separator.
+fn mac_digits(mac: &str) -> Option<String> {
+    let (groups, size) = if mac.contains(':') {
+        (mac.split(':').collect::<Vec<_>>(), 2)
+    } else if mac.contains('-') {
+        (mac.split('-').collect(), 2)
+    } else if mac.contains('.') {
+        (mac.split('.').collect(), 4)
+    } else {
+        (vec![mac], 12)
+    };
+
+    if groups.len() * size != 12
+        || groups
+            .iter()
+            .any(|group| group.len() != size || !group.chars().all(|c| c.is_ascii_hexdigit()))
+    {
+        return None;
+    }
+
+    Some(groups.concat().to_ascii_lowercase())
+}
+
+impl Function for NormalizeMacFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let mac = String::from_utf8_lossy(&bytes);
+
+        let digits = mac_digits(mac.trim()).ok_or(format!("invalid MAC address: {}", mac))?;
+        let octets = (0..12)
+            .step_by(2)
+            .map(|i| &digits[i..i + 2])
+            .collect::<Vec<_>>();
+
+        Ok(Value::from(octets.join(":")).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for NormalizeMacFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_normalize_mac() {
+        let cases = vec![
+            ("aa:bb:cc:dd:ee:ff", Ok("aa:bb:cc:dd:ee:ff")),
+            ("AA-BB-CC-DD-EE-FF", Ok("aa:bb:cc:dd:ee:ff")),
+            ("aabb.ccdd.eeff", Ok("aa:bb:cc:dd:ee:ff")),
+            ("AABBCCDDEEFF", Ok("aa:bb:cc:dd:ee:ff")),
+            (" 00:1A:2b:3C:4d:5E ", Ok("00:1a:2b:3c:4d:5e")),
+            ("aa:bb:cc:dd:ee", Err("invalid MAC address: aa:bb:cc:dd:ee")),
+            (
+                "aa:bb:cc:dd:ee:fg",
+                Err("invalid MAC address: aa:bb:cc:dd:ee:fg"),
+            ),
+            (
+                "aa:bb-cc:dd:ee:ff",
+                Err("invalid MAC address: aa:bb-cc:dd:ee:ff"),
+            ),
+            ("aabb.ccdd", Err("invalid MAC address: aabb.ccdd")),
+        ];
+
+        for (mac, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(mac));
+
+            let query = NormalizeMacFn::new(Box::new(Path::from(vec![vec!["foo"]])));
+
+            assert_eq!(
+                query.execute(&event),
+                exp.map(|v| QueryValue::Value(Value::from(v)))
+                    .map_err(ToOwned::to_owned)
+            );
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct NormalizeNewlinesFn {
+    value: Box<dyn Function>,
+    ending: Option<Box<dyn Function>>,
+}
+
+impl NormalizeNewlinesFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        ending: Option<Box<dyn Function>>,
+    ) -> Self {
+        Self { value, ending }
+    }
+}
+
+impl Function for NormalizeNewlinesFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let ending = optional_value!(ctx, self.ending, Value::Bytes(v) => v);
+        let ending = ending.as_ref().map(|v| v.as_ref()).unwrap_or(b"\n");
+
+        let mut normalized = Vec::with_capacity(bytes.len());
+        let mut iter = bytes.iter().peekable();
+
+        // `\r\n`, a lone `\r` and a lone `\n` are all treated as a single
+        // line ending.
+        while let Some(&byte) = iter.next() {
+            match byte {
+                b'\r' => {
+                    if iter.peek() == Some(&&b'\n') {
+                        iter.next();
+                    }
+                    normalized.extend_from_slice(ending);
+                }
+                b'\n' => normalized.extend_from_slice(ending),
+                byte => normalized.push(byte),
+            }
+        }
+
+        Ok(Value::Bytes(normalized.into()).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "ending",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for NormalizeNewlinesFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let ending = arguments.optional("ending");
+
+        Ok(Self { value, ending })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_normalize_newlines() {
+        let cases = vec![
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from("foo\nbar\n"));
+                    event
+                },
+                Ok(Value::from("foo\nbar\n")),
+                NormalizeNewlinesFn::new(Box::new(Path::from(vec![vec!["foo"]])), None),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from("foo\r\nbar\r\n"));
+                    event
+                },
+                Ok(Value::from("foo\nbar\n")),
+                NormalizeNewlinesFn::new(Box::new(Path::from(vec![vec!["foo"]])), None),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from("foo\rbar\r"));
+                    event
+                },
+                Ok(Value::from("foo\nbar\n")),
+                NormalizeNewlinesFn::new(Box::new(Path::from(vec![vec!["foo"]])), None),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from("foo\r\nbar\rbaz\n\r\rqux"));
+                    event
+                },
+                Ok(Value::from("foo\nbar\nbaz\n\n\nqux")),
+                NormalizeNewlinesFn::new(Box::new(Path::from(vec![vec!["foo"]])), None),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from("foo\nbar\rbaz\r\n"));
+                    event
+                },
+                Ok(Value::from("foo\r\nbar\r\nbaz\r\n")),
+                NormalizeNewlinesFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Some(Box::new(Literal::from(Value::from("\r\n")))),
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct NormalizeNumericFn {
+    value: Box<dyn Function>,
+}
+
+impl NormalizeNumericFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+/// Parses a string that looks like a number. Strings such as `inf` or `NaN`
+/// are deliberately not treated as numbers.
+fn parse_numeric(string: &str) -> Option<Value> {
+    let string = string.trim();
+
+    if string.is_empty()
+        || !string
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '+' | '-' | '.' | 'e' | 'E'))
+    {
+        return None;
+    }
+
+    string
+        .parse::<i64>()
+        .map(Value::Integer)
+        .or_else(|_| string.parse::<f64>().map(Value::Float))
+        .ok()
+}
+
+impl Function for NormalizeNumericFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let value = required!(ctx, self.value, QueryValue::Value(v) => v);
+
+        let value = match value {
+            Value::Bytes(bytes) => std::str::from_utf8(&bytes)
+                .ok()
+                .and_then(parse_numeric)
+                .unwrap_or(Value::Bytes(bytes)),
+            value => value,
+        };
+
+        Ok(value.into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(_)),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for NormalizeNumericFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_normalize_numeric() {
+        let cases = vec![
+            (Value::from("123"), Value::from(123)),
+            (Value::from(" -42 "), Value::from(-42)),
+            (Value::from("1.5"), Value::from(1.5)),
+            (Value::from("2e3"), Value::from(2000.0)),
+            (Value::from(123), Value::from(123)),
+            (Value::from(1.5), Value::from(1.5)),
+            (Value::from("12abc"), Value::from("12abc")),
+            (Value::from("inf"), Value::from("inf")),
+            (Value::from("1.2.3"), Value::from("1.2.3")),
+            (Value::from(""), Value::from("")),
+            (Value::from(true), Value::from(true)),
+        ];
+
+        for (value, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", value);
+
+            let query = NormalizeNumericFn::new(Box::new(Path::from(vec![vec!["foo"]])));
+
+            assert_eq!(query.execute(&event), Ok(QueryValue::Value(exp)));
+        }
+    }
+}
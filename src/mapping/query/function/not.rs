@@ -19,6 +19,10 @@ impl Function for NotFn {
             v => Err(format!("unable to perform NOT on {:?} value", v)),
         })
     }
+
+    fn describe(&self) -> String {
+        format!("!{}", self.query.describe())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,57 @@
+use super::prelude::*;
+use crate::mapping::context;
+
+#[derive(Debug, Default)]
+pub(in crate::mapping) struct NowFn;
+
+impl NowFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new() -> Self {
+        Self
+    }
+}
+
+impl Function for NowFn {
+    fn execute(&self, _: &Event) -> Result<QueryValue> {
+        Ok(Value::Timestamp(context::now()).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[]
+    }
+}
+
+impl TryFrom<ArgumentList> for NowFn {
+    type Error = String;
+
+    fn try_from(_: ArgumentList) -> Result<Self> {
+        Ok(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::ExecutionContext;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn check_now() {
+        let now = Utc.ymd(2021, 1, 4).and_hms_milli(12, 30, 15, 250);
+        let context = ExecutionContext::new().with_now(now);
+
+        assert_eq!(
+            context::scoped_new(&context, || NowFn::new().execute(&Event::from(""))),
+            Ok(Value::Timestamp(now).into())
+        );
+
+        let before = Utc::now();
+        let result = NowFn::new().execute(&Event::from(""));
+        let after = Utc::now();
+
+        match result {
+            Ok(QueryValue::Value(Value::Timestamp(ts))) => assert!(before <= ts && ts <= after),
+            v => panic!("unexpected result: {:?}", v),
+        }
+    }
+}
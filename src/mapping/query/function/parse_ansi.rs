@@ -0,0 +1,214 @@
+use super::prelude::*;
+use std::collections::BTreeMap;
+
+/// Splits a string with ANSI escape sequences into its plain `text`, as
+/// stripped by `strip_ansi_escapes`, and the `styles` set by its SGR (Select
+/// Graphic Rendition) sequences, in order of appearance.
+///
+/// Styles are named, e.g. `bold` or `red`. Extended colors are named by their
+/// palette index or hex value, e.g. `fg(208)` or `bg(#ff8700)`, and any other
+/// codes are kept as their number.
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseAnsiFn {
+    value: Box<dyn Function>,
+}
+
+impl ParseAnsiFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+const COLORS: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+fn style_name(code: u32) -> String {
+    let name = match code {
+        0 => "reset",
+        1 => "bold",
+        2 => "dim",
+        3 => "italic",
+        4 => "underline",
+        5 => "blink",
+        7 => "reverse",
+        8 => "hidden",
+        9 => "strikethrough",
+        22 => "normal_intensity",
+        23 => "no_italic",
+        24 => "no_underline",
+        25 => "no_blink",
+        27 => "no_reverse",
+        28 => "no_hidden",
+        29 => "no_strikethrough",
+        30..=37 => COLORS[(code - 30) as usize],
+        39 => "default",
+        40..=47 => return format!("bg_{}", COLORS[(code - 40) as usize]),
+        49 => "bg_default",
+        90..=97 => return format!("bright_{}", COLORS[(code - 90) as usize]),
+        100..=107 => return format!("bg_bright_{}", COLORS[(code - 100) as usize]),
+        _ => return code.to_string(),
+    };
+
+    name.to_owned()
+}
+
+/// Returns the styles set by the parameters of a single SGR sequence.
+fn sgr_styles(params: &str) -> Vec<String> {
+    // An empty parameter, as in `ESC[m`, is a reset.
+    let mut codes = params
+        .split(';')
+        .map(|param| param.parse::<u32>().unwrap_or(0));
+    let mut styles = Vec::new();
+
+    while let Some(code) = codes.next() {
+        let style = match code {
+            38 | 48 => {
+                let target = if code == 38 { "fg" } else { "bg" };
+                match codes.next() {
+                    Some(5) => format!("{}({})", target, codes.next().unwrap_or(0)),
+                    Some(2) => {
+                        let mut channel = || codes.next().unwrap_or(0).min(255);
+                        let (r, g, b) = (channel(), channel(), channel());
+                        format!("{}(#{:02x}{:02x}{:02x})", target, r, g, b)
+                    }
+                    _ => code.to_string(),
+                }
+            }
+            code => style_name(code),
+        };
+        styles.push(style);
+    }
+
+    styles
+}
+
+/// Scans the string for CSI sequences, `ESC [ params final`, and returns the
+/// styles of those that are SGR sequences, which end in `m`.
+fn scan_styles(bytes: &[u8]) -> Vec<String> {
+    let mut styles = Vec::new();
+    let mut i = 0;
+
+    while i + 1 < bytes.len() {
+        if bytes[i] != 0x1b || bytes[i + 1] != b'[' {
+            i += 1;
+            continue;
+        }
+
+        let start = i + 2;
+        match bytes[start..]
+            .iter()
+            .position(|b| (0x40..=0x7e).contains(b))
+        {
+            Some(len) => {
+                if bytes[start + len] == b'm' {
+                    let params = String::from_utf8_lossy(&bytes[start..start + len]);
+                    styles.extend(sgr_styles(&params));
+                }
+                i = start + len + 1;
+            }
+            None => break,
+        }
+    }
+
+    styles
+}
+
+impl Function for ParseAnsiFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+
+        let text = strip_ansi_escapes::strip(&bytes).map_err(|err| err.to_string())?;
+        let styles = scan_styles(&bytes).into_iter().map(Value::from).collect();
+
+        let mut map = BTreeMap::new();
+        map.insert("text".to_owned(), Value::from(bytes::Bytes::from(text)));
+        map.insert("styles".to_owned(), Value::Array(styles));
+
+        Ok(Value::Map(map).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseAnsiFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{function::Argument, path::Path};
+    use serde_json::json;
+
+    #[test]
+    fn check_parse_ansi() {
+        let cases = vec![
+            (
+                Value::from("\x1b[1;31mERROR\x1b[0m disk \x1b[4mfull\x1b[m"),
+                Ok(Value::from(json!({
+                    "text": "ERROR disk full",
+                    "styles": ["bold", "red", "reset", "underline", "reset"],
+                }))),
+            ),
+            (
+                Value::from("\x1b[38;5;208mwarn\x1b[48;2;0;128;255m!\x1b[92;100m ok\x1b[39;49m"),
+                Ok(Value::from(json!({
+                    "text": "warn! ok",
+                    "styles": [
+                        "fg(208)",
+                        "bg(#0080ff)",
+                        "bright_green",
+                        "bg_bright_black",
+                        "default",
+                        "bg_default",
+                    ],
+                }))),
+            ),
+            (
+                // Cursor movement is stripped, but isn't a style.
+                Value::from("\x1b[2Kplain \x1b[53mtext"),
+                Ok(Value::from(json!({
+                    "text": "plain text",
+                    "styles": ["53"],
+                }))),
+            ),
+            (
+                Value::from("no escapes"),
+                Ok(Value::from(json!({
+                    "text": "no escapes",
+                    "styles": [],
+                }))),
+            ),
+            (
+                Value::from(31),
+                Err("invalid argument type 'integer' for parameter 'value'".to_string()),
+            ),
+        ];
+
+        for (value, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", value);
+
+            let query = ParseAnsiFn::new(Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                ParseAnsiFn::parameters()[0].clone(),
+            )));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
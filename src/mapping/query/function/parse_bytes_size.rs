@@ -0,0 +1,132 @@
+use super::format_bytes::{BINARY_UNITS, SI_UNITS};
+use super::prelude::*;
+
+/// Parses a size with a unit, such as `1.5 KiB` or `10MB`, into a number of
+/// bytes. This is the inverse of `format_bytes`.
+///
+/// Both binary units, in multiples of 1024, and SI units, in multiples of
+/// 1000, are recognized regardless of case. A size without a unit is taken to
+/// be in bytes, and fractional byte counts are rounded to the nearest byte.
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseBytesSizeFn {
+    value: Box<dyn Function>,
+}
+
+impl ParseBytesSizeFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+/// Returns the number of bytes in the given unit.
+fn unit_size(unit: &str) -> Option<f64> {
+    let find = |units: &[&str]| units.iter().position(|u| u.eq_ignore_ascii_case(unit));
+
+    match unit {
+        "" => Some(1.0),
+        _ => find(BINARY_UNITS)
+            .map(|i| 1024f64.powi(i as i32))
+            .or_else(|| find(SI_UNITS).map(|i| 1000f64.powi(i as i32))),
+    }
+}
+
+fn parse_size(input: &str) -> Result<i64> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+
+    let number = number
+        .parse::<f64>()
+        .map_err(|_| format!("unable to parse size {:?}: invalid number", input))?;
+    let unit = unit.trim_start();
+    let unit_size = unit_size(unit)
+        .ok_or_else(|| format!("unable to parse size {:?}: unknown unit {:?}", input, unit))?;
+
+    let bytes = (number * unit_size).round();
+    if bytes > i64::MAX as f64 {
+        return Err(format!("unable to parse size {:?}: too large", input));
+    }
+
+    Ok(bytes as i64)
+}
+
+impl Function for ParseBytesSizeFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+
+        parse_size(&String::from_utf8_lossy(&bytes)).map(|size| Value::Integer(size).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseBytesSizeFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{function::Argument, path::Path};
+
+    #[test]
+    fn check_parse_bytes_size() {
+        let cases = vec![
+            (Value::from("1.5 KiB"), Ok(Value::from(1536))),
+            (Value::from("10MB"), Ok(Value::from(10_000_000))),
+            (Value::from("10 mb"), Ok(Value::from(10_000_000))),
+            (Value::from("2 GiB"), Ok(Value::from(2_147_483_648i64))),
+            (Value::from("1kB"), Ok(Value::from(1000))),
+            (Value::from("512 B"), Ok(Value::from(512))),
+            (Value::from("512"), Ok(Value::from(512))),
+            (Value::from("0.3 KB"), Ok(Value::from(300))),
+            (
+                Value::from("10 XB"),
+                Err(r#"unable to parse size "10 XB": unknown unit "XB""#.to_string()),
+            ),
+            (
+                Value::from("KiB"),
+                Err(r#"unable to parse size "KiB": invalid number"#.to_string()),
+            ),
+            (
+                Value::from("-1 KiB"),
+                Err(r#"unable to parse size "-1 KiB": invalid number"#.to_string()),
+            ),
+            (
+                Value::from("99999999 EiB"),
+                Err(r#"unable to parse size "99999999 EiB": too large"#.to_string()),
+            ),
+            (
+                Value::from(1024),
+                Err("invalid argument type 'integer' for parameter 'value'".to_string()),
+            ),
+        ];
+
+        for (value, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", value);
+
+            let query = ParseBytesSizeFn::new(Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                ParseBytesSizeFn::parameters()[0].clone(),
+            )));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use super::prelude::*;
+use percent_encoding::percent_decode_str;
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseCookiesFn {
+    value: Box<dyn Function>,
+}
+
+impl ParseCookiesFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+/// Parses the value of a `Cookie` header, e.g. `a=1; b=2`, into a map of
+/// cookie names to their URL-decoded values.
+///
+/// Segments without a `=` or without a name are malformed, and are skipped.
+/// If a cookie appears more than once, the last value wins.
+fn parse_cookies(header: &str) -> BTreeMap<String, Value> {
+    header
+        .split(';')
+        .filter_map(|segment| {
+            let segment = segment.trim();
+            let separator = segment.find('=')?;
+            let name = segment[..separator].trim();
+            if name.is_empty() {
+                return None;
+            }
+
+            let value = segment[separator + 1..].trim();
+            let value = percent_decode_str(value).decode_utf8_lossy().into_owned();
+
+            Some((name.to_owned(), Value::from(value)))
+        })
+        .collect()
+}
+
+impl Function for ParseCookiesFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+
+        Ok(Value::Map(parse_cookies(&String::from_utf8_lossy(&bytes))).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseCookiesFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_parse_cookies() {
+        let cases = vec![
+            (
+                "session=abc123; theme=dark;lang=en-US",
+                json!({"session": "abc123", "theme": "dark", "lang": "en-US"}),
+            ),
+            (
+                "token=a=b==; next=%2Fhome%3Fq%3D1",
+                json!({"token": "a=b==", "next": "/home?q=1"}),
+            ),
+            (
+                "valid=1; malformed; =no_name; ; empty=",
+                json!({"valid": "1", "empty": ""}),
+            ),
+            ("a=1; a=2", json!({"a": "2"})),
+            ("", json!({})),
+        ];
+
+        for (input, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(input));
+            let query = ParseCookiesFn::new(Box::new(Path::from(vec![vec!["foo"]])));
+
+            assert_eq!(
+                query.execute(&event),
+                Ok(QueryValue::Value(Value::from(exp)))
+            );
+        }
+    }
+}
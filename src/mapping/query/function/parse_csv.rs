@@ -0,0 +1,257 @@
+use super::prelude::*;
+
+/// Parses a single delimited line, quoted as per RFC 4180, into an array of
+/// strings. When field names are given the fields are returned as a map
+/// instead, in which case there must be exactly one field per name.
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseCsvFn {
+    value: Box<dyn Function>,
+    names: Option<Box<dyn Function>>,
+    delimiter: Option<Box<dyn Function>>,
+}
+
+impl ParseCsvFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        names: Option<Box<dyn Function>>,
+        delimiter: Option<Box<dyn Function>>,
+    ) -> Self {
+        Self {
+            value,
+            names,
+            delimiter,
+        }
+    }
+}
+
+fn parse(input: &str, delimiter: char) -> Result<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = input.char_indices().peekable();
+
+    loop {
+        match chars.peek() {
+            // A quoted field runs until a quote that isn't doubled, and must
+            // be followed by a delimiter or the end of the line.
+            Some(&(start, '"')) => {
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) if matches!(chars.peek(), Some((_, '"'))) => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some((_, '"')) => break,
+                        Some((_, c)) => field.push(c),
+                        None => return Err(format!("unterminated quoted field at byte {}", start)),
+                    }
+                }
+
+                match chars.next() {
+                    Some((_, c)) if c == delimiter => {}
+                    Some((i, _)) => return Err(format!("expected delimiter at byte {}", i)),
+                    None => {
+                        fields.push(field);
+                        return Ok(fields);
+                    }
+                }
+            }
+            _ => loop {
+                match chars.next() {
+                    Some((_, c)) if c == delimiter => break,
+                    Some((i, '"')) => return Err(format!("unexpected quote at byte {}", i)),
+                    Some((_, c)) => field.push(c),
+                    None => {
+                        fields.push(field);
+                        return Ok(fields);
+                    }
+                }
+            },
+        }
+
+        fields.push(std::mem::take(&mut field));
+    }
+}
+
+impl Function for ParseCsvFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let names = optional_value!(ctx, self.names, Value::Array(v) => v);
+        let delimiter = match optional_value!(ctx, self.delimiter, Value::Bytes(v) => v) {
+            Some(bytes) => {
+                let delimiter = String::from_utf8_lossy(&bytes);
+                let mut chars = delimiter.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if c != '"' => c,
+                    _ => {
+                        return Err(format!(
+                            "delimiter must be a single character other than a quote, got {:?}",
+                            delimiter
+                        ))
+                    }
+                }
+            }
+            None => ',',
+        };
+
+        let input = String::from_utf8_lossy(&bytes);
+        let input = input.trim_end_matches(|c| c == '\r' || c == '\n');
+        let fields =
+            parse(input, delimiter).map_err(|err| format!("unable to parse CSV: {}", err))?;
+
+        let names = match names {
+            Some(names) => names,
+            None => {
+                return Ok(
+                    Value::from(fields.into_iter().map(Value::from).collect::<Vec<_>>()).into(),
+                )
+            }
+        };
+
+        if names.len() != fields.len() {
+            return Err(format!(
+                "expected {} fields, got {}",
+                names.len(),
+                fields.len()
+            ));
+        }
+
+        names
+            .into_iter()
+            .zip(fields)
+            .map(|(name, field)| match name {
+                Value::Bytes(name) => Ok((
+                    String::from_utf8_lossy(&name).into_owned(),
+                    Value::from(field),
+                )),
+                v => Err(format!("field names must be strings, got {}", v.kind())),
+            })
+            .collect::<Result<_>>()
+            .map(|map| Value::Map(map).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "names",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Array(_))),
+                required: false,
+            },
+            Parameter {
+                keyword: "delimiter",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseCsvFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let names = arguments.optional("names");
+        let delimiter = arguments.optional("delimiter");
+
+        Ok(Self {
+            value,
+            names,
+            delimiter,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_parse_csv() {
+        let cases = vec![
+            (
+                "2021-01-04,web-1,200",
+                None,
+                None,
+                Ok(json!(["2021-01-04", "web-1", "200"])),
+            ),
+            (
+                "2021-01-04,web-1,200\r\n",
+                Some(json!(["ts", "host", "status"])),
+                None,
+                Ok(json!({"ts": "2021-01-04", "host": "web-1", "status": "200"})),
+            ),
+            (
+                r##""a, b","say ""hi""",,"line
+break","""##,
+                None,
+                None,
+                Ok(json!(["a, b", r#"say "hi""#, "", "line\nbreak", ""])),
+            ),
+            (" a |b| ", None, Some("|"), Ok(json!([" a ", "b", " "]))),
+            ("", None, None, Ok(json!([""]))),
+            (
+                "a,b",
+                Some(json!(["ts", "host", "status"])),
+                None,
+                Err("expected 3 fields, got 2"),
+            ),
+            (
+                "a,b",
+                Some(json!(["ts", 1])),
+                None,
+                Err("field names must be strings, got integer"),
+            ),
+            (
+                r#"a,"b"c"#,
+                None,
+                None,
+                Err("unable to parse CSV: expected delimiter at byte 5"),
+            ),
+            (
+                r#"a,b"c"#,
+                None,
+                None,
+                Err("unable to parse CSV: unexpected quote at byte 3"),
+            ),
+            (
+                r#"a,"b"#,
+                None,
+                None,
+                Err("unable to parse CSV: unterminated quoted field at byte 2"),
+            ),
+            (
+                "a,b",
+                None,
+                Some("||"),
+                Err(r#"delimiter must be a single character other than a quote, got "||""#),
+            ),
+        ];
+
+        for (input, names, delimiter, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(input));
+            let query = ParseCsvFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                names.map(|v| Box::new(Literal::from(Value::from(v))) as _),
+                delimiter.map(|v| Box::new(Literal::from(Value::from(v))) as _),
+            );
+
+            assert_eq!(
+                query.execute(&event),
+                exp.map(|v| Value::from(v).into())
+                    .map_err(ToOwned::to_owned),
+                "{}",
+                input
+            );
+        }
+    }
+}
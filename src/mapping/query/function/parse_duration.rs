@@ -0,0 +1,199 @@
+use super::prelude::*;
+
+/// Parses a human readable duration, such as `5m30s`, `250ms` or `1.5h`, into
+/// a number in the given unit, e.g. `parse_duration(.elapsed, "seconds")`.
+///
+/// A duration is a sequence of numbers, each followed by one of the units
+/// `ns`, `us` (or `µs`), `ms`, `s`, `m`, `h` and `d`. The output unit can be
+/// given either as one of those, or spelled out, such as `milliseconds`.
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseDurationFn {
+    value: Box<dyn Function>,
+    unit: Box<dyn Function>,
+}
+
+impl ParseDurationFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>, unit: Box<dyn Function>) -> Self {
+        Self { value, unit }
+    }
+}
+
+/// Returns the number of nanoseconds in the given unit of a duration.
+fn unit_nanos(unit: &str) -> Option<f64> {
+    match unit {
+        "ns" => Some(1.0),
+        "us" | "µs" => Some(1e3),
+        "ms" => Some(1e6),
+        "s" => Some(1e9),
+        "m" => Some(60e9),
+        "h" => Some(3600e9),
+        "d" => Some(86400e9),
+        _ => None,
+    }
+}
+
+/// Returns the number of nanoseconds in the given output unit, which can also
+/// be spelled out in full.
+fn output_unit_nanos(unit: &str) -> Option<f64> {
+    let unit = match unit {
+        "nanoseconds" => "ns",
+        "microseconds" => "us",
+        "milliseconds" => "ms",
+        "seconds" => "s",
+        "minutes" => "m",
+        "hours" => "h",
+        "days" => "d",
+        unit => unit,
+    };
+
+    unit_nanos(unit)
+}
+
+fn parse_duration(input: &str) -> Result<f64> {
+    let err = |reason: String| format!("unable to parse duration {:?}: {}", input, reason);
+
+    let mut rest = input.trim();
+    if rest.is_empty() {
+        return Err(err("empty duration".to_owned()));
+    }
+
+    let mut nanos = 0.0;
+    while !rest.is_empty() {
+        let split = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let (number, tail) = rest.split_at(split);
+        let number = number
+            .parse::<f64>()
+            .map_err(|_| err("invalid number".to_owned()))?;
+
+        let split = tail
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(split);
+        let unit_nanos = match unit {
+            "" => return Err(err("missing unit".to_owned())),
+            unit => unit_nanos(unit).ok_or_else(|| err(format!("unknown unit {:?}", unit)))?,
+        };
+
+        nanos += number * unit_nanos;
+        rest = tail;
+    }
+
+    Ok(nanos)
+}
+
+impl Function for ParseDurationFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let unit = required_value!(ctx, self.unit, Value::Bytes(v) => v);
+        let unit = String::from_utf8_lossy(&unit);
+
+        let output_nanos =
+            output_unit_nanos(&unit).ok_or_else(|| format!("unknown duration unit {:?}", unit))?;
+
+        parse_duration(&String::from_utf8_lossy(&bytes))
+            .map(|nanos| Value::Float(nanos / output_nanos).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "unit",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseDurationFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let unit = arguments.required("unit")?;
+
+        Ok(Self { value, unit })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{function::Argument, path::Path};
+
+    #[test]
+    fn check_parse_duration() {
+        let cases = vec![
+            (Value::from("5m30s"), "seconds", Ok(Value::from(330.0))),
+            (Value::from("250ms"), "seconds", Ok(Value::from(0.25))),
+            (Value::from("1.5h"), "m", Ok(Value::from(90.0))),
+            (Value::from("1d12h"), "hours", Ok(Value::from(36.0))),
+            (Value::from("2s500ms"), "ms", Ok(Value::from(2500.0))),
+            (Value::from("15µs"), "ns", Ok(Value::from(15000.0))),
+            (Value::from(" 45s "), "minutes", Ok(Value::from(0.75))),
+            (
+                Value::from("10"),
+                "s",
+                Err(r#"unable to parse duration "10": missing unit"#.to_string()),
+            ),
+            (
+                Value::from("10 s"),
+                "s",
+                Err(r#"unable to parse duration "10 s": unknown unit " s""#.to_string()),
+            ),
+            (
+                Value::from("3w"),
+                "s",
+                Err(r#"unable to parse duration "3w": unknown unit "w""#.to_string()),
+            ),
+            (
+                Value::from("ms"),
+                "s",
+                Err(r#"unable to parse duration "ms": invalid number"#.to_string()),
+            ),
+            (
+                Value::from("1..5s"),
+                "s",
+                Err(r#"unable to parse duration "1..5s": invalid number"#.to_string()),
+            ),
+            (
+                Value::from(""),
+                "s",
+                Err(r#"unable to parse duration "": empty duration"#.to_string()),
+            ),
+            (
+                Value::from("1s"),
+                "fortnights",
+                Err(r#"unknown duration unit "fortnights""#.to_string()),
+            ),
+            (
+                Value::from(10),
+                "s",
+                Err("invalid argument type 'integer' for parameter 'value'".to_string()),
+            ),
+        ];
+
+        for (value, unit, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", value);
+
+            let query = ParseDurationFn::new(
+                Box::new(Argument::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    ParseDurationFn::parameters()[0].clone(),
+                )),
+                Box::new(Literal::from(Value::from(unit))),
+            );
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
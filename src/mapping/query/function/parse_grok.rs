@@ -0,0 +1,213 @@
+use super::prelude::*;
+use std::collections::BTreeMap;
+
+/// Matches a string against a grok pattern and returns a map of its named
+/// captures. The pattern, along with any custom pattern definitions, must be
+/// given as literals so that it can be compiled once when the mapping is
+/// parsed.
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseGrokFn {
+    value: Box<dyn Function>,
+    pattern: grok::Pattern,
+}
+
+impl ParseGrokFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        pattern: &str,
+        definitions: BTreeMap<String, Value>,
+    ) -> Result<Self> {
+        let pattern = compile(pattern, definitions)?;
+
+        Ok(Self { value, pattern })
+    }
+}
+
+fn compile(pattern: &str, definitions: BTreeMap<String, Value>) -> Result<grok::Pattern> {
+    let mut grok = grok::Grok::with_patterns();
+
+    for (name, definition) in definitions {
+        match definition {
+            Value::Bytes(bytes) => {
+                grok.insert_definition(name, String::from_utf8_lossy(&bytes).into_owned())
+            }
+            v => {
+                return Err(format!(
+                    "invalid grok pattern definition type '{}' for {}",
+                    v.kind(),
+                    name
+                ))
+            }
+        }
+    }
+
+    grok.compile(pattern, true)
+        .map_err(|err| format!("invalid grok pattern: {}", err))
+}
+
+/// Resolves an argument that must not depend on the event.
+fn literal(query: Box<dyn Function>, keyword: &str) -> Result<Value> {
+    if !query.is_constant() {
+        return Err(format!(
+            "parameter {} passed to parse_grok must be a literal",
+            keyword
+        ));
+    }
+
+    match query.execute(&Event::from(""))? {
+        QueryValue::Value(value) => Ok(value),
+        v => unexpected_type!(v),
+    }
+}
+
+impl Function for ParseGrokFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let value = String::from_utf8_lossy(&bytes);
+
+        let matches = self
+            .pattern
+            .match_against(&value)
+            .ok_or("unable to parse input with grok pattern")?;
+
+        let map = matches
+            .iter()
+            .map(|(name, value)| (name.to_owned(), Value::from(value.to_owned())))
+            .collect::<BTreeMap<_, _>>();
+
+        Ok(Value::Map(map).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "pattern",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "definitions",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Map(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseGrokFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        let pattern = match literal(arguments.required("pattern")?, "pattern")? {
+            Value::Bytes(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            v => unexpected_type!(v),
+        };
+
+        let definitions = match arguments.optional("definitions") {
+            Some(query) => match literal(query, "definitions")? {
+                Value::Map(map) => map,
+                v => unexpected_type!(v),
+            },
+            None => BTreeMap::new(),
+        };
+
+        let pattern = compile(&pattern, definitions)?;
+
+        Ok(Self { value, pattern })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_parse_grok() {
+        let cases = vec![
+            (
+                "2020-10-02T23:22:12.223222Z info Hello world",
+                "%{TIMESTAMP_ISO8601:timestamp} %{LOGLEVEL:level} %{GREEDYDATA:message}",
+                json!({}),
+                Ok(json!({
+                    "timestamp": "2020-10-02T23:22:12.223222Z",
+                    "level": "info",
+                    "message": "Hello world",
+                })),
+            ),
+            (
+                "order 1234-ab shipped",
+                "order %{ORDER_ID:id} %{WORD:status}",
+                json!({"ORDER_ID": "%{INT}-[a-z]+"}),
+                Ok(json!({"id": "1234-ab", "status": "shipped"})),
+            ),
+            (
+                // Groups that took no part in the match are empty.
+                "2020-10-02T23:22:12.223222Z",
+                "(%{TIMESTAMP_ISO8601:timestamp}|%{LOGLEVEL:level})",
+                json!({}),
+                Ok(json!({"timestamp": "2020-10-02T23:22:12.223222Z", "level": ""})),
+            ),
+            (
+                "an ungrokkable message",
+                "%{TIMESTAMP_ISO8601:timestamp} %{GREEDYDATA:message}",
+                json!({}),
+                Err("unable to parse input with grok pattern"),
+            ),
+        ];
+
+        for (input, pattern, definitions, exp) in cases {
+            let definitions = match Value::from(definitions) {
+                Value::Map(map) => map,
+                _ => unreachable!(),
+            };
+
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(input));
+            let query = ParseGrokFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                pattern,
+                definitions,
+            )
+            .unwrap();
+
+            assert_eq!(
+                query.execute(&event),
+                exp.map(|v| Value::from(v).into())
+                    .map_err(ToOwned::to_owned),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn check_invalid_pattern() {
+        let path = || Box::new(Path::from(vec![vec!["foo"]]));
+
+        assert_eq!(
+            ParseGrokFn::new(path(), "%{NOT_A_PATTERN}", BTreeMap::new()).err(),
+            Some(
+                "invalid grok pattern: The given pattern definition name \"NOT_A_PATTERN\" \
+                 could not be found in the definition map"
+                    .to_owned()
+            )
+        );
+
+        let mut definitions = BTreeMap::new();
+        definitions.insert("ORDER_ID".to_owned(), Value::from(42));
+        assert_eq!(
+            ParseGrokFn::new(path(), "%{ORDER_ID}", definitions).err(),
+            Some("invalid grok pattern definition type 'integer' for ORDER_ID".to_owned())
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use super::prelude::*;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref DURATION_RE: Regex = Regex::new(
+        r"(?x)
+        ^P
+        (?:(\d+)W)?
+        (?:(\d+)D)?
+        (?:T(?:(\d+)H)?(?:(\d+)M)?(?:(\d+)S)?)?$"
+    )
+    .unwrap();
+}
+
+/// The number of seconds in each of the components captured by
+/// `DURATION_RE`, in order.
+const COMPONENT_SECONDS: [i64; 5] = [7 * 24 * 60 * 60, 24 * 60 * 60, 60 * 60, 60, 1];
+
+/// Parses an ISO 8601 duration, such as `PT1H30M`, into a number of seconds.
+///
+/// Only weeks, days, hours, minutes and whole seconds are supported. Years and
+/// months are rejected, as their length in seconds varies.
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseIso8601DurationFn {
+    value: Box<dyn Function>,
+}
+
+impl ParseIso8601DurationFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+fn parse_duration(input: &str) -> Result<i64> {
+    let invalid = || format!("invalid ISO 8601 duration: {:?}", input);
+    let captures = DURATION_RE.captures(input).ok_or_else(invalid)?;
+
+    // The pattern matches a bare `P`, or a `T` without any time components.
+    if input.ends_with('T') || captures.iter().skip(1).all(|c| c.is_none()) {
+        return Err(invalid());
+    }
+
+    captures
+        .iter()
+        .skip(1)
+        .zip(COMPONENT_SECONDS.iter())
+        .try_fold(0i64, |total, (component, seconds)| match component {
+            None => Ok(total),
+            Some(component) => component
+                .as_str()
+                .parse::<i64>()
+                .ok()
+                .and_then(|n| n.checked_mul(*seconds))
+                .and_then(|n| total.checked_add(n))
+                .ok_or_else(|| format!("ISO 8601 duration {:?} is out of range", input)),
+        })
+}
+
+impl Function for ParseIso8601DurationFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+
+        parse_duration(&String::from_utf8_lossy(&bytes)).map(|n| Value::Integer(n).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseIso8601DurationFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{function::Argument, path::Path};
+
+    #[test]
+    fn check_parse_iso8601_duration() {
+        let cases = vec![
+            (Value::from("PT1H30M"), Ok(Value::from(5400))),
+            (Value::from("P1DT2H"), Ok(Value::from(93600))),
+            (Value::from("P2W"), Ok(Value::from(1_209_600))),
+            (Value::from("PT45S"), Ok(Value::from(45))),
+            (Value::from("P1W1DT1H1M1S"), Ok(Value::from(694_861))),
+            (
+                Value::from("1 hour"),
+                Err(r#"invalid ISO 8601 duration: "1 hour""#.to_string()),
+            ),
+            (
+                Value::from("P"),
+                Err(r#"invalid ISO 8601 duration: "P""#.to_string()),
+            ),
+            (
+                Value::from("P1DT"),
+                Err(r#"invalid ISO 8601 duration: "P1DT""#.to_string()),
+            ),
+            (
+                Value::from("P1Y"),
+                Err(r#"invalid ISO 8601 duration: "P1Y""#.to_string()),
+            ),
+            (
+                Value::from("PT1.5S"),
+                Err(r#"invalid ISO 8601 duration: "PT1.5S""#.to_string()),
+            ),
+            (
+                Value::from("P99999999999999999W"),
+                Err(r#"ISO 8601 duration "P99999999999999999W" is out of range"#.to_string()),
+            ),
+            (
+                Value::from(5400),
+                Err("invalid argument type 'integer' for parameter 'value'".to_string()),
+            ),
+        ];
+
+        for (value, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", value);
+
+            let query = ParseIso8601DurationFn::new(Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                ParseIso8601DurationFn::parameters()[0].clone(),
+            )));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseJsonFn {
+    value: Box<dyn Function>,
+}
+
+impl ParseJsonFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+/// Parses a JSON document of any type into a value.
+pub(super) fn parse_json(bytes: &[u8]) -> Result<Value> {
+    serde_json::from_slice::<serde_json::Value>(bytes)
+        .map(Value::from)
+        .map_err(|err| format!("unable to parse JSON: {}", err))
+}
+
+impl Function for ParseJsonFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+
+        parse_json(&bytes).map(Into::into)
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseJsonFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{function::Argument, path::Path};
+    use serde_json::json;
+
+    #[test]
+    fn check_parse_json() {
+        let cases = vec![
+            (
+                Value::from(r#"{"user": {"id": 1, "tags": ["a"]}}"#),
+                Ok(Value::from(json!({"user": {"id": 1, "tags": ["a"]}}))),
+            ),
+            (Value::from("[1, null]"), Ok(Value::from(json!([1, null])))),
+            (Value::from(r#""hello""#), Ok(Value::from("hello"))),
+            (Value::from("3.5"), Ok(Value::from(3.5))),
+            (
+                Value::from(r#"{"user": "#),
+                Err(
+                    "unable to parse JSON: EOF while parsing a value at line 1 column 9"
+                        .to_string(),
+                ),
+            ),
+            (
+                Value::from("{'a': 1}"),
+                Err("unable to parse JSON: key must be a string at line 1 column 2".to_string()),
+            ),
+            (
+                Value::from(1),
+                Err("invalid argument type 'integer' for parameter 'value'".to_string()),
+            ),
+        ];
+
+        for (value, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", value);
+
+            let query = ParseJsonFn::new(Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                ParseJsonFn::parameters()[0].clone(),
+            )));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseJsonArrayFn {
+    value: Box<dyn Function>,
+}
+
+impl ParseJsonArrayFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for ParseJsonArrayFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let array = required_value!(ctx, self.value, Value::Array(v) => v);
+
+        array
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| match value {
+                Value::Bytes(bytes) => serde_json::from_slice::<serde_json::Value>(&bytes)
+                    .map(Value::from)
+                    .map_err(|err| format!("unable to parse element {} as JSON: {}", i, err)),
+                value => Err(format!(
+                    "unable to parse element {} as JSON: expected string, got {}",
+                    i,
+                    value.kind()
+                )),
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(|array| Value::Array(array).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Array(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseJsonArrayFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_parse_json_array() {
+        let cases = vec![
+            (
+                json!([r#"{"a": 1}"#, "[true, null]", r#""str""#, "2.5"]),
+                Ok(Value::from(json!([{"a": 1}, [true, null], "str", 2.5]))),
+            ),
+            (json!([]), Ok(Value::from(json!([])))),
+            (
+                json!([r#"{"a": 1}"#, r#"{"a": "#, "{}"]),
+                Err(
+                    "unable to parse element 1 as JSON: EOF while parsing a value at line 1 column 6"
+                        .to_string(),
+                ),
+            ),
+            (
+                json!(["{}", 5]),
+                Err("unable to parse element 1 as JSON: expected string, got integer".to_string()),
+            ),
+        ];
+
+        for (value, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(value));
+
+            let query = ParseJsonArrayFn::new(Box::new(Path::from(vec![vec!["foo"]])));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
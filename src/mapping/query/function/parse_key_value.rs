@@ -0,0 +1,317 @@
+use super::prelude::*;
+use bytes::Bytes;
+use std::collections::BTreeMap;
+
+/// Parses `key=value` pairs, such as logfmt, into a map. Keys and values can
+/// be quoted with `"`, in which case `\"` and `\\` are unescaped. A key with
+/// no value is set to `true`, and a key that is repeated takes its last value.
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseKeyValueFn {
+    value: Box<dyn Function>,
+    key_value_delimiter: Option<Box<dyn Function>>,
+    field_delimiter: Option<Box<dyn Function>>,
+}
+
+impl ParseKeyValueFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        key_value_delimiter: Option<Box<dyn Function>>,
+        field_delimiter: Option<Box<dyn Function>>,
+    ) -> Self {
+        Self {
+            value,
+            key_value_delimiter,
+            field_delimiter,
+        }
+    }
+}
+
+fn delimiter(bytes: Option<Bytes>, default: &str, keyword: &str) -> Result<String> {
+    let delimiter = bytes
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .unwrap_or_else(|| default.to_owned());
+
+    if delimiter.is_empty() {
+        return Err(format!("{} must not be empty", keyword));
+    }
+
+    Ok(delimiter)
+}
+
+/// Parses `input` into a map of its pairs, using the given delimiters.
+pub(super) fn parse_key_value(
+    input: &str,
+    key_value_delimiter: &str,
+    field_delimiter: &str,
+) -> Result<BTreeMap<String, Value>> {
+    let parser = Parser {
+        input,
+        pos: 0,
+        key_value_delimiter,
+        field_delimiter,
+    };
+
+    parser
+        .parse()
+        .map_err(|err| format!("unable to parse key value pairs: {}", err))
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    key_value_delimiter: &'a str,
+    field_delimiter: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.rest().starts_with(token);
+        if found {
+            self.pos += token.len();
+        }
+        found
+    }
+
+    /// Reads a key or a value, which ends at any of the given delimiters
+    /// unless it is quoted.
+    fn token(&mut self, delimiters: &[&str]) -> Result<String> {
+        if self.rest().starts_with('"') {
+            return self.quoted();
+        }
+
+        let rest = self.rest();
+        let end = delimiters
+            .iter()
+            .filter_map(|delimiter| rest.find(delimiter))
+            .min()
+            .unwrap_or(rest.len());
+        self.pos += end;
+
+        Ok(rest[..end].trim().to_owned())
+    }
+
+    fn quoted(&mut self) -> Result<String> {
+        let start = self.pos;
+        let mut token = String::new();
+        let mut chars = self.rest().char_indices().skip(1);
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(token);
+                }
+                '\\' => match chars.next() {
+                    Some((_, c)) if c == '"' || c == '\\' => token.push(c),
+                    Some((_, c)) => {
+                        token.push('\\');
+                        token.push(c);
+                    }
+                    None => break,
+                },
+                c => token.push(c),
+            }
+        }
+
+        Err(format!("unterminated quoted string at byte {}", start))
+    }
+
+    fn parse(mut self) -> Result<BTreeMap<String, Value>> {
+        let mut map = BTreeMap::new();
+
+        loop {
+            self.skip_whitespace();
+            while self.eat(self.field_delimiter) {
+                self.skip_whitespace();
+            }
+            if self.rest().is_empty() {
+                break;
+            }
+
+            let key = self.token(&[self.key_value_delimiter, self.field_delimiter])?;
+            let end = self.pos;
+            self.skip_whitespace();
+
+            let value = if self.eat(self.key_value_delimiter) {
+                self.skip_whitespace();
+                Value::from(self.token(&[self.field_delimiter])?)
+            } else {
+                self.pos = end;
+                Value::Boolean(true)
+            };
+            map.insert(key, value);
+
+            let end = self.pos;
+            self.skip_whitespace();
+
+            // A whitespace delimiter has already been consumed along with any
+            // other whitespace.
+            let delimited = self.rest().is_empty()
+                || self.eat(self.field_delimiter)
+                || (self.field_delimiter.trim().is_empty() && self.pos > end);
+            if !delimited {
+                return Err(format!("expected field delimiter at byte {}", end));
+            }
+        }
+
+        Ok(map)
+    }
+}
+
+impl Function for ParseKeyValueFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let key_value_delimiter = delimiter(
+            optional_value!(ctx, self.key_value_delimiter, Value::Bytes(v) => v),
+            "=",
+            "key_value_delimiter",
+        )?;
+        let field_delimiter = delimiter(
+            optional_value!(ctx, self.field_delimiter, Value::Bytes(v) => v),
+            " ",
+            "field_delimiter",
+        )?;
+
+        parse_key_value(
+            &String::from_utf8_lossy(&bytes),
+            &key_value_delimiter,
+            &field_delimiter,
+        )
+        .map(|map| Value::Map(map).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "key_value_delimiter",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: false,
+            },
+            Parameter {
+                keyword: "field_delimiter",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseKeyValueFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let key_value_delimiter = arguments.optional("key_value_delimiter");
+        let field_delimiter = arguments.optional("field_delimiter");
+
+        Ok(Self {
+            value,
+            key_value_delimiter,
+            field_delimiter,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_parse_key_value() {
+        let cases = vec![
+            (
+                r#"at=info method=GET path="/a b" status=200  duration=0.5ms"#,
+                None,
+                None,
+                Ok(json!({
+                    "at": "info",
+                    "method": "GET",
+                    "path": "/a b",
+                    "status": "200",
+                    "duration": "0.5ms",
+                })),
+            ),
+            (
+                r#"msg="said \"hi\" to C:\\" debug "quoted key"=1"#,
+                None,
+                None,
+                Ok(json!({"msg": r#"said "hi" to C:\"#, "debug": true, "quoted key": "1"})),
+            ),
+            (
+                "user: bob, id: 42, role: admin, id: 43",
+                Some(":"),
+                Some(","),
+                Ok(json!({"user": "bob", "id": "43", "role": "admin"})),
+            ),
+            (
+                "a=1&b=&c=x=y",
+                None,
+                Some("&"),
+                Ok(json!({"a": "1", "b": "", "c": "x=y"})),
+            ),
+            ("", None, None, Ok(json!({}))),
+            (
+                r#"a=1 b="unterminated"#,
+                None,
+                None,
+                Err("unable to parse key value pairs: unterminated quoted string at byte 6"),
+            ),
+            (
+                r#"a="1"x b=2"#,
+                None,
+                None,
+                Err("unable to parse key value pairs: expected field delimiter at byte 5"),
+            ),
+            (
+                r#"a="1"x, b=2"#,
+                None,
+                Some(","),
+                Err("unable to parse key value pairs: expected field delimiter at byte 5"),
+            ),
+            (
+                "a=1",
+                Some(""),
+                None,
+                Err("key_value_delimiter must not be empty"),
+            ),
+        ];
+
+        for (input, key_value_delimiter, field_delimiter, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(input));
+            let literal = |s: Option<&str>| {
+                s.map(|s| Box::new(Literal::from(Value::from(s))) as Box<dyn Function>)
+            };
+            let query = ParseKeyValueFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                literal(key_value_delimiter),
+                literal(field_delimiter),
+            );
+
+            assert_eq!(
+                query.execute(&event),
+                exp.map(|v| Value::from(v).into())
+                    .map_err(ToOwned::to_owned),
+                "{}",
+                input
+            );
+        }
+    }
+}
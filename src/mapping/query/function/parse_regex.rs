@@ -0,0 +1,149 @@
+use super::prelude::*;
+use std::collections::BTreeMap;
+
+/// Matches a string against a regex and returns a map of its named capture
+/// groups. Groups that took no part in the match are null.
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseRegexFn {
+    value: Box<dyn Function>,
+    pattern: Box<dyn Function>,
+}
+
+impl ParseRegexFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>, pattern: Box<dyn Function>) -> Self {
+        Self { value, pattern }
+    }
+}
+
+impl Function for ParseRegexFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let regex = required!(ctx, self.pattern, QueryValue::Regex(r) => r);
+
+        let value = String::from_utf8_lossy(&bytes);
+        let captures = regex
+            .regex()
+            .captures(&value)
+            .ok_or_else(|| format!("value does not match regex {}", regex))?;
+
+        let map = regex
+            .regex()
+            .capture_names()
+            .flatten()
+            .map(|name| {
+                let value = captures
+                    .name(name)
+                    .map(|capture| Value::from(capture.as_str().to_owned()))
+                    .unwrap_or(Value::Null);
+                (name.to_owned(), value)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        Ok(Value::Map(map).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "pattern",
+                accepts: |v| matches!(v, QueryValue::Regex(_)),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseRegexFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let pattern = arguments.required("pattern")?;
+
+        Ok(Self { value, pattern })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{function::Argument, path::Path, regex::Regex};
+    use serde_json::json;
+
+    fn regex(pattern: &str, insensitive: bool) -> Box<dyn Function> {
+        Box::new(Literal::from(QueryValue::Regex(
+            Regex::new(pattern.to_owned(), false, insensitive, false).unwrap(),
+        )))
+    }
+
+    #[test]
+    fn check_parse_regex() {
+        let cases = vec![
+            (
+                "INFO starting up",
+                regex(r"^(?P<level>\w+) (?P<msg>.*)$", false),
+                Ok(json!({"level": "INFO", "msg": "starting up"})),
+            ),
+            (
+                "GET /index.html",
+                regex(
+                    r"^(?P<method>[A-Z]+) (?P<path>\S+)(?: (?P<protocol>\S+))?",
+                    false,
+                ),
+                Ok(json!({"method": "GET", "path": "/index.html", "protocol": null})),
+            ),
+            (
+                // Unnamed groups are left out.
+                "user=bob id=42",
+                regex(r"user=(\w+) id=(?P<id>\d+)", false),
+                Ok(json!({"id": "42"})),
+            ),
+            (
+                "Level: WARN",
+                regex(r"level: (?P<level>\w+)", true),
+                Ok(json!({"level": "WARN"})),
+            ),
+            (
+                "starting up",
+                regex(r"^(?P<level>\w+): (?P<msg>.*)$", false),
+                Err(r"value does not match regex /^(?P<level>\w+): (?P<msg>.*)$/".to_owned()),
+            ),
+        ];
+
+        for (input, pattern, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(input));
+            let query = ParseRegexFn::new(Box::new(Path::from(vec![vec!["foo"]])), pattern);
+
+            assert_eq!(
+                query.execute(&event),
+                exp.map(|v| Value::from(v).into()),
+                "{}",
+                input
+            );
+        }
+
+        let mut event = Event::from("");
+        event
+            .as_mut_log()
+            .insert("foo", Value::from("INFO starting up"));
+        let query = ParseRegexFn::new(
+            Box::new(Path::from(vec![vec!["foo"]])),
+            Box::new(Argument::new(
+                Box::new(Literal::from(Value::from(r"^(?P<level>\w+)"))),
+                ParseRegexFn::parameters()[1].clone(),
+            )),
+        );
+
+        assert_eq!(
+            query.execute(&event),
+            Err("invalid argument type 'string' for parameter 'pattern'".to_string())
+        );
+    }
+}
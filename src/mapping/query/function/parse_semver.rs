@@ -0,0 +1,209 @@
+use super::prelude::*;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+lazy_static! {
+    // See https://semver.org/#is-there-a-suggested-regular-expression-regex-to-check-a-semver-string
+    static ref SEMVER_RE: Regex = Regex::new(
+        r"(?x)
+        ^(0|[1-9]\d*)\.(0|[1-9]\d*)\.(0|[1-9]\d*)
+        (?:-((?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*)(?:\.(?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*))*))?
+        (?:\+([0-9a-zA-Z-]+(?:\.[0-9a-zA-Z-]+)*))?$"
+    )
+    .unwrap();
+}
+
+/// A parsed semantic version, as defined by https://semver.org.
+#[derive(Debug, PartialEq)]
+pub(super) struct Version {
+    major: i64,
+    minor: i64,
+    patch: i64,
+    prerelease: Option<String>,
+    build: Option<String>,
+}
+
+impl Version {
+    pub(super) fn parse(input: &str) -> Result<Self> {
+        let invalid = || format!("invalid semantic version: {}", input);
+        let captures = SEMVER_RE.captures(input).ok_or_else(invalid)?;
+        let number = |i: usize| {
+            captures
+                .get(i)
+                .ok_or_else(invalid)?
+                .as_str()
+                .parse::<i64>()
+                .map_err(|_| invalid())
+        };
+
+        Ok(Self {
+            major: number(1)?,
+            minor: number(2)?,
+            patch: number(3)?,
+            prerelease: captures.get(4).map(|m| m.as_str().to_owned()),
+            build: captures.get(5).map(|m| m.as_str().to_owned()),
+        })
+    }
+
+    /// Compares two versions by precedence. Build metadata is ignored.
+    pub(super) fn precedence(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.prerelease, &other.prerelease) {
+                (None, None) => Ordering::Equal,
+                // A pre-release version has lower precedence than the normal
+                // version it is attached to.
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_prerelease(a, b),
+            })
+    }
+}
+
+fn compare_prerelease(a: &str, b: &str) -> Ordering {
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                // Numeric identifiers have lower precedence than
+                // alphanumeric ones.
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+impl From<Version> for Value {
+    fn from(version: Version) -> Self {
+        let mut map = BTreeMap::new();
+        map.insert("major".to_owned(), Value::Integer(version.major));
+        map.insert("minor".to_owned(), Value::Integer(version.minor));
+        map.insert("patch".to_owned(), Value::Integer(version.patch));
+
+        if let Some(prerelease) = version.prerelease {
+            map.insert("prerelease".to_owned(), Value::from(prerelease));
+        }
+
+        if let Some(build) = version.build {
+            map.insert("build".to_owned(), Value::from(build));
+        }
+
+        Value::Map(map)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseSemverFn {
+    value: Box<dyn Function>,
+}
+
+impl ParseSemverFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for ParseSemverFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let version = Version::parse(&String::from_utf8_lossy(&bytes))?;
+
+        Ok(Value::from(version).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseSemverFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_parse_semver() {
+        let cases = vec![
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from("1.2.3-beta.1+build.5"));
+                    event
+                },
+                Ok(Value::from(json!({
+                    "major": 1,
+                    "minor": 2,
+                    "patch": 3,
+                    "prerelease": "beta.1",
+                    "build": "build.5",
+                }))),
+                ParseSemverFn::new(Box::new(Path::from(vec![vec!["foo"]]))),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from("10.0.21"));
+                    event
+                },
+                Ok(Value::from(json!({"major": 10, "minor": 0, "patch": 21}))),
+                ParseSemverFn::new(Box::new(Path::from(vec![vec!["foo"]]))),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from("1.02.3"));
+                    event
+                },
+                Err("invalid semantic version: 1.02.3".to_string()),
+                ParseSemverFn::new(Box::new(Path::from(vec![vec!["foo"]]))),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from("1.2"));
+                    event
+                },
+                Err("invalid semantic version: 1.2".to_string()),
+                ParseSemverFn::new(Box::new(Path::from(vec![vec!["foo"]]))),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+}
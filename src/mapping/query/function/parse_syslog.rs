@@ -0,0 +1,185 @@
+use super::prelude::*;
+use crate::mapping::context;
+use chrono::{DateTime, Datelike, Utc};
+use std::collections::BTreeMap;
+use syslog_loose::{IncompleteDate, Message, ProcId, Protocol};
+
+/// Parses an RFC 3164 or RFC 5424 syslog message into a map, with the same
+/// fields as the syslog source. Structured data is nested under the ID of
+/// each element.
+///
+/// As in the source, parsing is lenient and fields that can't be found are
+/// left out rather than failing the query.
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseSyslogFn {
+    value: Box<dyn Function>,
+}
+
+impl ParseSyslogFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+/// RFC 3164 timestamps have no year, so the one of the current time is used,
+/// unless that would put a December message in the future.
+fn resolve_year((month, _date, _hour, _min, _sec): IncompleteDate) -> i32 {
+    let now = context::now();
+    if now.month() == 1 && month == 12 {
+        now.year() - 1
+    } else {
+        now.year()
+    }
+}
+
+fn message_to_value(message: Message<&str>) -> Value {
+    let mut map = BTreeMap::new();
+
+    map.insert("message".to_owned(), Value::from(message.msg.to_owned()));
+    if let Some(timestamp) = message.timestamp {
+        map.insert(
+            "timestamp".to_owned(),
+            Value::from(DateTime::<Utc>::from(timestamp)),
+        );
+    }
+    if let Some(host) = message.hostname {
+        map.insert("hostname".to_owned(), Value::from(host.to_owned()));
+    }
+    if let Some(severity) = message.severity {
+        map.insert(
+            "severity".to_owned(),
+            Value::from(severity.as_str().to_owned()),
+        );
+    }
+    if let Some(facility) = message.facility {
+        map.insert(
+            "facility".to_owned(),
+            Value::from(facility.as_str().to_owned()),
+        );
+    }
+    if let Protocol::RFC5424(version) = message.protocol {
+        map.insert("version".to_owned(), Value::from(version as i64));
+    }
+    if let Some(app_name) = message.appname {
+        map.insert("appname".to_owned(), Value::from(app_name.to_owned()));
+    }
+    if let Some(msg_id) = message.msgid {
+        map.insert("msgid".to_owned(), Value::from(msg_id.to_owned()));
+    }
+    if let Some(procid) = message.procid {
+        let value = match procid {
+            ProcId::PID(pid) => Value::from(pid as i64),
+            ProcId::Name(name) => Value::from(name.to_owned()),
+        };
+        map.insert("procid".to_owned(), value);
+    }
+
+    for element in message.structured_data {
+        let params = element
+            .params
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), Value::from(value.to_owned())))
+            .collect();
+        map.insert(element.id.to_owned(), Value::Map(params));
+    }
+
+    Value::Map(map)
+}
+
+impl Function for ParseSyslogFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let line = String::from_utf8_lossy(&bytes);
+
+        let message = syslog_loose::parse_message_with_year(line.trim(), resolve_year);
+
+        Ok(message_to_value(message).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseSyslogFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::{query::path::Path, ExecutionContext};
+    use chrono::{Local, TimeZone};
+    use serde_json::json;
+
+    fn parse_syslog(line: &str, now: DateTime<Utc>) -> Result<QueryValue> {
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from(line));
+        let query = ParseSyslogFn::new(Box::new(Path::from(vec![vec!["foo"]])));
+
+        context::scoped_new(&ExecutionContext::new().with_now(now), || {
+            query.execute(&event)
+        })
+    }
+
+    #[test]
+    fn rfc5424() {
+        let line = r#"<13>1 2019-02-13T19:48:34+00:00 74794bfb6795 root 8449 - [meta sequenceId="1" sysUpTime="37" language="EN"][origin ip="192.168.0.1" software="test"] i am foobar"#;
+
+        let mut exp = Value::from(json!({
+            "message": "i am foobar",
+            "hostname": "74794bfb6795",
+            "severity": "notice",
+            "facility": "user",
+            "version": 1,
+            "appname": "root",
+            "procid": 8449,
+            "meta": {"sequenceId": "1", "sysUpTime": "37", "language": "EN"},
+            "origin": {"ip": "192.168.0.1", "software": "test"},
+        }));
+        if let Value::Map(map) = &mut exp {
+            map.insert(
+                "timestamp".to_owned(),
+                Value::from(Utc.ymd(2019, 2, 13).and_hms(19, 48, 34)),
+            );
+        }
+
+        assert_eq!(parse_syslog(line, Utc::now()), Ok(exp.into()));
+    }
+
+    #[test]
+    fn rfc3164_year() {
+        let line = "<13>Dec 31 23:59:00 74794bfb6795 root[8539]: i am foobar";
+
+        for (now, year) in vec![
+            (Utc.ymd(2021, 1, 4).and_hms(12, 0, 0), 2020),
+            (Utc.ymd(2021, 6, 1).and_hms(12, 0, 0), 2021),
+        ] {
+            let mut exp = Value::from(json!({
+                "message": "i am foobar",
+                "hostname": "74794bfb6795",
+                "severity": "notice",
+                "facility": "user",
+                "appname": "root",
+                "procid": 8539,
+            }));
+            if let Value::Map(map) = &mut exp {
+                let timestamp: DateTime<Utc> = Local.ymd(year, 12, 31).and_hms(23, 59, 0).into();
+                map.insert("timestamp".to_owned(), Value::from(timestamp));
+            }
+
+            assert_eq!(parse_syslog(line, now), Ok(exp.into()));
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use super::prelude::*;
+use crate::types::Conversion;
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, TimeZone, Utc};
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseTimestampFn {
+    value: Box<dyn Function>,
+    format: Box<dyn Function>,
+    timezone: Option<Box<dyn Function>>,
+}
+
+impl ParseTimestampFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        format: Box<dyn Function>,
+        timezone: Option<Box<dyn Function>>,
+    ) -> Self {
+        Self {
+            value,
+            format,
+            timezone,
+        }
+    }
+}
+
+/// The time zone that timestamps without one are taken to be in.
+enum Zone {
+    Utc,
+    Local,
+    Offset(FixedOffset),
+}
+
+impl Zone {
+    fn parse(zone: &str) -> Result<Self> {
+        let invalid = || {
+            format!(
+                r#"invalid timezone {:?}, expected "UTC", "local" or an offset such as "+02:00""#,
+                zone
+            )
+        };
+
+        match zone {
+            "UTC" | "utc" | "Z" => return Ok(Zone::Utc),
+            "local" => return Ok(Zone::Local),
+            _ => {}
+        }
+
+        let sign = match zone.chars().next() {
+            Some('+') => 1,
+            Some('-') => -1,
+            _ => return Err(invalid()),
+        };
+        let digits = zone[1..].replace(':', "");
+        if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let hours = digits[..2].parse::<i32>().map_err(|_| invalid())?;
+        let minutes = digits[2..].parse::<i32>().map_err(|_| invalid())?;
+
+        FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+            .map(Zone::Offset)
+            .ok_or_else(invalid)
+    }
+
+    fn to_utc(&self, datetime: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            Zone::Utc => Some(DateTime::from_utc(datetime, Utc)),
+            Zone::Local => Local
+                .from_local_datetime(&datetime)
+                .single()
+                .map(Into::into),
+            Zone::Offset(offset) => offset
+                .from_local_datetime(&datetime)
+                .single()
+                .map(Into::into),
+        }
+    }
+}
+
+/// Does the format include a time zone, as in `Conversion`?
+fn format_has_zone(format: &str) -> bool {
+    ["%Z", "%z", "%:z", "%#z", "%+"]
+        .iter()
+        .any(|specifier| format.contains(specifier))
+}
+
+impl Function for ParseTimestampFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value,
+            Value::Timestamp(v) => return Ok(Value::Timestamp(v).into()),
+            Value::Bytes(v) => v,
+        );
+        let format = required_value!(ctx, self.format, Value::Bytes(v) => v);
+        let format = String::from_utf8_lossy(&format);
+
+        let zone = optional_value!(ctx, self.timezone, Value::Bytes(v) => v)
+            .map(|zone| Zone::parse(&String::from_utf8_lossy(&zone)))
+            .transpose()?;
+
+        if let Some(zone) = zone.filter(|_| !format_has_zone(&format)) {
+            let value = String::from_utf8_lossy(&bytes);
+            let datetime = NaiveDateTime::parse_from_str(&value, &format)
+                .map_err(|err| format!("Invalid timestamp {:?}: {}", value, err))?;
+
+            return zone
+                .to_utc(datetime)
+                .map(|datetime| Value::Timestamp(datetime).into())
+                .ok_or_else(|| format!("Invalid timestamp {:?}: ambiguous local time", value));
+        }
+
+        // Formats with a time zone are parsed as such, any others are taken
+        // to be in local time.
+        let conversion = format!("timestamp|{}", format)
+            .parse::<Conversion>()
+            .map_err(|err| err.to_string())?;
+
+        conversion
+            .convert::<Value>(bytes)
+            .map(Into::into)
+            .map_err(|err| err.to_string())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| {
+                    matches!(
+                        v,
+                        QueryValue::Value(Value::Bytes(_)) | QueryValue::Value(Value::Timestamp(_))
+                    )
+                },
+                required: true,
+            },
+            Parameter {
+                keyword: "format",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "timezone",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseTimestampFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let format = arguments.required("format")?;
+        let timezone = arguments.optional("timezone");
+
+        Ok(Self {
+            value,
+            format,
+            timezone,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{function::Argument, path::Path};
+
+    #[test]
+    fn check_parse_timestamp() {
+        let cases = vec![
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from("16/10/2019:12:00:00 +0100"));
+                    event
+                },
+                Ok(Value::from(Utc.ymd(2019, 10, 16).and_hms(11, 0, 0))),
+                ParseTimestampFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from("%d/%m/%Y:%H:%M:%S %z"))),
+                    None,
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from(Utc.ymd(2019, 10, 16).and_hms(11, 0, 0)));
+                    event
+                },
+                Ok(Value::from(Utc.ymd(2019, 10, 16).and_hms(11, 0, 0))),
+                ParseTimestampFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from("%d/%m/%Y:%H:%M:%S %z"))),
+                    None,
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from("yesterday"));
+                    event
+                },
+                Err(
+                    "Invalid timestamp \"yesterday\": input contains invalid characters"
+                        .to_string(),
+                ),
+                ParseTimestampFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from("%d/%m/%Y:%H:%M:%S %z"))),
+                    None,
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::Integer(1571223600));
+                    event
+                },
+                Err("invalid argument type 'integer' for parameter 'value'".to_string()),
+                ParseTimestampFn::new(
+                    Box::new(Argument::new(
+                        Box::new(Path::from(vec![vec!["foo"]])),
+                        ParseTimestampFn::parameters()[0].clone(),
+                    )),
+                    Box::new(Literal::from(Value::from("%d/%m/%Y:%H:%M:%S %z"))),
+                    None,
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+
+    #[test]
+    fn timezone() {
+        let cases = vec![
+            (
+                "2021-03-12 14:02:55",
+                "%Y-%m-%d %H:%M:%S",
+                "UTC",
+                Ok(Utc.ymd(2021, 3, 12).and_hms(14, 2, 55)),
+            ),
+            (
+                "2021-03-12 14:02:55.250",
+                "%Y-%m-%d %H:%M:%S%.f",
+                "+02:00",
+                Ok(Utc.ymd(2021, 3, 12).and_hms_milli(12, 2, 55, 250)),
+            ),
+            (
+                "12/Mar/2021:14:02:55",
+                "%d/%b/%Y:%T",
+                "-0530",
+                Ok(Utc.ymd(2021, 3, 12).and_hms(19, 32, 55)),
+            ),
+            (
+                "2021-03-12 14:02:55",
+                "%Y-%m-%d %H:%M:%S",
+                "local",
+                Ok(Local.ymd(2021, 3, 12).and_hms(14, 2, 55).into()),
+            ),
+            (
+                // A time zone in the value takes precedence.
+                "12/Mar/2021:14:02:55 +0000",
+                "%d/%b/%Y:%T %z",
+                "+02:00",
+                Ok(Utc.ymd(2021, 3, 12).and_hms(14, 2, 55)),
+            ),
+            (
+                "2021-03-12",
+                "%Y-%m-%d %H:%M:%S",
+                "UTC",
+                Err(r#"Invalid timestamp "2021-03-12": premature end of input"#),
+            ),
+            (
+                "2021-03-12 14:02:55",
+                "%Y-%m-%d %H:%M:%S",
+                "Mars/Olympus_Mons",
+                Err(
+                    r#"invalid timezone "Mars/Olympus_Mons", expected "UTC", "local" or an offset such as "+02:00""#,
+                ),
+            ),
+            (
+                "2021-03-12 14:02:55",
+                "%Y-%m-%d %H:%M:%S",
+                "+2",
+                Err(
+                    r#"invalid timezone "+2", expected "UTC", "local" or an offset such as "+02:00""#,
+                ),
+            ),
+        ];
+
+        for (value, format, timezone, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(value));
+            let query = ParseTimestampFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                Box::new(Literal::from(Value::from(format))),
+                Some(Box::new(Literal::from(Value::from(timezone)))),
+            );
+
+            assert_eq!(
+                query.execute(&event),
+                exp.map(|v| Value::from(v).into())
+                    .map_err(ToOwned::to_owned),
+                "{}",
+                value
+            );
+        }
+    }
+}
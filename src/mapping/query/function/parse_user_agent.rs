@@ -0,0 +1,188 @@
+use super::prelude::*;
+use std::collections::BTreeMap;
+use woothee::parser::{Parser, WootheeResult};
+
+/// The family reported for anything that isn't recognized.
+const OTHER: &str = "Other";
+
+/// The value woothee reports for anything it doesn't recognize.
+const UNKNOWN: &str = "UNKNOWN";
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ParseUserAgentFn {
+    value: Box<dyn Function>,
+}
+
+impl ParseUserAgentFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+fn family(family: &str) -> Value {
+    match family {
+        UNKNOWN | "" => Value::from(OTHER.to_owned()),
+        family => Value::from(family.to_owned()),
+    }
+}
+
+fn version(version: &str) -> Value {
+    match version {
+        UNKNOWN | "" => Value::Null,
+        version => Value::from(version.to_owned()),
+    }
+}
+
+fn family_map(fields: Vec<(&str, Value)>) -> Value {
+    Value::Map(
+        fields
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value))
+            .collect(),
+    )
+}
+
+/// Parses a `User-Agent` header into the family and version of the browser
+/// and operating system, and the category of the device, such as `pc`,
+/// `smartphone` or `crawler`, using the woothee dataset.
+///
+/// Agents that aren't recognized are reported with an `Other` family and a
+/// null version, rather than as an error.
+fn parse_user_agent(agent: &str) -> BTreeMap<String, Value> {
+    let result = Parser::new().parse(agent).unwrap_or_default();
+    let WootheeResult {
+        name,
+        category,
+        os,
+        os_version,
+        version: browser_version,
+        ..
+    } = result;
+
+    let mut map = BTreeMap::new();
+    map.insert(
+        "browser".to_owned(),
+        family_map(vec![
+            ("family", family(name)),
+            ("version", version(browser_version)),
+        ]),
+    );
+    map.insert(
+        "os".to_owned(),
+        family_map(vec![
+            ("family", family(os)),
+            ("version", version(&os_version)),
+        ]),
+    );
+    map.insert(
+        "device".to_owned(),
+        family_map(vec![("family", family(category))]),
+    );
+    map
+}
+
+impl Function for ParseUserAgentFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+
+        Ok(Value::Map(parse_user_agent(&String::from_utf8_lossy(&bytes))).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ParseUserAgentFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_parse_user_agent() {
+        let cases = vec![
+            (
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/87.0.4280.88 Safari/537.36",
+                json!({
+                    "browser": { "family": "Chrome", "version": "87.0.4280.88" },
+                    "os": { "family": "Windows 10", "version": "NT 10.0" },
+                    "device": { "family": "pc" },
+                }),
+            ),
+            (
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 14_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/14.0.1 Mobile/15E148 Safari/604.1",
+                json!({
+                    "browser": { "family": "Safari", "version": "14.0.1" },
+                    "os": { "family": "iPhone", "version": "14.2" },
+                    "device": { "family": "smartphone" },
+                }),
+            ),
+            (
+                "Mozilla/5.0 (X11; Linux x86_64; rv:83.0) Gecko/20100101 Firefox/83.0",
+                json!({
+                    "browser": { "family": "Firefox", "version": "83.0" },
+                    "os": { "family": "Linux", "version": null },
+                    "device": { "family": "pc" },
+                }),
+            ),
+            (
+                "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+                json!({
+                    "browser": { "family": "Googlebot", "version": null },
+                    "os": { "family": "Other", "version": null },
+                    "device": { "family": "crawler" },
+                }),
+            ),
+            (
+                "my-internal-client",
+                json!({
+                    "browser": { "family": "Other", "version": null },
+                    "os": { "family": "Other", "version": null },
+                    "device": { "family": "Other" },
+                }),
+            ),
+        ];
+
+        for (agent, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(agent));
+
+            let query = ParseUserAgentFn::new(Box::new(Path::from(vec![vec!["foo"]])));
+
+            assert_eq!(query.execute(&event), Ok(Value::from(exp).into()));
+        }
+    }
+
+    #[test]
+    fn check_parse_user_agent_non_string() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from(5));
+
+        let query = ParseUserAgentFn::new(Box::new(Argument::new(
+            Box::new(Path::from(vec![vec!["foo"]])),
+            ParseUserAgentFn::parameters()[0].clone(),
+        )));
+
+        assert_eq!(
+            query.execute(&event),
+            Err("invalid argument type 'integer' for parameter 'value'".to_string())
+        );
+    }
+}
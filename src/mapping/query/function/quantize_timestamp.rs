@@ -0,0 +1,139 @@
+use super::prelude::*;
+use chrono::{TimeZone, Utc};
+
+#[derive(Debug)]
+pub(in crate::mapping) struct QuantizeTimestampFn {
+    value: Box<dyn Function>,
+    interval: Box<dyn Function>,
+}
+
+impl QuantizeTimestampFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>, interval: Box<dyn Function>) -> Self {
+        Self { value, interval }
+    }
+}
+
+impl Function for QuantizeTimestampFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let timestamp = required_value!(ctx, self.value, Value::Timestamp(v) => v);
+        let interval = required_value!(ctx, self.interval, Value::Integer(v) => v);
+
+        if interval <= 0 {
+            return Err(format!(
+                "quantize interval must be positive, got {}",
+                interval
+            ));
+        }
+
+        // Rounds down to a multiple of the interval since the Unix epoch, also
+        // for timestamps before it.
+        let seconds = timestamp.timestamp();
+        seconds
+            .checked_sub(seconds.rem_euclid(interval))
+            .and_then(|seconds| Utc.timestamp_opt(seconds, 0).single())
+            .map(|quantized| Value::Timestamp(quantized).into())
+            .ok_or_else(|| {
+                format!(
+                    "unable to quantize {} to an interval of {} seconds: out of range",
+                    timestamp.to_rfc3339(),
+                    interval
+                )
+            })
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Timestamp(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "interval",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Integer(_))),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for QuantizeTimestampFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let interval = arguments.required("interval")?;
+
+        Ok(Self { value, interval })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_quantize_timestamp() {
+        let cases = vec![
+            (
+                Utc.ymd(2020, 2, 12).and_hms_milli(14, 37, 21, 500),
+                300,
+                Ok(Value::from(Utc.ymd(2020, 2, 12).and_hms(14, 35, 0))),
+            ),
+            (
+                Utc.ymd(2020, 2, 12).and_hms(14, 35, 0),
+                300,
+                Ok(Value::from(Utc.ymd(2020, 2, 12).and_hms(14, 35, 0))),
+            ),
+            (
+                Utc.ymd(2020, 2, 12).and_hms(14, 37, 21),
+                3600,
+                Ok(Value::from(Utc.ymd(2020, 2, 12).and_hms(14, 0, 0))),
+            ),
+            (
+                Utc.ymd(1969, 12, 31).and_hms(23, 59, 59),
+                3600,
+                Ok(Value::from(Utc.ymd(1969, 12, 31).and_hms(23, 0, 0))),
+            ),
+            (
+                Utc.ymd(1969, 12, 31).and_hms(23, 59, 59),
+                10_000_000_000_000_i64,
+                Err("unable to quantize 1969-12-31T23:59:59+00:00 to an interval of 10000000000000 seconds: out of range".to_string()),
+            ),
+            (
+                Utc.ymd(2020, 2, 12).and_hms(14, 37, 21),
+                0,
+                Err("quantize interval must be positive, got 0".to_string()),
+            ),
+        ];
+
+        for (timestamp, interval, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(timestamp));
+            let query = QuantizeTimestampFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                Box::new(Literal::from(Value::from(interval))),
+            );
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from("2020-02-12"));
+        let query = QuantizeTimestampFn::new(
+            Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                QuantizeTimestampFn::parameters()[0].clone(),
+            )),
+            Box::new(Literal::from(Value::from(300))),
+        );
+
+        assert_eq!(
+            query.execute(&event),
+            Err("invalid argument type 'string' for parameter 'value'".to_string())
+        );
+    }
+}
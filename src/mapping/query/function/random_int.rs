@@ -0,0 +1,111 @@
+use super::prelude::*;
+use crate::mapping::context;
+use rand::Rng;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct RandomIntFn {
+    min: Box<dyn Function>,
+    max: Box<dyn Function>,
+}
+
+impl RandomIntFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(min: Box<dyn Function>, max: Box<dyn Function>) -> Self {
+        Self { min, max }
+    }
+}
+
+impl Function for RandomIntFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let min = required_value!(ctx, self.min, Value::Integer(v) => v);
+        let max = required_value!(ctx, self.max, Value::Integer(v) => v);
+
+        if min > max {
+            return Err(format!(
+                "min must not be greater than max, got {} and {}",
+                min, max
+            ));
+        }
+
+        let value = context::with_rng(|rng| rng.gen_range(min..=max));
+
+        Ok(Value::Integer(value).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "min",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Integer(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "max",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Integer(_))),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for RandomIntFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let min = arguments.required("min")?;
+        let max = arguments.required("max")?;
+
+        Ok(Self { min, max })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::ExecutionContext;
+
+    fn random_int(min: i64, max: i64) -> RandomIntFn {
+        RandomIntFn::new(
+            Box::new(Literal::from(Value::Integer(min))),
+            Box::new(Literal::from(Value::Integer(max))),
+        )
+    }
+
+    #[test]
+    fn check_random_int() {
+        let event = Event::from("");
+
+        for _ in 0..100 {
+            match random_int(-3, 3).execute(&event) {
+                Ok(QueryValue::Value(Value::Integer(v))) => assert!((-3..=3).contains(&v)),
+                v => panic!("unexpected result: {:?}", v),
+            }
+        }
+
+        assert_eq!(
+            random_int(7, 7).execute(&event),
+            Ok(Value::Integer(7).into())
+        );
+        assert_eq!(
+            random_int(1, 0).execute(&event),
+            Err("min must not be greater than max, got 1 and 0".to_string())
+        );
+    }
+
+    #[test]
+    fn check_random_int_seeded() {
+        let event = Event::from("");
+        let query = random_int(0, 1_000_000);
+        let draw = || {
+            context::scoped_new(&ExecutionContext::new().with_seed(42), || {
+                (0..10)
+                    .map(|_| query.execute(&event))
+                    .collect::<Result<Vec<_>>>()
+            })
+        };
+
+        let first = draw().unwrap();
+        assert_eq!(first, draw().unwrap());
+        assert!(first.iter().any(|v| *v != first[0]));
+    }
+}
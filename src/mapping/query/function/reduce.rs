@@ -0,0 +1,170 @@
+use super::prelude::*;
+use crate::mapping::query::bound::Bound;
+
+/// Folds the elements of an array into a single value, from left to right.
+///
+/// The reducer is called with the value accumulated so far, starting with the
+/// seed, and the next element, and returns the new accumulated value. The
+/// reducer reads them as its first two arguments, bound to `accumulator` and
+/// `element`, so any other arguments of the reducer can still refer to fields
+/// of the event. The seed is returned as is for an empty array, and the first
+/// error returned by the reducer fails the whole reduction.
+#[derive(Debug)]
+pub(in crate::mapping) struct ReduceFn {
+    value: Box<dyn Function>,
+    accumulator: Bound,
+    element: Bound,
+    reducer: Box<dyn Function>,
+    seed: Box<dyn Function>,
+}
+
+impl ReduceFn {
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        accumulator: Bound,
+        element: Bound,
+        reducer: Box<dyn Function>,
+        seed: Box<dyn Function>,
+    ) -> Self {
+        Self {
+            value,
+            accumulator,
+            element,
+            reducer,
+            seed,
+        }
+    }
+}
+
+impl Function for ReduceFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let array = match self.value.execute(ctx)? {
+            QueryValue::Value(Value::Array(array)) => array,
+            v => {
+                return Err(format!(
+                    "unable to reduce {} value, expected an array",
+                    v.kind()
+                ))
+            }
+        };
+
+        let mut accumulator = match self.seed.execute(ctx)? {
+            QueryValue::Value(value) => value,
+            v => return Err(format!("unable to reduce from a {} seed", v.kind())),
+        };
+
+        for (i, element) in array.into_iter().enumerate() {
+            let result = self.accumulator.bind(accumulator, || {
+                self.element.bind(element, || self.reducer.execute(ctx))
+            });
+
+            accumulator = match result {
+                Ok(QueryValue::Value(value)) => value,
+                Ok(v) => {
+                    return Err(format!(
+                        "reducer returned non-value of type {} at index {}",
+                        v.kind(),
+                        i
+                    ))
+                }
+                Err(err) => return Err(format!("unable to reduce element {}: {}", i, err)),
+            };
+        }
+
+        Ok(accumulator.into())
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} reduced with {} starting from {}",
+            self.value.describe(),
+            self.reducer.describe(),
+            self.seed.describe()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::{AddFn, StripPrefixFn};
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    fn path(field: &str) -> Box<dyn Function> {
+        Box::new(Path::from(vec![vec![field]]))
+    }
+
+    #[test]
+    fn check_reduce() {
+        let (accumulator, element) = (Bound::new("accumulator"), Bound::new("element"));
+        let reduce = |seed: Box<dyn Function>| {
+            let add = AddFn::new(Box::new(accumulator), Box::new(element));
+            ReduceFn::new(path("foo"), accumulator, element, Box::new(add), seed)
+        };
+
+        let cases = vec![
+            (
+                json!([1, 2, 3, 4]),
+                Ok(Value::from(10)),
+                reduce(Box::new(Literal::from(Value::from(0)))),
+            ),
+            (
+                json!([]),
+                Ok(Value::from(0)),
+                reduce(Box::new(Literal::from(Value::from(0)))),
+            ),
+            (json!([1, 2, 3]), Ok(Value::from(16)), reduce(path("bar"))),
+            (
+                json!(["a", "b", "c"]),
+                Ok(Value::from("abc")),
+                reduce(Box::new(Literal::from(Value::from("")))),
+            ),
+            (
+                json!([1, true]),
+                Err(
+                    "unable to reduce element 1: unable to add right-hand field type Boolean(true)"
+                        .to_string(),
+                ),
+                reduce(Box::new(Literal::from(Value::from(0)))),
+            ),
+            (
+                json!(1),
+                Err("unable to reduce integer value, expected an array".to_string()),
+                reduce(Box::new(Literal::from(Value::from(0)))),
+            ),
+        ];
+
+        for (value, exp, query) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(value));
+            event.as_mut_log().insert("bar", Value::from(10));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+
+    #[test]
+    fn custom_reducer() {
+        // Strips each of the prefixes in turn, so the accumulator is the
+        // string being stripped and the elements are the prefixes.
+        let (accumulator, element) = (Bound::new("accumulator"), Bound::new("element"));
+        let query = ReduceFn::new(
+            path("prefixes"),
+            accumulator,
+            element,
+            Box::new(StripPrefixFn::new(Box::new(accumulator), Box::new(element))),
+            path("path"),
+        );
+
+        let mut event = Event::from("");
+        event
+            .as_mut_log()
+            .insert("prefixes", Value::from(json!(["/api", "/v1"])));
+        event
+            .as_mut_log()
+            .insert("path", Value::from("/api/v1/users"));
+
+        assert_eq!(query.execute(&event), Ok(Value::from("/users").into()));
+    }
+}
@@ -0,0 +1,244 @@
+use super::prelude::*;
+use regex::bytes::Regex;
+
+/// Replaces every occurrence of a pattern in a string. The pattern is matched
+/// literally, unless regex mode is enabled, in which case it must be given as
+/// a literal so that it can be compiled once when the mapping is parsed.
+#[derive(Debug)]
+pub(in crate::mapping) struct ReplaceFn {
+    value: Box<dyn Function>,
+    pattern: Pattern,
+    replacement: Box<dyn Function>,
+}
+
+#[derive(Debug)]
+enum Pattern {
+    Literal(Box<dyn Function>),
+    Regex(Regex),
+}
+
+impl ReplaceFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        pattern: Box<dyn Function>,
+        replacement: Box<dyn Function>,
+        regex: Option<Box<dyn Function>>,
+    ) -> Result<Self> {
+        let pattern = compile(pattern, regex)?;
+
+        Ok(Self {
+            value,
+            pattern,
+            replacement,
+        })
+    }
+}
+
+fn compile(pattern: Box<dyn Function>, regex: Option<Box<dyn Function>>) -> Result<Pattern> {
+    let regex = match regex {
+        Some(query) => match literal(query, "regex")? {
+            Value::Boolean(regex) => regex,
+            v => unexpected_type!(v),
+        },
+        None => false,
+    };
+
+    if !regex {
+        return Ok(Pattern::Literal(pattern));
+    }
+
+    let pattern = match literal(pattern, "pattern")? {
+        Value::Bytes(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        v => unexpected_type!(v),
+    };
+
+    Regex::new(&pattern)
+        .map(Pattern::Regex)
+        .map_err(|err| format!("invalid regex pattern {:?}: {}", pattern, err))
+}
+
+/// Resolves an argument that must not depend on the event.
+fn literal(query: Box<dyn Function>, keyword: &str) -> Result<Value> {
+    if !query.is_constant() {
+        return Err(format!(
+            "parameter {} passed to replace must be a literal",
+            keyword
+        ));
+    }
+
+    match query.execute(&Event::from(""))? {
+        QueryValue::Value(value) => Ok(value),
+        v => unexpected_type!(v),
+    }
+}
+
+impl Function for ReplaceFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let value = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let replacement = required_value!(ctx, self.replacement, Value::Bytes(v) => v);
+
+        let replaced = match &self.pattern {
+            Pattern::Literal(pattern) => {
+                let pattern = required_value!(ctx, pattern, Value::Bytes(v) => v);
+                let value = String::from_utf8_lossy(&value);
+                let pattern = String::from_utf8_lossy(&pattern);
+                let replacement = String::from_utf8_lossy(&replacement);
+                value.replace(pattern.as_ref(), &replacement).into_bytes()
+            }
+            // Capture groups in the replacement, such as `$1`, are expanded.
+            Pattern::Regex(regex) => regex.replace_all(&value, &replacement[..]).into_owned(),
+        };
+
+        Ok(Value::Bytes(replaced.into()).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "pattern",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "replacement",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "regex",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Boolean(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for ReplaceFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let pattern = arguments.required("pattern")?;
+        let replacement = arguments.required("replacement")?;
+        let regex = arguments.optional("regex");
+
+        let pattern = compile(pattern, regex)?;
+
+        Ok(Self {
+            value,
+            pattern,
+            replacement,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_replace() {
+        let cases = vec![
+            ("a\tb\tc", "\t", " ", None, Ok("a b c")),
+            ("foo bar", "baz", "qux", None, Ok("foo bar")),
+            // Patterns are literal unless regex mode is enabled.
+            ("4111-1111", r"\d+", "x", None, Ok("4111-1111")),
+            ("4111-1111", r"\d+", "x", Some(true), Ok("x-x")),
+            (
+                "card 4111-1111-1111-1234",
+                r"\d{4}-\d{4}-\d{4}-(\d{4})",
+                "****-$1",
+                Some(true),
+                Ok("card ****-1234"),
+            ),
+            ("foo bar", r"\d+", "x", Some(true), Ok("foo bar")),
+            (
+                "foo",
+                "(",
+                "x",
+                Some(true),
+                Err("invalid regex pattern \"(\": regex parse error:\n    (\n    ^\nerror: unclosed group"),
+            ),
+        ];
+
+        for (value, pattern, replacement, regex, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(value));
+
+            let query = ReplaceFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                Box::new(Literal::from(Value::from(pattern))),
+                Box::new(Literal::from(Value::from(replacement))),
+                regex.map(|v| Box::new(Literal::from(Value::Boolean(v))) as _),
+            );
+
+            assert_eq!(
+                query.and_then(|query| query.execute(&event)),
+                exp.map(|v| QueryValue::Value(Value::from(v)))
+                    .map_err(|e| e.to_string()),
+                "{}",
+                pattern
+            );
+        }
+    }
+
+    #[test]
+    fn check_replace_non_string() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from(5));
+
+        let query = ReplaceFn::new(
+            Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                ReplaceFn::parameters()[0].clone(),
+            )),
+            Box::new(Literal::from(Value::from("5"))),
+            Box::new(Literal::from(Value::from("6"))),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            query.execute(&event),
+            Err("invalid argument type 'integer' for parameter 'value'".to_string())
+        );
+    }
+
+    #[test]
+    fn check_replace_dynamic_pattern() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from("a-b-c"));
+        event.as_mut_log().insert("pattern", Value::from("-"));
+
+        // A literal pattern can come from the event.
+        let query = ReplaceFn::new(
+            Box::new(Path::from(vec![vec!["foo"]])),
+            Box::new(Path::from(vec![vec!["pattern"]])),
+            Box::new(Literal::from(Value::from("+"))),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(query.execute(&event), Ok(Value::from("a+b+c").into()));
+
+        // A regex pattern is compiled up front, so it can't.
+        assert_eq!(
+            ReplaceFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                Box::new(Path::from(vec![vec!["pattern"]])),
+                Box::new(Literal::from(Value::from("+"))),
+                Some(Box::new(Literal::from(Value::Boolean(true)))),
+            )
+            .err(),
+            Some("parameter pattern passed to replace must be a literal".to_owned())
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use super::prelude::*;
+use crate::mapping::context;
+use rand::Rng;
+
+/// Returns true for a random fraction of the calls, given by the rate, e.g.
+/// `if sample(0.1) { ... }` takes the branch for about one event in ten.
+///
+/// The random number generator of the execution context is used, so a seeded
+/// mapping samples the same events every time.
+#[derive(Debug)]
+pub(in crate::mapping) struct SampleFn {
+    rate: Box<dyn Function>,
+}
+
+impl SampleFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(rate: Box<dyn Function>) -> Self {
+        Self { rate }
+    }
+}
+
+impl Function for SampleFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let rate = required_value!(ctx, self.rate,
+            Value::Float(v) => v,
+            Value::Integer(v) => v as f64,
+        );
+
+        if !(0.0..=1.0).contains(&rate) {
+            return Err(format!("sample rate must be between 0 and 1, got {}", rate));
+        }
+
+        let keep = context::with_rng(|rng| rng.gen_bool(rate));
+
+        Ok(Value::Boolean(keep).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "rate",
+            accepts: |v| {
+                matches!(
+                    v,
+                    QueryValue::Value(Value::Float(_)) | QueryValue::Value(Value::Integer(_))
+                )
+            },
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for SampleFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let rate = arguments.required("rate")?;
+
+        Ok(Self { rate })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::{parser::parse, ExecutionContext};
+
+    fn sample(rate: Value) -> SampleFn {
+        SampleFn::new(Box::new(Literal::from(rate)))
+    }
+
+    #[test]
+    fn check_sample() {
+        let event = Event::from("");
+
+        let cases = vec![
+            (Value::from(0), Ok(Value::from(false))),
+            (Value::from(1), Ok(Value::from(true))),
+            (Value::from(0.0), Ok(Value::from(false))),
+            (Value::from(1.0), Ok(Value::from(true))),
+            (
+                Value::from(1.5),
+                Err("sample rate must be between 0 and 1, got 1.5".to_string()),
+            ),
+            (
+                Value::from(-0.1),
+                Err("sample rate must be between 0 and 1, got -0.1".to_string()),
+            ),
+        ];
+
+        for (rate, exp) in cases {
+            assert_eq!(sample(rate).execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+
+    #[test]
+    fn check_sample_seeded() {
+        let draw = || {
+            let mapping = parse("if sample(0.5) { .kept = true } else { .kept = false }")
+                .unwrap()
+                .with_context(ExecutionContext::new().with_seed(1));
+
+            (0..10)
+                .map(|_| {
+                    let mut event = Event::from("");
+                    mapping.execute(&mut event).unwrap();
+                    match event.as_log().get("kept") {
+                        Some(Value::Boolean(kept)) => *kept,
+                        v => panic!("unexpected result: {:?}", v),
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let expected = vec![
+            false, true, true, true, true, false, true, true, true, false,
+        ];
+        assert_eq!(draw(), expected);
+        assert_eq!(draw(), expected);
+    }
+}
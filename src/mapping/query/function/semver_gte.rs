@@ -0,0 +1,91 @@
+use super::parse_semver::Version;
+use super::prelude::*;
+use std::cmp::Ordering;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct SemverGteFn {
+    value: Box<dyn Function>,
+    other: Box<dyn Function>,
+}
+
+impl SemverGteFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>, other: Box<dyn Function>) -> Self {
+        Self { value, other }
+    }
+}
+
+impl Function for SemverGteFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let value = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let other = required_value!(ctx, self.other, Value::Bytes(v) => v);
+
+        let value = Version::parse(&String::from_utf8_lossy(&value))?;
+        let other = Version::parse(&String::from_utf8_lossy(&other))?;
+
+        Ok(Value::Boolean(value.precedence(&other) != Ordering::Less).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "other",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for SemverGteFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let other = arguments.required("other")?;
+
+        Ok(Self { value, other })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_semver_gte() {
+        let cases = vec![
+            ("1.2.0", "1.2.0", Ok(true)),
+            ("1.10.0", "1.9.3", Ok(true)),
+            ("1.2.0", "1.2.1", Ok(false)),
+            ("1.2.0-rc.1", "1.2.0", Ok(false)),
+            ("1.2.0", "1.2.0-rc.1", Ok(true)),
+            ("1.2.0-rc.10", "1.2.0-rc.2", Ok(true)),
+            ("1.2.0-alpha", "1.2.0-alpha.1", Ok(false)),
+            ("1.2.0-1", "1.2.0-alpha", Ok(false)),
+            ("1.2.0+build.2", "1.2.0+build.1", Ok(true)),
+            (
+                "1.2",
+                "1.2.0",
+                Err("invalid semantic version: 1.2".to_string()),
+            ),
+        ];
+
+        for (value, other, exp) in cases {
+            let query = SemverGteFn::new(
+                Box::new(Literal::from(Value::from(value))),
+                Box::new(Literal::from(Value::from(other))),
+            );
+
+            assert_eq!(
+                query.execute(&Event::from("")),
+                exp.map(|v| QueryValue::Value(Value::Boolean(v)))
+            );
+        }
+    }
+}
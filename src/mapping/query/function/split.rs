@@ -105,6 +105,7 @@ impl TryFrom<ArgumentList> for SplitFn {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mapping::query::function::Argument;
     use crate::mapping::query::path::Path;
     use crate::mapping::query::regex::Regex;
 
@@ -187,6 +188,48 @@ mod tests {
                     None,
                 ),
             ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from("a,b,c"));
+                    event
+                },
+                Ok(Value::from(vec![Value::from("a"), Value::from("b,c")])),
+                SplitFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from(","))),
+                    Some(Box::new(Literal::from(Value::from(2)))),
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from("a,b,c"));
+                    event
+                },
+                Ok(Value::from(vec![Value::from("a,b,c")])),
+                SplitFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from(";"))),
+                    None,
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from(5));
+                    event
+                },
+                Err("invalid argument type 'integer' for parameter 'value'".to_string()),
+                SplitFn::new(
+                    Box::new(Argument::new(
+                        Box::new(Path::from(vec![vec!["foo"]])),
+                        SplitFn::parameters()[0].clone(),
+                    )),
+                    Box::new(Literal::from(Value::from(","))),
+                    None,
+                ),
+            ),
         ];
 
         for (input_event, exp, query) in cases {
@@ -0,0 +1,209 @@
+use super::prelude::*;
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+
+/// A calendar period a timestamp can be truncated to, in UTC.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Period {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl Period {
+    pub(super) fn parse(unit: &[u8]) -> Result<Self> {
+        match unit {
+            b"day" => Ok(Self::Day),
+            b"week" => Ok(Self::Week),
+            b"month" => Ok(Self::Month),
+            b"year" => Ok(Self::Year),
+            _ => Err(format!(
+                "unknown period unit '{}', expected one of day, week, month or year",
+                String::from_utf8_lossy(unit)
+            )),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Year => "year",
+        }
+    }
+
+    /// Returns the first instant of the period containing `timestamp`. Weeks
+    /// start on Monday.
+    pub(super) fn start(self, timestamp: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        let (year, month, day) = (timestamp.year(), timestamp.month(), timestamp.day());
+
+        let start = match self {
+            Self::Day => midnight(year, month, day),
+            Self::Week => {
+                let days = timestamp.weekday().num_days_from_monday();
+                Self::Day
+                    .start(timestamp)
+                    .ok()
+                    .and_then(|day| day.checked_sub_signed(Duration::days(days.into())))
+            }
+            Self::Month => midnight(year, month, 1),
+            Self::Year => midnight(year, 1, 1),
+        };
+
+        start.ok_or_else(|| self.out_of_range("start", timestamp))
+    }
+
+    /// Returns the last instant of the period containing `timestamp`, at
+    /// nanosecond precision.
+    pub(super) fn end(self, timestamp: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        let start = self.start(timestamp)?;
+
+        let next = match self {
+            Self::Day => start.checked_add_signed(Duration::days(1)),
+            Self::Week => start.checked_add_signed(Duration::days(7)),
+            Self::Month if start.month() == 12 => midnight(start.year() + 1, 1, 1),
+            Self::Month => midnight(start.year(), start.month() + 1, 1),
+            Self::Year => midnight(start.year() + 1, 1, 1),
+        };
+
+        next.and_then(|next| next.checked_sub_signed(Duration::nanoseconds(1)))
+            .ok_or_else(|| self.out_of_range("end", timestamp))
+    }
+
+    fn out_of_range(self, bound: &str, timestamp: DateTime<Utc>) -> String {
+        format!(
+            "the {} of the {} of {} is out of range",
+            bound,
+            self.as_str(),
+            timestamp.to_rfc3339()
+        )
+    }
+}
+
+/// Returns the first instant of a date, if the date can be represented.
+fn midnight(year: i32, month: u32, day: u32) -> Option<DateTime<Utc>> {
+    Utc.ymd_opt(year, month, day)
+        .single()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub(in crate::mapping) struct StartOfFn {
+    value: Box<dyn Function>,
+    unit: Box<dyn Function>,
+}
+
+impl StartOfFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>, unit: Box<dyn Function>) -> Self {
+        Self { value, unit }
+    }
+}
+
+impl Function for StartOfFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let timestamp = required_value!(ctx, self.value, Value::Timestamp(v) => v);
+        let unit = required_value!(ctx, self.unit, Value::Bytes(v) => v);
+
+        Ok(Value::Timestamp(Period::parse(&unit)?.start(timestamp)?).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Timestamp(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "unit",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for StartOfFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let unit = arguments.required("unit")?;
+
+        Ok(Self { value, unit })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_start_of() {
+        let cases = vec![
+            (
+                "day",
+                Ok(Value::from(Utc.ymd(2020, 2, 12).and_hms(0, 0, 0))),
+            ),
+            (
+                // 2020-02-12 is a Wednesday.
+                "week",
+                Ok(Value::from(Utc.ymd(2020, 2, 10).and_hms(0, 0, 0))),
+            ),
+            (
+                "month",
+                Ok(Value::from(Utc.ymd(2020, 2, 1).and_hms(0, 0, 0))),
+            ),
+            (
+                "year",
+                Ok(Value::from(Utc.ymd(2020, 1, 1).and_hms(0, 0, 0))),
+            ),
+            (
+                "hour",
+                Err(
+                    "unknown period unit 'hour', expected one of day, week, month or year"
+                        .to_string(),
+                ),
+            ),
+        ];
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert(
+            "foo",
+            Value::from(Utc.ymd(2020, 2, 12).and_hms_nano(13, 45, 10, 500)),
+        );
+
+        for (unit, exp) in cases {
+            let query = StartOfFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                Box::new(Literal::from(Value::from(unit))),
+            );
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+
+        // The week of the first day chrono can represent starts before it.
+        let timestamp = chrono::MIN_DATE.and_hms(0, 0, 0);
+        assert_ne!(timestamp.weekday(), chrono::Weekday::Mon);
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from(timestamp));
+        let query = StartOfFn::new(
+            Box::new(Path::from(vec![vec!["foo"]])),
+            Box::new(Literal::from(Value::from("week"))),
+        );
+
+        assert_eq!(
+            query.execute(&event),
+            Err(format!(
+                "the start of the week of {} is out of range",
+                timestamp.to_rfc3339()
+            ))
+        );
+    }
+}
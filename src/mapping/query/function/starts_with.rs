@@ -0,0 +1,134 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct StartsWithFn {
+    value: Box<dyn Function>,
+    prefix: Box<dyn Function>,
+    case_insensitive: Option<Box<dyn Function>>,
+}
+
+impl StartsWithFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        prefix: Box<dyn Function>,
+        case_insensitive: Option<Box<dyn Function>>,
+    ) -> Self {
+        Self {
+            value,
+            prefix,
+            case_insensitive,
+        }
+    }
+}
+
+fn lowercase(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).to_lowercase()
+}
+
+impl Function for StartsWithFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let pattern = required_value!(ctx, self.prefix, Value::Bytes(v) => v);
+        let case_insensitive =
+            optional_value!(ctx, self.case_insensitive, Value::Boolean(v) => v).unwrap_or(false);
+
+        let result = if case_insensitive {
+            lowercase(&bytes).starts_with(&lowercase(&pattern))
+        } else {
+            bytes.starts_with(&pattern)
+        };
+
+        Ok(Value::Boolean(result).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "prefix",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "case_insensitive",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Boolean(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for StartsWithFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let prefix = arguments.required("prefix")?;
+        let case_insensitive = arguments.optional("case_insensitive");
+
+        Ok(Self {
+            value,
+            prefix,
+            case_insensitive,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_starts_with() {
+        let cases = vec![
+            ("GET /index.html", "GET ", None, true),
+            ("GET /index.html", "POST ", None, false),
+            ("GET /index.html", "get ", Some(false), false),
+            ("GET /index.html", "get ", Some(true), true),
+            ("GET", "GET /", None, false),
+        ];
+
+        for (value, prefix, case_insensitive, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(value));
+
+            let query = StartsWithFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                Box::new(Literal::from(Value::from(prefix))),
+                case_insensitive.map(|v| Box::new(Literal::from(Value::Boolean(v))) as _),
+            );
+
+            assert_eq!(
+                query.execute(&event),
+                Ok(QueryValue::Value(Value::Boolean(exp)))
+            );
+        }
+    }
+
+    #[test]
+    fn check_starts_with_non_string() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from(5));
+
+        let query = StartsWithFn::new(
+            Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                StartsWithFn::parameters()[0].clone(),
+            )),
+            Box::new(Literal::from(Value::from("5"))),
+            None,
+        );
+
+        assert_eq!(
+            query.execute(&event),
+            Err("invalid argument type 'integer' for parameter 'value'".to_string())
+        );
+    }
+}
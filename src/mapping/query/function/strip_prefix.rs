@@ -0,0 +1,105 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct StripPrefixFn {
+    value: Box<dyn Function>,
+    prefix: Box<dyn Function>,
+}
+
+impl StripPrefixFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>, prefix: Box<dyn Function>) -> Self {
+        Self { value, prefix }
+    }
+}
+
+impl Function for StripPrefixFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let affix = required_value!(ctx, self.prefix, Value::Bytes(v) => v);
+
+        if bytes.starts_with(&affix) {
+            Ok(Value::Bytes(bytes.slice(affix.len()..)).into())
+        } else {
+            Ok(Value::Bytes(bytes).into())
+        }
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "prefix",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for StripPrefixFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let prefix = arguments.required("prefix")?;
+
+        Ok(Self { value, prefix })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_strip_prefix() {
+        let cases = vec![
+            ("/var/log/syslog", "/var/log/", "syslog"),
+            ("/tmp/syslog", "/var/log/", "/tmp/syslog"),
+            ("syslog", "", "syslog"),
+            ("", "/var/log/", ""),
+            ("/var/log/", "/var/log/", ""),
+        ];
+
+        for (value, prefix, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(value));
+
+            let query = StripPrefixFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                Box::new(Literal::from(Value::from(prefix))),
+            );
+
+            assert_eq!(
+                query.execute(&event),
+                Ok(QueryValue::Value(Value::from(exp)))
+            );
+        }
+    }
+
+    #[test]
+    fn check_strip_prefix_non_string() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from(5));
+
+        let query = StripPrefixFn::new(
+            Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                StripPrefixFn::parameters()[0].clone(),
+            )),
+            Box::new(Literal::from(Value::from("5"))),
+        );
+
+        assert_eq!(
+            query.execute(&event),
+            Err("invalid argument type 'integer' for parameter 'value'".to_string())
+        );
+    }
+}
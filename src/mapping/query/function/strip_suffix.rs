@@ -0,0 +1,105 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct StripSuffixFn {
+    value: Box<dyn Function>,
+    suffix: Box<dyn Function>,
+}
+
+impl StripSuffixFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>, suffix: Box<dyn Function>) -> Self {
+        Self { value, suffix }
+    }
+}
+
+impl Function for StripSuffixFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let affix = required_value!(ctx, self.suffix, Value::Bytes(v) => v);
+
+        if bytes.ends_with(&affix) {
+            Ok(Value::Bytes(bytes.slice(..bytes.len() - affix.len())).into())
+        } else {
+            Ok(Value::Bytes(bytes).into())
+        }
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "suffix",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for StripSuffixFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let suffix = arguments.required("suffix")?;
+
+        Ok(Self { value, suffix })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_strip_suffix() {
+        let cases = vec![
+            ("syslog.log", ".log", "syslog"),
+            ("syslog.txt", ".log", "syslog.txt"),
+            ("syslog", "", "syslog"),
+            ("", ".log", ""),
+            (".log", ".log", ""),
+        ];
+
+        for (value, suffix, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(value));
+
+            let query = StripSuffixFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                Box::new(Literal::from(Value::from(suffix))),
+            );
+
+            assert_eq!(
+                query.execute(&event),
+                Ok(QueryValue::Value(Value::from(exp)))
+            );
+        }
+    }
+
+    #[test]
+    fn check_strip_suffix_non_string() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from(5));
+
+        let query = StripSuffixFn::new(
+            Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                StripSuffixFn::parameters()[0].clone(),
+            )),
+            Box::new(Literal::from(Value::from("5"))),
+        );
+
+        assert_eq!(
+            query.execute(&event),
+            Err("invalid argument type 'integer' for parameter 'value'".to_string())
+        );
+    }
+}
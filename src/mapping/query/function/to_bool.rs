@@ -0,0 +1,92 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ToBoolFn {
+    value: Box<dyn Function>,
+}
+
+impl ToBoolFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for ToBoolFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let value = required_value!(ctx, self.value,
+            Value::Boolean(v) => v,
+            Value::Bytes(v) => match v.as_ref() {
+                v if v.eq_ignore_ascii_case(b"true") => true,
+                v if v.eq_ignore_ascii_case(b"false") => false,
+                v => {
+                    return Err(format!(
+                        "unable to convert {:?} to a boolean",
+                        String::from_utf8_lossy(v)
+                    ))
+                }
+            },
+        );
+
+        Ok(Value::Boolean(value).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| {
+                matches!(
+                    v,
+                    QueryValue::Value(Value::Boolean(_)) | QueryValue::Value(Value::Bytes(_))
+                )
+            },
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ToBoolFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_to_bool() {
+        let cases = vec![
+            (Value::from("true"), Ok(Value::Boolean(true))),
+            (Value::from("TRUE"), Ok(Value::Boolean(true))),
+            (Value::from("False"), Ok(Value::Boolean(false))),
+            (Value::Boolean(false), Ok(Value::Boolean(false))),
+            (
+                Value::from("yes"),
+                Err("unable to convert \"yes\" to a boolean".to_string()),
+            ),
+            (
+                Value::Integer(1),
+                Err("invalid argument type 'integer' for parameter 'value'".to_string()),
+            ),
+        ];
+
+        for (input, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", input);
+            let query = ToBoolFn::new(Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                ToBoolFn::parameters()[0].clone(),
+            )));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ToFloatFn {
+    value: Box<dyn Function>,
+}
+
+impl ToFloatFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for ToFloatFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let value = required_value!(ctx, self.value,
+            Value::Float(v) => v,
+            Value::Integer(v) => v as f64,
+            Value::Bytes(v) => {
+                let s = String::from_utf8_lossy(&v);
+                s.trim()
+                    .parse()
+                    .map_err(|_| format!("unable to convert {:?} to a float", s))?
+            },
+        );
+
+        Ok(Value::Float(value).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| {
+                matches!(
+                    v,
+                    QueryValue::Value(Value::Float(_))
+                        | QueryValue::Value(Value::Integer(_))
+                        | QueryValue::Value(Value::Bytes(_))
+                )
+            },
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ToFloatFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_to_float() {
+        let cases = vec![
+            (Value::from("3.9"), Ok(Value::Float(3.9))),
+            (Value::from("-1e3"), Ok(Value::Float(-1000.0))),
+            (Value::from("404"), Ok(Value::Float(404.0))),
+            (Value::Integer(2), Ok(Value::Float(2.0))),
+            (Value::Float(0.5), Ok(Value::Float(0.5))),
+            (
+                Value::from("1.2.3"),
+                Err("unable to convert \"1.2.3\" to a float".to_string()),
+            ),
+            (
+                Value::Boolean(true),
+                Err("invalid argument type 'boolean' for parameter 'value'".to_string()),
+            ),
+        ];
+
+        for (input, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", input);
+            let query = ToFloatFn::new(Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                ToFloatFn::parameters()[0].clone(),
+            )));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
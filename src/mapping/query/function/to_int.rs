@@ -0,0 +1,91 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct ToIntFn {
+    value: Box<dyn Function>,
+}
+
+impl ToIntFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for ToIntFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let value = required_value!(ctx, self.value,
+            Value::Integer(v) => v,
+            Value::Bytes(v) => {
+                let s = String::from_utf8_lossy(&v);
+                s.trim()
+                    .parse()
+                    .map_err(|_| format!("unable to convert {:?} to an integer", s))?
+            },
+        );
+
+        Ok(Value::Integer(value).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| {
+                matches!(
+                    v,
+                    QueryValue::Value(Value::Integer(_)) | QueryValue::Value(Value::Bytes(_))
+                )
+            },
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for ToIntFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_to_int() {
+        let cases = vec![
+            (Value::from("404"), Ok(Value::Integer(404))),
+            (Value::from(" -12 "), Ok(Value::Integer(-12))),
+            (Value::Integer(7), Ok(Value::Integer(7))),
+            (
+                Value::from("3.9"),
+                Err("unable to convert \"3.9\" to an integer".to_string()),
+            ),
+            (
+                Value::from("abc"),
+                Err("unable to convert \"abc\" to an integer".to_string()),
+            ),
+            (
+                Value::Float(3.9),
+                Err("invalid argument type 'float' for parameter 'value'".to_string()),
+            ),
+        ];
+
+        for (input, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", input);
+            let query = ToIntFn::new(Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                ToIntFn::parameters()[0].clone(),
+            )));
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
+}
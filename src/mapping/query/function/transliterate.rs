@@ -0,0 +1,83 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct TransliterateFn {
+    value: Box<dyn Function>,
+}
+
+impl TransliterateFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for TransliterateFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+
+        Ok(Value::from(deunicode::deunicode(&String::from_utf8_lossy(&bytes))).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for TransliterateFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_transliterate() {
+        let cases = vec![
+            ("café", "cafe"),
+            ("Ærøskøbing Straße", "AEroskobing Strasse"),
+            ("Crème Brûlée à la Façon", "Creme Brulee a la Facon"),
+            ("Москва", "Moskva"),
+            ("already ASCII: 1 + 1 = 2", "already ASCII: 1 + 1 = 2"),
+            ("", ""),
+        ];
+
+        for (input, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(input));
+            let query = TransliterateFn::new(Box::new(Path::from(vec![vec!["foo"]])));
+
+            assert_eq!(
+                query.execute(&event),
+                Ok(QueryValue::Value(Value::from(exp))),
+                "{}",
+                input
+            );
+        }
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from(42));
+        let query = TransliterateFn::new(Box::new(Argument::new(
+            Box::new(Path::from(vec![vec!["foo"]])),
+            TransliterateFn::parameters()[0].clone(),
+        )));
+
+        assert_eq!(
+            query.execute(&event),
+            Err("invalid argument type 'integer' for parameter 'value'".to_string())
+        );
+    }
+}
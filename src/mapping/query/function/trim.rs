@@ -0,0 +1,77 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct TrimFn {
+    value: Box<dyn Function>,
+}
+
+impl TrimFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>) -> Self {
+        Self { value }
+    }
+}
+
+impl Function for TrimFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+
+        Ok(Value::from(String::from_utf8_lossy(&bytes).trim().to_owned()).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+            required: true,
+        }]
+    }
+}
+
+impl TryFrom<ArgumentList> for TrimFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+
+        Ok(Self { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_trim() {
+        let cases = vec![
+            ("  foo bar \t\n", "foo bar"),
+            ("\u{3000}café\u{a0}", "café"),
+        ];
+
+        for (input, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(input));
+            let query = TrimFn::new(Box::new(Path::from(vec![vec!["foo"]])));
+
+            assert_eq!(
+                query.execute(&event),
+                Ok(QueryValue::Value(Value::from(exp)))
+            );
+        }
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from(42));
+        let query = TrimFn::new(Box::new(Argument::new(
+            Box::new(Path::from(vec![vec!["foo"]])),
+            TrimFn::parameters()[0].clone(),
+        )));
+
+        assert_eq!(
+            query.execute(&event),
+            Err("invalid argument type 'integer' for parameter 'value'".to_string())
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct TruncateWordsFn {
+    value: Box<dyn Function>,
+    limit: Box<dyn Function>,
+    ellipsis: Option<Box<dyn Function>>,
+}
+
+impl TruncateWordsFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        limit: Box<dyn Function>,
+        ellipsis: Option<Box<dyn Function>>,
+    ) -> Self {
+        Self {
+            value,
+            limit,
+            ellipsis,
+        }
+    }
+}
+
+impl Function for TruncateWordsFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let bytes = required_value!(ctx, self.value, Value::Bytes(v) => v);
+        let limit = required_value!(ctx, self.limit, Value::Integer(v) => v);
+        let ellipsis = optional_value!(ctx, self.ellipsis, Value::Bytes(v) => v);
+
+        if limit < 0 {
+            return Err(format!("word limit must not be negative, got {}", limit));
+        }
+
+        let value = String::from_utf8_lossy(&bytes);
+        let mut words = value.split_whitespace();
+        let truncated = words.by_ref().take(limit as usize).collect::<Vec<_>>();
+
+        // Strings that are short enough are returned untouched, including
+        // their whitespace.
+        if words.next().is_none() {
+            return Ok(Value::Bytes(bytes).into());
+        }
+
+        let mut truncated = truncated.join(" ");
+        if let Some(ellipsis) = ellipsis {
+            truncated.push_str(&String::from_utf8_lossy(&ellipsis));
+        }
+
+        Ok(Value::from(truncated).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "limit",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Integer(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "ellipsis",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Bytes(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for TruncateWordsFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let limit = arguments.required("limit")?;
+        let ellipsis = arguments.optional("ellipsis");
+
+        Ok(Self {
+            value,
+            limit,
+            ellipsis,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+
+    #[test]
+    fn check_truncate_words() {
+        let cases = vec![
+            (
+                "a short  description",
+                3,
+                Some("..."),
+                Ok(Value::from("a short  description")),
+            ),
+            (
+                "a much longer description that goes on",
+                3,
+                Some("..."),
+                Ok(Value::from("a much longer...")),
+            ),
+            (
+                "  a much\tlonger\ndescription",
+                2,
+                None,
+                Ok(Value::from("a much")),
+            ),
+            ("foo bar", 0, Some("..."), Ok(Value::from("..."))),
+            (
+                "foo bar",
+                -1,
+                None,
+                Err("word limit must not be negative, got -1".to_string()),
+            ),
+        ];
+
+        for (input, limit, ellipsis, exp) in cases {
+            let mut event = Event::from("");
+            event.as_mut_log().insert("foo", Value::from(input));
+            let query = TruncateWordsFn::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                Box::new(Literal::from(Value::from(limit))),
+                ellipsis.map(|v| Box::new(Literal::from(Value::from(v))) as _),
+            );
+
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+
+        let mut event = Event::from("");
+        event.as_mut_log().insert("foo", Value::from(42));
+        let query = TruncateWordsFn::new(
+            Box::new(Argument::new(
+                Box::new(Path::from(vec![vec!["foo"]])),
+                TruncateWordsFn::parameters()[0].clone(),
+            )),
+            Box::new(Literal::from(Value::from(3))),
+            None,
+        );
+
+        assert_eq!(
+            query.execute(&event),
+            Err("invalid argument type 'integer' for parameter 'value'".to_string())
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct WeekdayNameFn {
+    value: Box<dyn Function>,
+    abbreviated: Option<Box<dyn Function>>,
+}
+
+impl WeekdayNameFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(
+        value: Box<dyn Function>,
+        abbreviated: Option<Box<dyn Function>>,
+    ) -> Self {
+        Self { value, abbreviated }
+    }
+}
+
+impl Function for WeekdayNameFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let timestamp = required_value!(ctx, self.value, Value::Timestamp(v) => v);
+        let abbreviated =
+            optional_value!(ctx, self.abbreviated, Value::Boolean(v) => v).unwrap_or(false);
+
+        let format = if abbreviated { "%a" } else { "%A" };
+
+        Ok(Value::from(timestamp.format(format).to_string()).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Timestamp(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "abbreviated",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Boolean(_))),
+                required: false,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for WeekdayNameFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let abbreviated = arguments.optional("abbreviated");
+
+        Ok(Self { value, abbreviated })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::{function::Argument, path::Path};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn check_weekday_name() {
+        let cases = vec![
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from(Utc.ymd(2021, 1, 4).and_hms(12, 0, 0)));
+                    event
+                },
+                Ok(Value::from("Monday")),
+                WeekdayNameFn::new(Box::new(Path::from(vec![vec!["foo"]])), None),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from(Utc.ymd(2021, 1, 4).and_hms(12, 0, 0)));
+                    event
+                },
+                Ok(Value::from("Mon")),
+                WeekdayNameFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Some(Box::new(Literal::from(Value::Boolean(true)))),
+                ),
+            ),
+            (
+                Event::from(""),
+                Err("invalid argument type 'string' for parameter 'value'".to_string()),
+                WeekdayNameFn::new(
+                    Box::new(Argument::new(
+                        Box::new(Literal::from(Value::from("2021-01-04"))),
+                        WeekdayNameFn::parameters()[0].clone(),
+                    )),
+                    None,
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub(in crate::mapping) struct WindowsFn {
+    value: Box<dyn Function>,
+    size: Box<dyn Function>,
+}
+
+impl WindowsFn {
+    #[cfg(test)]
+    pub(in crate::mapping) fn new(value: Box<dyn Function>, size: Box<dyn Function>) -> Self {
+        Self { value, size }
+    }
+}
+
+impl Function for WindowsFn {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        let array = required_value!(ctx, self.value, Value::Array(v) => v);
+        let size = required_value!(ctx, self.size, Value::Integer(v) => v);
+
+        if size <= 0 {
+            return Err(format!("window size must be positive, got {}", size));
+        }
+
+        // An array shorter than the window has no windows at all.
+        let windows = array
+            .windows(size as usize)
+            .map(|window| Value::Array(window.to_vec()))
+            .collect::<Vec<_>>();
+
+        Ok(Value::Array(windows).into())
+    }
+
+    fn parameters() -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Array(_))),
+                required: true,
+            },
+            Parameter {
+                keyword: "size",
+                accepts: |v| matches!(v, QueryValue::Value(Value::Integer(_))),
+                required: true,
+            },
+        ]
+    }
+}
+
+impl TryFrom<ArgumentList> for WindowsFn {
+    type Error = String;
+
+    fn try_from(mut arguments: ArgumentList) -> Result<Self> {
+        let value = arguments.required("value")?;
+        let size = arguments.required("size")?;
+
+        Ok(Self { value, size })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::query::function::Argument;
+    use crate::mapping::query::path::Path;
+    use serde_json::json;
+
+    #[test]
+    fn check_windows() {
+        let cases = vec![
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from(json!([1, 2, 3, 4])));
+                    event
+                },
+                Ok(Value::from(json!([[1, 2], [2, 3], [3, 4]]))),
+                WindowsFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from(2))),
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event
+                        .as_mut_log()
+                        .insert("foo", Value::from(json!([1, "two", 3])));
+                    event
+                },
+                Ok(Value::from(json!([[1, "two", 3]]))),
+                WindowsFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from(3))),
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from(json!([1, 2])));
+                    event
+                },
+                Ok(Value::from(json!([]))),
+                WindowsFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from(3))),
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from(json!([1])));
+                    event
+                },
+                Err("window size must be positive, got -1".to_string()),
+                WindowsFn::new(
+                    Box::new(Path::from(vec![vec!["foo"]])),
+                    Box::new(Literal::from(Value::from(-1))),
+                ),
+            ),
+            (
+                {
+                    let mut event = Event::from("");
+                    event.as_mut_log().insert("foo", Value::from("bar"));
+                    event
+                },
+                Err("invalid argument type 'string' for parameter 'value'".to_string()),
+                WindowsFn::new(
+                    Box::new(Argument::new(
+                        Box::new(Path::from(vec![vec!["foo"]])),
+                        WindowsFn::parameters()[0].clone(),
+                    )),
+                    Box::new(Literal::from(Value::from(2))),
+                ),
+            ),
+        ];
+
+        for (input_event, exp, query) in cases {
+            assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
+        }
+    }
+}
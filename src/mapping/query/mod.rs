@@ -2,8 +2,11 @@ use crate::{
     event::{Event, Value},
     mapping::Result,
 };
+use std::collections::BTreeMap;
 
 pub mod arithmetic;
+pub mod bound;
+pub mod coalesce;
 pub mod function;
 pub mod path;
 pub mod query_value;
@@ -11,10 +14,45 @@ pub mod regex;
 
 use query_value::QueryValue;
 
-pub(in crate::mapping) trait Function: Send + core::fmt::Debug {
+pub(in crate::mapping) trait Function: Send + Sync + core::fmt::Debug {
     /// Run the function to produce a [`Value`].
     fn execute(&self, context: &Event) -> Result<QueryValue>;
 
+    /// Describe the query in prose, for use in [`Mapping::explain`].
+    ///
+    /// [`Mapping::explain`]: crate::mapping::Mapping::explain
+    fn describe(&self) -> String {
+        "a query".to_owned()
+    }
+
+    /// Runs the query like `execute`, except that a path missing from the
+    /// event resolves to `None` rather than an error, for functions such as
+    /// `exists` that treat a missing field as a value of its own.
+    fn resolve(&self, context: &Event) -> Result<Option<QueryValue>> {
+        self.execute(context).map(Some)
+    }
+
+    /// Whether the query resolves to the same value for every event, e.g. a
+    /// literal.
+    fn is_constant(&self) -> bool {
+        false
+    }
+
+    /// Checks the query for errors that can be detected before it is run
+    /// against any event, see [`Mapping::validate`].
+    ///
+    /// [`Mapping::validate`]: crate::mapping::Mapping::validate
+    fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// The path of a field the query moves out of the event, such as with
+    /// `del(.old)`. The assignment of the query removes the field once the
+    /// query has been executed.
+    fn removed_path(&self) -> Option<&str> {
+        None
+    }
+
     /// Return the static set of parameters this function accepts.
     fn parameters() -> &'static [function::Parameter]
     where
@@ -49,4 +87,110 @@ impl Function for Literal {
     fn execute(&self, _: &Event) -> Result<query_value::QueryValue> {
         Ok(self.value.clone())
     }
+
+    fn is_constant(&self) -> bool {
+        true
+    }
+
+    fn describe(&self) -> String {
+        match &self.value {
+            QueryValue::Value(Value::Bytes(bytes)) => {
+                format!("literal {:?}", String::from_utf8_lossy(bytes))
+            }
+            QueryValue::Value(Value::Null) => "literal null".to_owned(),
+            QueryValue::Value(value) => format!("literal {}", value.to_string_lossy()),
+            QueryValue::Regex(regex) => format!("regex {}", regex),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// A map literal such as `{"foo": .bar, "baz": 10}`. The values of the map are
+/// queries, and are resolved each time the map is executed.
+#[derive(Debug)]
+pub(in crate::mapping) struct Map {
+    fields: Vec<(String, Box<dyn Function>)>,
+}
+
+impl Map {
+    pub(in crate::mapping) fn new(fields: Vec<(String, Box<dyn Function>)>) -> Self {
+        Self { fields }
+    }
+}
+
+impl Function for Map {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        self.fields
+            .iter()
+            .map(|(key, query)| match query.execute(ctx)? {
+                QueryValue::Value(value) => Ok((key.clone(), value)),
+                query => Err(format!(
+                    "map field {} can not be assigned a {}",
+                    key,
+                    query.kind()
+                )),
+            })
+            .collect::<Result<BTreeMap<_, _>>>()
+            .map(|map| Value::Map(map).into())
+    }
+
+    fn is_constant(&self) -> bool {
+        self.fields.iter().all(|(_, query)| query.is_constant())
+    }
+
+    fn describe(&self) -> String {
+        let fields = self
+            .fields
+            .iter()
+            .map(|(key, query)| format!("{:?}: {}", key, query.describe()))
+            .collect::<Vec<_>>();
+
+        format!("map {{{}}}", fields.join(", "))
+    }
+}
+
+/// An array literal such as `["foo", .bar]`. Like the values of a map literal,
+/// the elements are resolved each time the array is executed.
+#[derive(Debug)]
+pub(in crate::mapping) struct Array {
+    elements: Vec<Box<dyn Function>>,
+}
+
+impl Array {
+    pub(in crate::mapping) fn new(elements: Vec<Box<dyn Function>>) -> Self {
+        Self { elements }
+    }
+}
+
+impl Function for Array {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        self.elements
+            .iter()
+            .enumerate()
+            .map(|(i, query)| match query.execute(ctx)? {
+                QueryValue::Value(value) => Ok(value),
+                query => Err(format!(
+                    "array element {} can not be assigned a {}",
+                    i,
+                    query.kind()
+                )),
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(|array| Value::Array(array).into())
+    }
+
+    fn is_constant(&self) -> bool {
+        self.elements.iter().all(|query| query.is_constant())
+    }
+
+    fn describe(&self) -> String {
+        let elements = self
+            .elements
+            .iter()
+            .map(|query| query.describe())
+            .collect::<Vec<_>>();
+
+        format!("array [{}]", elements.join(", "))
+    }
 }
@@ -1,9 +1,69 @@
 use super::{query_value::QueryValue, Function};
 use crate::{
-    event::{util::log::get_value, Event, PathIter},
+    event::{util::log::get_value, Event, PathIter, Value},
     mapping::Result,
 };
 
+/// The value of an array index that is out of bounds.
+static NULL: Value = Value::Null;
+
+/// The result of resolving a single segment of a path.
+enum Lookup<'a> {
+    Found(&'a Value),
+    OutOfBounds,
+}
+
+/// Splits the trailing array indices from a path segment, e.g. `foo[1][-1]`
+/// is split into `foo` and `[1, -1]`.
+fn split_indices(segment: &str) -> (&str, Vec<i64>) {
+    let mut key = segment;
+    let mut indices = Vec::new();
+
+    while key.ends_with(']') {
+        let start = match key.rfind('[') {
+            Some(start) => start,
+            None => break,
+        };
+
+        match key[start + 1..key.len() - 1].parse() {
+            Ok(index) => indices.push(index),
+            Err(_) => break,
+        }
+
+        key = &key[..start];
+    }
+
+    indices.reverse();
+    (key, indices)
+}
+
+/// Resolves array indices against a value. Negative indices count from the
+/// end of the array.
+///
+/// Returns `None` if the value being indexed isn't an array.
+fn index<'a>(mut value: &'a Value, indices: &[i64]) -> Option<Lookup<'a>> {
+    for &index in indices {
+        let array = match value {
+            Value::Array(array) => array,
+            _ => return None,
+        };
+
+        let index = if index < 0 {
+            array.len() as i64 + index
+        } else {
+            index
+        };
+
+        if index < 0 || index as usize >= array.len() {
+            return Some(Lookup::OutOfBounds);
+        }
+
+        value = &array[index as usize];
+    }
+
+    Some(Lookup::Found(value))
+}
+
 #[derive(Debug)]
 pub(in crate::mapping) struct Path {
     // TODO: Switch to String once Event API is cleaned up.
@@ -41,8 +101,17 @@ impl From<Vec<Vec<&str>>> for Path {
     }
 }
 
-impl Function for Path {
-    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+impl Path {
+    /// Returns the value at the path, or `None` if it isn't present in the
+    /// event. As with `execute`, an array index that is out of bounds
+    /// resolves to null.
+    pub(in crate::mapping) fn get<'a>(&self, ctx: &'a Event) -> Option<&'a Value> {
+        self.lookup(ctx).ok()
+    }
+
+    /// Looks up the value at the path. If it isn't present in the event, the
+    /// index of the first segment that is missing is returned instead.
+    fn lookup<'a>(&self, ctx: &'a Event) -> std::result::Result<&'a Value, usize> {
         // Event.as_log returns a LogEvent struct rather than a naked
         // IndexMap<_, Value>, which means specifically for the first item in
         // the path we need to manually call .get.
@@ -50,10 +119,19 @@ impl Function for Path {
         // If we could simply pull either an IndexMap or Value out of a LogEvent
         // then we wouldn't need this duplicate code as we'd jump straight into
         // the path walker.
-        let mut value = self.path[0]
+        let mut value = match self.path[0]
             .iter()
-            .find_map(|p| ctx.as_log().get(p))
-            .ok_or_else(|| format!("path .{} not found in event", self.path[0].first().unwrap()))?;
+            .find_map(|p| {
+                let (key, indices) = split_indices(p);
+                ctx.as_log()
+                    .get(key)
+                    .and_then(|value| index(value, &indices))
+            })
+            .ok_or(0usize)?
+        {
+            Lookup::Found(value) => value,
+            Lookup::OutOfBounds => return Ok(&NULL),
+        };
 
         // Walk remaining (if any) path segments. Our parse is already capable
         // of extracting individual path tokens from user input. For example,
@@ -68,25 +146,47 @@ impl Function for Path {
         // or when we refactor the value API we add options for providing
         // unescaped tokens.
         for (i, segments) in self.path.iter().enumerate().skip(1) {
-            value = segments
+            value = match segments
                 .iter()
-                .find_map(|p| get_value(value, PathIter::new(p)))
-                .ok_or_else(|| {
-                    format!(
-                        "path {} not found in event",
-                        self.path
-                            .iter()
-                            .take(i + 1)
-                            .fold("".to_string(), |acc, p| format!(
-                                "{}.{}",
-                                acc,
-                                p.first().unwrap()
-                            ),)
-                    )
-                })?;
+                .find_map(|p| {
+                    let (key, indices) = split_indices(p);
+                    get_value(value, PathIter::new(key)).and_then(|value| index(value, &indices))
+                })
+                .ok_or(i)?
+            {
+                Lookup::Found(value) => value,
+                Lookup::OutOfBounds => return Ok(&NULL),
+            };
         }
 
-        Ok(value.clone().into())
+        Ok(value)
+    }
+}
+
+impl Function for Path {
+    fn execute(&self, ctx: &Event) -> Result<QueryValue> {
+        self.lookup(ctx)
+            .map(|value| value.clone().into())
+            .map_err(|i| {
+                let path = self.path.iter().take(i + 1).fold("".to_string(), |acc, p| {
+                    format!("{}.{}", acc, p.first().unwrap())
+                });
+                format!("path {} not found in event", path)
+            })
+    }
+
+    fn resolve(&self, ctx: &Event) -> Result<Option<QueryValue>> {
+        Ok(self.get(ctx).map(|value| value.clone().into()))
+    }
+
+    fn describe(&self) -> String {
+        self.path
+            .iter()
+            .map(|segments| match segments.as_slice() {
+                [segment] => format!(".{}", segment),
+                segments => format!(".({})", segments.join(" | ")),
+            })
+            .collect()
     }
 }
 
@@ -186,4 +286,53 @@ mod tests {
             assert_eq!(query.execute(&input_event), exp.map(QueryValue::Value));
         }
     }
+
+    #[test]
+    fn check_path_index() {
+        let mut event = Event::from("");
+        event.as_mut_log().insert(
+            "foo",
+            Value::from(json!({"items": [{"name": "a"}, {"name": "b"}, [1, 2]]})),
+        );
+        event
+            .as_mut_log()
+            .insert("bar", Value::from(json!(["x", "y"])));
+
+        let cases = vec![
+            (Ok(Value::from("x")), Path::from(vec![vec!["bar[0]"]])),
+            (Ok(Value::from("y")), Path::from(vec![vec!["bar[-1]"]])),
+            (Ok(Value::from("x")), Path::from(vec![vec!["bar[-2]"]])),
+            (Ok(Value::Null), Path::from(vec![vec!["bar[2]"]])),
+            (Ok(Value::Null), Path::from(vec![vec!["bar[-3]"]])),
+            (
+                Ok(Value::from("a")),
+                Path::from(vec![vec!["foo"], vec!["items[0]"], vec!["name"]]),
+            ),
+            (
+                Ok(Value::from(2)),
+                Path::from(vec![vec!["foo"], vec!["items[-1][-1]"]]),
+            ),
+            (
+                Ok(Value::Null),
+                Path::from(vec![vec!["foo"], vec!["items[5]"], vec!["name"]]),
+            ),
+            (
+                Ok(Value::from("y")),
+                Path::from(vec![vec!["baz[0]", "bar[-1]"]]),
+            ),
+            (
+                Err("path .foo[0] not found in event".to_string()),
+                Path::from(vec![vec!["foo[0]"]]),
+            ),
+            (
+                Err("path .foo.items[0][0] not found in event".to_string()),
+                Path::from(vec![vec!["foo"], vec!["items[0][0]"]]),
+            ),
+        ];
+
+        for (exp, query) in cases {
+            assert_eq!(query.get(&event), exp.as_ref().ok());
+            assert_eq!(query.execute(&event), exp.map(QueryValue::Value));
+        }
+    }
 }
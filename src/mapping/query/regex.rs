@@ -51,6 +51,22 @@ impl Regex {
     }
 }
 
+impl std::fmt::Display for Regex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "/{}/", self.pattern)?;
+        if self.insensitive {
+            write!(f, "i")?;
+        }
+        if self.global {
+            write!(f, "g")?;
+        }
+        if self.multiline {
+            write!(f, "m")?;
+        }
+        Ok(())
+    }
+}
+
 /// regex::Regex doesn't implement PartialEq.
 impl PartialEq for Regex {
     fn eq(&self, other: &Self) -> bool {